@@ -0,0 +1,95 @@
+use bevy::{
+    ecs::system::SystemParam,
+    input::gamepad::{GamepadConnection, GamepadConnectionEvent},
+    prelude::*,
+};
+
+// Stick input below this is treated as resting
+pub const STICK_DEADZONE: f32 = 0.25;
+
+/*
+The pad the game listens to. The first one plugged in is used, and when it's
+unplugged the next connected one takes over
+*/
+#[derive(Resource, Default)]
+pub struct ActiveGamepad(pub Option<Gamepad>);
+
+#[derive(SystemParam)]
+pub struct GamepadInput<'w> {
+    active: Res<'w, ActiveGamepad>,
+    buttons: Res<'w, Input<GamepadButton>>,
+    axes: Res<'w, Axis<GamepadAxis>>,
+}
+
+impl GamepadInput<'_> {
+    pub fn is_connected(&self) -> bool {
+        self.active.0.is_some()
+    }
+
+    pub fn pressed(&self, button_type: GamepadButtonType) -> bool {
+        self.active.0.is_some_and(|gamepad| {
+            self.buttons
+                .pressed(GamepadButton::new(gamepad, button_type))
+        })
+    }
+
+    pub fn just_pressed(&self, button_type: GamepadButtonType) -> bool {
+        self.active.0.is_some_and(|gamepad| {
+            self.buttons
+                .just_pressed(GamepadButton::new(gamepad, button_type))
+        })
+    }
+
+    pub fn any_just_pressed(&self) -> Option<GamepadButtonType> {
+        let gamepad = self.active.0?;
+
+        self.buttons
+            .get_just_pressed()
+            .find(|button| button.gamepad == gamepad)
+            .map(|button| button.button_type)
+    }
+
+    // None while the stick rests in its deadzone
+    pub fn stick(&self, x: GamepadAxisType, y: GamepadAxisType) -> Option<Vec2> {
+        let gamepad = self.active.0?;
+        let stick = Vec2::new(
+            self.axes.get(GamepadAxis::new(gamepad, x))?,
+            self.axes.get(GamepadAxis::new(gamepad, y))?,
+        );
+
+        (stick.length() > STICK_DEADZONE).then_some(stick)
+    }
+
+    pub fn left_stick(&self) -> Option<Vec2> {
+        self.stick(GamepadAxisType::LeftStickX, GamepadAxisType::LeftStickY)
+    }
+
+    pub fn right_stick(&self) -> Option<Vec2> {
+        self.stick(GamepadAxisType::RightStickX, GamepadAxisType::RightStickY)
+    }
+}
+
+pub fn gamepad_connections(
+    mut active: ResMut<ActiveGamepad>,
+    mut connection_ev: EventReader<GamepadConnectionEvent>,
+    gamepads: Res<Gamepads>,
+) {
+    for ev in connection_ev.iter() {
+        match &ev.connection {
+            GamepadConnection::Connected(info) => {
+                info!("Gamepad {} connected: {}", ev.gamepad.id, info.name);
+
+                if active.0.is_none() {
+                    active.0 = Some(ev.gamepad);
+                }
+            }
+            GamepadConnection::Disconnected => {
+                info!("Gamepad {} disconnected", ev.gamepad.id);
+
+                if active.0 == Some(ev.gamepad) {
+                    active.0 = gamepads.iter().find(|gamepad| *gamepad != ev.gamepad);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,417 @@
+use std::collections::BTreeMap;
+
+use bevy::{ecs::system::SystemParam, prelude::*};
+use serde::{Deserialize, Serialize};
+
+use super::gamepad::GamepadInput;
+
+/*
+Everything the player can do with a button. Systems ask whether an action is
+pressed instead of looking at keys, so every action can be rebound
+*/
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Serialize, Deserialize)]
+pub enum InputAction {
+    MoveUp,
+    MoveDown,
+    MoveLeft,
+    MoveRight,
+    Shoot,
+    Dash,
+    SkillOne,
+    SkillTwo,
+    Pause,
+    Confirm,
+    Back,
+    MenuUp,
+    MenuDown,
+    MenuLeft,
+    MenuRight,
+    Reroll,
+    Banish,
+    Skip,
+    CheatLevelUp,
+    CheatDie,
+    CheatSpawnMenu,
+    CheatColliders,
+    CheatFastForward,
+}
+
+/*
+Actions read at the same time. Two actions of a shared context can't have the same
+binding, while moving and navigating menus can both use the arrows
+*/
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum InputContext {
+    Gameplay,
+    PauseMenu,
+    LevelUp,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub enum Binding {
+    Key(KeyCode),
+    Mouse(MouseButton),
+    Pad(GamepadButtonType),
+}
+
+impl Binding {
+    pub fn label(&self) -> String {
+        match self {
+            Binding::Key(key) => format!("{:?}", key),
+            Binding::Mouse(button) => format!("Mouse {:?}", button),
+            Binding::Pad(button) => format!("Pad {:?}", button),
+        }
+    }
+
+    pub fn is_gamepad(&self) -> bool {
+        matches!(self, Binding::Pad(_))
+    }
+}
+
+impl InputAction {
+    pub fn all() -> Vec<InputAction> {
+        vec![
+            InputAction::MoveUp,
+            InputAction::MoveDown,
+            InputAction::MoveLeft,
+            InputAction::MoveRight,
+            InputAction::Shoot,
+            InputAction::Dash,
+            InputAction::SkillOne,
+            InputAction::SkillTwo,
+            InputAction::Pause,
+            InputAction::Confirm,
+            InputAction::Back,
+            InputAction::MenuUp,
+            InputAction::MenuDown,
+            InputAction::MenuLeft,
+            InputAction::MenuRight,
+            InputAction::Reroll,
+            InputAction::Banish,
+            InputAction::Skip,
+            InputAction::CheatLevelUp,
+            InputAction::CheatDie,
+            InputAction::CheatSpawnMenu,
+            InputAction::CheatColliders,
+            InputAction::CheatFastForward,
+        ]
+    }
+
+    pub fn get_name(&self) -> String {
+        match self {
+            InputAction::MoveUp => "Move Up",
+            InputAction::MoveDown => "Move Down",
+            InputAction::MoveLeft => "Move Left",
+            InputAction::MoveRight => "Move Right",
+            InputAction::Shoot => "Shoot",
+            InputAction::Dash => "Dash",
+            InputAction::SkillOne => "Skill 1",
+            InputAction::SkillTwo => "Skill 2",
+            InputAction::Pause => "Pause",
+            InputAction::Confirm => "Confirm",
+            InputAction::Back => "Back",
+            InputAction::MenuUp => "Menu Up",
+            InputAction::MenuDown => "Menu Down",
+            InputAction::MenuLeft => "Menu Left",
+            InputAction::MenuRight => "Menu Right",
+            InputAction::Reroll => "Reroll",
+            InputAction::Banish => "Banish",
+            InputAction::Skip => "Skip",
+            InputAction::CheatLevelUp => "Cheat: Level Up",
+            InputAction::CheatDie => "Cheat: Die",
+            InputAction::CheatSpawnMenu => "Cheat: Spawn Menu",
+            InputAction::CheatColliders => "Cheat: Colliders",
+            InputAction::CheatFastForward => "Cheat: Fast Forward",
+        }
+        .to_string()
+    }
+
+    pub fn contexts(&self) -> &'static [InputContext] {
+        match self {
+            InputAction::Confirm | InputAction::MenuLeft | InputAction::MenuRight => {
+                &[InputContext::PauseMenu, InputContext::LevelUp]
+            }
+            InputAction::Back | InputAction::MenuUp | InputAction::MenuDown => {
+                &[InputContext::PauseMenu]
+            }
+            InputAction::Reroll | InputAction::Banish | InputAction::Skip => {
+                &[InputContext::LevelUp]
+            }
+            _ => &[InputContext::Gameplay],
+        }
+    }
+
+    pub fn shares_context(&self, other: &InputAction) -> bool {
+        self.contexts()
+            .iter()
+            .any(|context| other.contexts().contains(context))
+    }
+
+    pub fn default_bindings(&self) -> Vec<Binding> {
+        use Binding::*;
+
+        match self {
+            InputAction::MoveUp => vec![
+                Key(KeyCode::W),
+                Key(KeyCode::Up),
+                Pad(GamepadButtonType::DPadUp),
+            ],
+            InputAction::MoveDown => vec![
+                Key(KeyCode::S),
+                Key(KeyCode::Down),
+                Pad(GamepadButtonType::DPadDown),
+            ],
+            InputAction::MoveLeft => vec![
+                Key(KeyCode::A),
+                Key(KeyCode::Left),
+                Pad(GamepadButtonType::DPadLeft),
+            ],
+            InputAction::MoveRight => vec![
+                Key(KeyCode::D),
+                Key(KeyCode::Right),
+                Pad(GamepadButtonType::DPadRight),
+            ],
+            InputAction::Shoot => vec![
+                Mouse(MouseButton::Left),
+                Pad(GamepadButtonType::RightTrigger2),
+            ],
+            InputAction::Dash => vec![Key(KeyCode::Space), Pad(GamepadButtonType::LeftTrigger2)],
+            InputAction::SkillOne => vec![
+                Key(KeyCode::ShiftLeft),
+                Key(KeyCode::ShiftRight),
+                Pad(GamepadButtonType::LeftTrigger),
+            ],
+            InputAction::SkillTwo => vec![
+                Mouse(MouseButton::Right),
+                Pad(GamepadButtonType::RightTrigger),
+            ],
+            InputAction::Pause => vec![Key(KeyCode::Escape), Pad(GamepadButtonType::Start)],
+            InputAction::Confirm => vec![Key(KeyCode::Return), Pad(GamepadButtonType::South)],
+            InputAction::Back => vec![Key(KeyCode::Back), Pad(GamepadButtonType::East)],
+            InputAction::MenuUp => vec![
+                Key(KeyCode::Up),
+                Key(KeyCode::W),
+                Pad(GamepadButtonType::DPadUp),
+            ],
+            InputAction::MenuDown => vec![
+                Key(KeyCode::Down),
+                Key(KeyCode::S),
+                Pad(GamepadButtonType::DPadDown),
+            ],
+            InputAction::MenuLeft => vec![
+                Key(KeyCode::Left),
+                Key(KeyCode::A),
+                Pad(GamepadButtonType::DPadLeft),
+            ],
+            InputAction::MenuRight => vec![
+                Key(KeyCode::Right),
+                Key(KeyCode::D),
+                Pad(GamepadButtonType::DPadRight),
+            ],
+            InputAction::Reroll => vec![Key(KeyCode::R), Pad(GamepadButtonType::North)],
+            InputAction::Banish => vec![Key(KeyCode::B), Pad(GamepadButtonType::West)],
+            InputAction::Skip => vec![Key(KeyCode::X), Pad(GamepadButtonType::East)],
+            InputAction::CheatLevelUp => vec![Key(KeyCode::L)],
+            InputAction::CheatDie => vec![Key(KeyCode::K)],
+            InputAction::CheatSpawnMenu => vec![Key(KeyCode::M)],
+            InputAction::CheatColliders => vec![Key(KeyCode::J)],
+            InputAction::CheatFastForward => vec![Key(KeyCode::F)],
+        }
+    }
+}
+
+/*
+Bindings of every action, saved with the settings. Actions missing from the map
+use their defaults, so saves from before an action existed still work
+*/
+#[derive(Resource, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct InputMap(pub BTreeMap<InputAction, Vec<Binding>>);
+
+impl InputMap {
+    pub fn bindings(&self, action: InputAction) -> Vec<Binding> {
+        self.0
+            .get(&action)
+            .cloned()
+            .unwrap_or_else(|| action.default_bindings())
+    }
+
+    // Replaces the bindings of the same device, keyboard and mouse counting as one
+    pub fn rebind(&mut self, action: InputAction, binding: Binding) {
+        let mut bindings = self.bindings(action);
+        bindings.retain(|bound| bound.is_gamepad() != binding.is_gamepad());
+        bindings.push(binding);
+        self.0.insert(action, bindings);
+    }
+
+    pub fn reset(&mut self) {
+        self.0.clear();
+    }
+
+    // Other actions that would fire along with this one
+    pub fn conflicts(&self, action: InputAction) -> Vec<InputAction> {
+        let bindings = self.bindings(action);
+
+        InputAction::all()
+            .into_iter()
+            .filter(|other| *other != action && action.shares_context(other))
+            .filter(|other| {
+                self.bindings(*other)
+                    .iter()
+                    .any(|binding| bindings.contains(binding))
+            })
+            .collect()
+    }
+
+    // Shown in prompts like "[R] Reroll"
+    pub fn key_label(&self, action: InputAction) -> String {
+        self.bindings(action)
+            .iter()
+            .find(|binding| !binding.is_gamepad())
+            .map_or("-".to_string(), |binding| binding.label())
+    }
+
+    pub fn label(&self, action: InputAction) -> String {
+        self.bindings(action)
+            .iter()
+            .map(|binding| binding.label())
+            .collect::<Vec<String>>()
+            .join(", ")
+    }
+}
+
+#[derive(SystemParam)]
+pub struct ActionInput<'w> {
+    pub map: Res<'w, InputMap>,
+    keyboard: Res<'w, Input<KeyCode>>,
+    mouse: Res<'w, Input<MouseButton>>,
+    pub gamepad: GamepadInput<'w>,
+}
+
+impl ActionInput<'_> {
+    pub fn pressed(&self, action: InputAction) -> bool {
+        self.map
+            .bindings(action)
+            .iter()
+            .any(|binding| match binding {
+                Binding::Key(key) => self.keyboard.pressed(*key),
+                Binding::Mouse(button) => self.mouse.pressed(*button),
+                Binding::Pad(button) => self.gamepad.pressed(*button),
+            })
+    }
+
+    pub fn just_pressed(&self, action: InputAction) -> bool {
+        self.map
+            .bindings(action)
+            .iter()
+            .any(|binding| match binding {
+                Binding::Key(key) => self.keyboard.just_pressed(*key),
+                Binding::Mouse(button) => self.mouse.just_pressed(*button),
+                Binding::Pad(button) => self.gamepad.just_pressed(*button),
+            })
+    }
+
+    // -1 to 1, like a stick
+    pub fn axis(&self, negative: InputAction, positive: InputAction) -> f32 {
+        self.pressed(positive) as i32 as f32 - self.pressed(negative) as i32 as f32
+    }
+
+    // Whatever was pressed this frame, used when rebinding
+    pub fn any_just_pressed(&self) -> Option<Binding> {
+        self.keyboard
+            .get_just_pressed()
+            .next()
+            .map(|key| Binding::Key(*key))
+            .or_else(|| {
+                self.mouse
+                    .get_just_pressed()
+                    .next()
+                    .map(|button| Binding::Mouse(*button))
+            })
+            .or_else(|| self.gamepad.any_just_pressed().map(Binding::Pad))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_bindings_have_no_conflicts() {
+        let map = InputMap::default();
+
+        for action in InputAction::all() {
+            assert_eq!(map.conflicts(action), vec![], "{:?}", action);
+        }
+    }
+
+    #[test]
+    fn rebinding_a_key_keeps_the_gamepad_binding() {
+        let mut map = InputMap::default();
+        map.rebind(InputAction::MoveUp, Binding::Key(KeyCode::I));
+
+        assert_eq!(
+            map.bindings(InputAction::MoveUp),
+            vec![
+                Binding::Pad(GamepadButtonType::DPadUp),
+                Binding::Key(KeyCode::I)
+            ]
+        );
+    }
+
+    #[test]
+    fn keyboard_and_mouse_count_as_one_device() {
+        let mut map = InputMap::default();
+        map.rebind(InputAction::Shoot, Binding::Key(KeyCode::E));
+
+        assert_eq!(
+            map.bindings(InputAction::Shoot),
+            vec![
+                Binding::Pad(GamepadButtonType::RightTrigger2),
+                Binding::Key(KeyCode::E)
+            ]
+        );
+    }
+
+    #[test]
+    fn rebinding_a_gamepad_button_keeps_the_keys() {
+        let mut map = InputMap::default();
+        map.rebind(InputAction::Dash, Binding::Pad(GamepadButtonType::South));
+
+        assert_eq!(
+            map.bindings(InputAction::Dash),
+            vec![
+                Binding::Key(KeyCode::Space),
+                Binding::Pad(GamepadButtonType::South)
+            ]
+        );
+    }
+
+    #[test]
+    fn shared_bindings_conflict_within_a_context() {
+        let mut map = InputMap::default();
+        map.rebind(InputAction::Dash, Binding::Key(KeyCode::W));
+
+        assert_eq!(map.conflicts(InputAction::Dash), vec![InputAction::MoveUp]);
+        assert_eq!(map.conflicts(InputAction::MoveUp), vec![InputAction::Dash]);
+    }
+
+    #[test]
+    fn shared_bindings_are_fine_across_contexts() {
+        let mut map = InputMap::default();
+        map.rebind(InputAction::Reroll, Binding::Key(KeyCode::W));
+
+        assert_eq!(map.conflicts(InputAction::Reroll), vec![]);
+    }
+
+    #[test]
+    fn reset_goes_back_to_the_defaults() {
+        let mut map = InputMap::default();
+        map.rebind(InputAction::Pause, Binding::Key(KeyCode::P));
+        map.reset();
+
+        assert_eq!(
+            map.bindings(InputAction::Pause),
+            InputAction::Pause.default_bindings()
+        );
+    }
+}
@@ -1,61 +1,77 @@
-use bevy::math::Vec3Swizzles;
 use bevy::prelude::*;
+use bevy::window::PrimaryWindow;
 
-use crate::actions::game_control::{get_movement, GameControl};
-use crate::movement::pause::ActionPauseState;
+use crate::actions::gamepad::{gamepad_connections, ActiveGamepad};
+use crate::actions::input_map::{ActionInput, InputAction, InputMap};
+use crate::actions::targeting::{EnemyTargets, TargetingMode};
+use crate::actions::touch::{
+    detect_touch_controls, spawn_touch_controls, touch_pause_button, update_touch_sticks,
+    TouchControls,
+};
 use crate::player::Player;
+use crate::save::SaveData;
+use crate::ui::clickable::window_to_ui;
+use crate::util::clock::clock_is_running;
 use crate::GameState;
 
-mod game_control;
-
-pub const FOLLOW_EPSILON: f32 = 5.;
+pub mod gamepad;
+pub mod input_map;
+pub mod targeting;
+pub mod touch;
 
 pub struct ActionsPlugin;
 
-// This plugin listens for keyboard input and converts the input into Actions
+// This plugin listens for input mapped to InputActions and converts it into Actions
 // Actions can then be used as a resource in other systems to act on the player input.
 impl Plugin for ActionsPlugin {
     fn build(&self, app: &mut App) {
-        app.init_resource::<Actions>().add_systems(
-            Update,
-            set_movement_actions.run_if(in_state(GameState::Playing)),
-        );
+        app.init_resource::<Actions>()
+            .init_resource::<ActiveGamepad>()
+            .init_resource::<InputMap>()
+            .init_resource::<TouchControls>()
+            .add_systems(Update, gamepad_connections)
+            .add_systems(OnEnter(GameState::Playing), spawn_touch_controls)
+            .add_systems(
+                Update,
+                (
+                    detect_touch_controls,
+                    update_touch_sticks,
+                    touch_pause_button,
+                    set_movement_actions.run_if(clock_is_running),
+                    set_aim_actions.run_if(clock_is_running),
+                )
+                    .chain()
+                    .run_if(in_state(GameState::Playing)),
+            );
     }
 }
 
 #[derive(Default, Resource)]
 pub struct Actions {
     pub player_movement: Option<Vec2>,
+    // Last direction of the right stick or touch aiming, the cursor is used while this is None
+    pub stick_aim: Option<Vec2>,
+    // Where bullets go from the player once targeting is applied, normalized
+    pub aim_direction: Option<Vec2>,
+    pub shooting: bool,
 }
 
 pub fn set_movement_actions(
     mut actions: ResMut<Actions>,
-    keyboard_input: Res<Input<KeyCode>>,
-    touch_input: Res<Touches>,
-    player: Query<&Transform, With<Player>>,
-    camera: Query<(&Camera, &GlobalTransform), With<Camera2d>>,
-    pause_state: Res<ActionPauseState>,
+    input: ActionInput,
+    touch_controls: Res<TouchControls>,
 ) {
-    if pause_state.is_paused {
-        return;
-    }
-
     let mut player_movement = Vec2::new(
-        get_movement(GameControl::Right, &keyboard_input)
-            - get_movement(GameControl::Left, &keyboard_input),
-        get_movement(GameControl::Up, &keyboard_input)
-            - get_movement(GameControl::Down, &keyboard_input),
+        input.axis(InputAction::MoveLeft, InputAction::MoveRight),
+        input.axis(InputAction::MoveDown, InputAction::MoveUp),
     );
 
-    if let Some(touch_position) = touch_input.first_pressed_position() {
-        let (camera, camera_transform) = camera.single();
-        if let Some(touch_position) = camera.viewport_to_world_2d(camera_transform, touch_position)
-        {
-            let diff = touch_position - player.single().translation.xy();
-            if diff.length() > FOLLOW_EPSILON {
-                player_movement = diff.normalize();
-            }
-        }
+    if let Some(stick) = input.gamepad.left_stick() {
+        player_movement = stick;
+    }
+
+    if let Some(stick) = touch_controls.movement {
+        player_movement = stick;
     }
 
     if player_movement != Vec2::ZERO {
@@ -64,3 +80,63 @@ pub fn set_movement_actions(
         actions.player_movement = None;
     }
 }
+
+pub fn set_aim_actions(
+    mut actions: ResMut<Actions>,
+    input: ActionInput,
+    mut cursor_moved: EventReader<CursorMoved>,
+    q_windows: Query<&Window, With<PrimaryWindow>>,
+    q_player: Query<(&Player, &Transform)>,
+    targets: EnemyTargets,
+    touch_controls: Res<TouchControls>,
+    save: Res<SaveData>,
+) {
+    // Moving the mouse hands aiming back to the cursor
+    if cursor_moved.iter().len() > 0 {
+        actions.stick_aim = None;
+    }
+
+    // Letting go of the stick keeps the last aim
+    if let Some(stick) = input.gamepad.right_stick().or(touch_controls.aim) {
+        actions.stick_aim = Some(stick.normalize());
+    }
+
+    let window = q_windows.single();
+    let (player, transform) = q_player.single();
+    let position = transform.translation.truncate();
+    let bullet_speed = player.bullet_speed();
+
+    let aim = actions.stick_aim.or_else(|| {
+        window.cursor_position().and_then(|cursor_position| {
+            (window_to_ui(window, cursor_position) - position).try_normalize()
+        })
+    });
+    let targeting = save.settings.targeting;
+
+    actions.aim_direction = match targeting {
+        TargetingMode::Manual => aim,
+        // A cursor is precise enough on its own, only sticks get bent toward enemies
+        TargetingMode::AimAssist => match actions.stick_aim {
+            Some(stick) => Some(targets.assisted_aim(position, stick, bullet_speed)),
+            None => aim,
+        },
+        // Falls back to the player's aim when nothing is in range
+        _ => targets
+            .auto_target(targeting, position, bullet_speed)
+            .or(aim),
+    };
+    actions.shooting = input.pressed(InputAction::Shoot) || touch_controls.aim.is_some();
+
+    // Touch players shoot on their own while the aim stick is let go
+    if touch_controls.is_active && touch_controls.aim.is_none() && save.settings.auto_fire {
+        let mode = match targeting.is_auto() {
+            true => targeting,
+            false => TargetingMode::Nearest,
+        };
+
+        if let Some(target) = targets.auto_target(mode, position, bullet_speed) {
+            actions.aim_direction = Some(target);
+            actions.shooting = true;
+        }
+    }
+}
@@ -0,0 +1,151 @@
+use bevy::{ecs::system::SystemParam, prelude::*};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    combat::health::{Health, HealthType},
+    constants::DISTANCE_SCALING,
+    enemies::{enemy::Enemy, spatial::EnemyGrid},
+    movement::velocity::Velocity,
+};
+
+// Aim bends toward enemies within this angle of the aimed direction, in radians
+pub const AIM_ASSIST_ANGLE: f32 = 0.3;
+pub const AIM_ASSIST_RANGE: f32 = 300.;
+// Auto targeting only picks enemies this close
+pub const AUTO_TARGET_RANGE: f32 = 350.;
+// Each step refines the lead with the flight time to the last guess
+const LEAD_STEPS: usize = 2;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default, Serialize, Deserialize)]
+pub enum TargetingMode {
+    // Bullets go exactly where the player aims
+    Manual,
+    // Stick aim bends toward the closest enemy roughly in the aimed direction
+    #[default]
+    AimAssist,
+    // Aim is picked for the player
+    Nearest,
+    LowestHealth,
+    HighestThreat,
+}
+
+impl TargetingMode {
+    pub fn all() -> Vec<TargetingMode> {
+        vec![
+            TargetingMode::Manual,
+            TargetingMode::AimAssist,
+            TargetingMode::Nearest,
+            TargetingMode::LowestHealth,
+            TargetingMode::HighestThreat,
+        ]
+    }
+
+    pub fn get_name(&self) -> String {
+        match self {
+            TargetingMode::Manual => "Manual",
+            TargetingMode::AimAssist => "Aim Assist",
+            TargetingMode::Nearest => "Nearest",
+            TargetingMode::LowestHealth => "Lowest HP",
+            TargetingMode::HighestThreat => "Highest Threat",
+        }
+        .to_string()
+    }
+
+    // The mode `step` places further in the list, wrapping around
+    pub fn cycled(&self, step: i32) -> TargetingMode {
+        let modes = TargetingMode::all();
+        let index = modes.iter().position(|mode| mode == self).unwrap_or(0) as i32;
+
+        modes[(index + step).rem_euclid(modes.len() as i32) as usize]
+    }
+
+    pub fn is_auto(&self) -> bool {
+        !matches!(self, TargetingMode::Manual | TargetingMode::AimAssist)
+    }
+}
+
+// An enemy as seen from the shooter, aimed with lead
+struct Target {
+    aim: Vec2,
+    distance: f32,
+    health: HealthType,
+    threat: f32,
+}
+
+#[derive(SystemParam)]
+pub struct EnemyTargets<'w, 's> {
+    grid: Res<'w, EnemyGrid>,
+    q_enemies: Query<
+        'w,
+        's,
+        (
+            &'static Enemy,
+            &'static Transform,
+            &'static Velocity,
+            &'static Health,
+        ),
+    >,
+}
+
+impl EnemyTargets<'_, '_> {
+    fn within<'a>(
+        &'a self,
+        from: Vec2,
+        range: f32,
+        bullet_speed: f32,
+    ) -> impl Iterator<Item = Target> + 'a {
+        self.grid
+            .around(from, range)
+            .filter_map(|entity| self.q_enemies.get(entity).ok())
+            .filter(move |(_, transform, _, _)| {
+                transform.translation.truncate().distance(from) < range
+            })
+            .map(move |(enemy, transform, velocity, health)| {
+                let mut position = transform.translation.truncate();
+
+                for _ in 0..LEAD_STEPS {
+                    let flight_time = position.distance(from) / (bullet_speed * DISTANCE_SCALING);
+                    position = enemy.estimate_position(transform, velocity, flight_time);
+                }
+
+                Target {
+                    aim: position - from,
+                    distance: transform.translation.truncate().distance(from),
+                    health: health.value,
+                    threat: enemy.enemy_type.difficulty(),
+                }
+            })
+    }
+
+    /*
+    Bends the aim toward the closest enemy roughly in the aimed direction, sticks
+    being too coarse to pick off single imps
+    */
+    pub fn assisted_aim(&self, from: Vec2, aim: Vec2, bullet_speed: f32) -> Vec2 {
+        self.within(from, AIM_ASSIST_RANGE, bullet_speed)
+            .filter(|target| target.aim.angle_between(aim).abs() < AIM_ASSIST_ANGLE)
+            .min_by(|a, b| a.distance.total_cmp(&b.distance))
+            .map_or(aim, |target| target.aim.normalize_or_zero())
+    }
+
+    // Closer enemies win ties, None when nothing is in range
+    pub fn auto_target(&self, mode: TargetingMode, from: Vec2, bullet_speed: f32) -> Option<Vec2> {
+        let targets = self.within(from, AUTO_TARGET_RANGE, bullet_speed);
+
+        let target = match mode {
+            TargetingMode::LowestHealth => targets.min_by(|a, b| {
+                a.health
+                    .cmp(&b.health)
+                    .then(a.distance.total_cmp(&b.distance))
+            }),
+            TargetingMode::HighestThreat => targets.min_by(|a, b| {
+                b.threat
+                    .total_cmp(&a.threat)
+                    .then(a.distance.total_cmp(&b.distance))
+            }),
+            _ => targets.min_by(|a, b| a.distance.total_cmp(&b.distance)),
+        };
+
+        target.map(|target| target.aim.normalize_or_zero())
+    }
+}
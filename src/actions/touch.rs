@@ -0,0 +1,260 @@
+use bevy::{
+    input::touch::{TouchInput, TouchPhase},
+    prelude::*,
+    window::PrimaryWindow,
+};
+
+use crate::{
+    actions::gamepad::STICK_DEADZONE,
+    collision::collider::Collider,
+    constants::SortingLayers,
+    loading::FontAssets,
+    movement::pause::TogglePauseMenu,
+    palette::Palette,
+    ui::{
+        alignment::{AlignedBundle, Alignment},
+        clickable::{window_to_ui, Clickable, ClickedEvent},
+        element::{SizeConstraint, SizeVec2},
+        offset::Offset,
+    },
+    InGame,
+};
+
+// How far the knob travels from the center of its stick
+pub const STICK_RADIUS: f32 = 50.;
+// Touches starting this close to a stick grab it, thumbs rarely land on the center
+const STICK_GRAB_RADIUS: f32 = 110.;
+const STICK_BASE_SIZE: f32 = 120.;
+const STICK_KNOB_SIZE: f32 = 50.;
+
+/*
+The on-screen controls are only shown once the screen has been touched, so
+they never get in the way of a mouse or a gamepad
+*/
+#[derive(Resource, Default)]
+pub struct TouchControls {
+    pub is_active: bool,
+    // Where each stick is pushed, None while it's let go
+    pub movement: Option<Vec2>,
+    pub aim: Option<Vec2>,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum TouchStickKind {
+    Move,
+    Aim,
+}
+
+#[derive(Component)]
+pub struct TouchStick {
+    kind: TouchStickKind,
+    // The finger holding the stick
+    touch: Option<u64>,
+}
+
+#[derive(Component)]
+pub struct TouchStickKnob;
+
+#[derive(Component)]
+pub struct TouchPauseButton;
+
+#[derive(Component)]
+pub struct TouchControlComponent;
+
+pub fn spawn_touch_controls(
+    font_assets: Res<FontAssets>,
+    palette: Res<Palette>,
+    mut commands: Commands,
+) {
+    spawn_stick(
+        TouchStickKind::Move,
+        Alignment::BottomLeft,
+        Vec2::new(120., 120.),
+        &palette,
+        &mut commands,
+    );
+    spawn_stick(
+        TouchStickKind::Aim,
+        Alignment::BottomRight,
+        Vec2::new(-150., 150.),
+        &palette,
+        &mut commands,
+    );
+
+    commands
+        .spawn(AlignedBundle::new(Alignment::TopRight, default()))
+        .insert(Offset {
+            amount: SizeVec2 {
+                x: SizeConstraint::Const(-40.),
+                y: SizeConstraint::Const(-40.),
+            },
+        })
+        .insert(Text2dBundle {
+            text: Text::from_section(
+                "II",
+                TextStyle {
+                    font: font_assets.gothic_pxl.clone(),
+                    font_size: 40.,
+                    color: palette.white,
+                },
+            ),
+            transform: Transform::from_translation(Vec3 {
+                x: 1000.,
+                y: 1000.,
+                z: SortingLayers::UI.into(),
+            }),
+            visibility: Visibility::Hidden,
+            ..Default::default()
+        })
+        .insert(Clickable::new())
+        .insert(Collider::new_rect(Vec2 { x: 60., y: 60. }))
+        .insert(TouchPauseButton)
+        .insert(TouchControlComponent);
+}
+
+fn spawn_stick(
+    kind: TouchStickKind,
+    alignment: Alignment,
+    offset: Vec2,
+    palette: &Palette,
+    commands: &mut Commands,
+) {
+    commands
+        .spawn(AlignedBundle::new(alignment, default()))
+        .insert(Offset {
+            amount: SizeVec2 {
+                x: SizeConstraint::Const(offset.x),
+                y: SizeConstraint::Const(offset.y),
+            },
+        })
+        .insert(SpriteBundle {
+            sprite: Sprite {
+                color: palette.dark.with_a(0.5),
+                custom_size: Some(Vec2::splat(STICK_BASE_SIZE)),
+                ..Default::default()
+            },
+            transform: Transform::from_translation(Vec3 {
+                x: 1000.,
+                y: 1000.,
+                z: SortingLayers::UI.into(),
+            }),
+            visibility: Visibility::Hidden,
+            ..Default::default()
+        })
+        .insert(TouchStick { kind, touch: None })
+        .insert(TouchControlComponent)
+        .with_children(|parent| {
+            parent
+                .spawn(SpriteBundle {
+                    sprite: Sprite {
+                        color: palette.orange.with_a(0.7),
+                        custom_size: Some(Vec2::splat(STICK_KNOB_SIZE)),
+                        ..Default::default()
+                    },
+                    transform: Transform::from_xyz(0., 0., 0.1),
+                    ..Default::default()
+                })
+                .insert(TouchStickKnob);
+        });
+}
+
+pub fn detect_touch_controls(
+    mut touch_ev: EventReader<TouchInput>,
+    mut touch_controls: ResMut<TouchControls>,
+    mut q_controls: Query<&mut Visibility, With<TouchControlComponent>>,
+) {
+    let touched = touch_ev
+        .iter()
+        .any(|touch| touch.phase == TouchPhase::Started);
+
+    if !touched || touch_controls.is_active {
+        return;
+    }
+
+    touch_controls.is_active = true;
+
+    for mut visibility in q_controls.iter_mut() {
+        *visibility = Visibility::Inherited;
+    }
+}
+
+/*
+A stick follows the finger that grabbed it until that finger is lifted, so
+moving and aiming can be done at the same time
+*/
+pub fn update_touch_sticks(
+    mut q_sticks: Query<(&mut TouchStick, &GlobalTransform, &Children)>,
+    mut q_knobs: Query<&mut Transform, With<TouchStickKnob>>,
+    q_windows: Query<&Window, With<PrimaryWindow>>,
+    touches: Res<Touches>,
+    mut touch_controls: ResMut<TouchControls>,
+    in_game: Res<State<InGame>>,
+) {
+    let window = q_windows.single();
+
+    touch_controls.movement = None;
+    touch_controls.aim = None;
+
+    for (mut stick, transform, children) in q_sticks.iter_mut() {
+        let center = transform.translation().truncate();
+
+        if *in_game.get() != InGame::Running {
+            stick.touch = None;
+        } else if stick.touch.is_none() {
+            stick.touch = touches
+                .iter_just_pressed()
+                .find(|touch| {
+                    window_to_ui(window, touch.position()).distance(center) < STICK_GRAB_RADIUS
+                })
+                .map(|touch| touch.id());
+        }
+
+        let held = stick.touch.and_then(|id| touches.get_pressed(id));
+        if held.is_none() {
+            stick.touch = None;
+        }
+
+        let knob = held.map_or(Vec2::ZERO, |touch| {
+            (window_to_ui(window, touch.position()) - center).clamp_length_max(STICK_RADIUS)
+        });
+
+        for child in children.iter() {
+            if let Ok(mut knob_transform) = q_knobs.get_mut(*child) {
+                knob_transform.translation.x = knob.x;
+                knob_transform.translation.y = knob.y;
+            }
+        }
+
+        if knob.length() < STICK_DEADZONE * STICK_RADIUS {
+            continue;
+        }
+
+        let value = knob / STICK_RADIUS;
+        match stick.kind {
+            TouchStickKind::Move => touch_controls.movement = Some(value),
+            TouchStickKind::Aim => touch_controls.aim = Some(value),
+        }
+    }
+}
+
+pub fn touch_pause_button(
+    q_button: Query<Entity, With<TouchPauseButton>>,
+    mut click_ev: EventReader<ClickedEvent>,
+    mut toggle_ev: EventWriter<TogglePauseMenu>,
+    touch_controls: Res<TouchControls>,
+    in_game: Res<State<InGame>>,
+) {
+    let Ok(button) = q_button.get_single() else {
+        return;
+    };
+
+    let clicked = click_ev.iter().any(|clicked| clicked.entity == button);
+
+    // Level up choices have to be made before pausing
+    let can_toggle = matches!(in_game.get(), InGame::Running | InGame::Paused);
+    if !clicked || !touch_controls.is_active || !can_toggle {
+        return;
+    }
+
+    toggle_ev.send(TogglePauseMenu);
+}
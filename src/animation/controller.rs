@@ -2,6 +2,8 @@ use std::{hash::Hash, time::Duration};
 
 use bevy::prelude::*;
 
+use crate::util::clock::GameClock;
+
 use super::{AnimationStateChangeEvent, AnimationStateInfo, AnimationStateStorage};
 
 /*
@@ -69,7 +71,7 @@ pub fn update_animation_state<T: Send + std::marker::Sync + 'static + Clone + Co
 Does the animation on each sprite
 */
 pub fn update_animation_frames<T: Send + std::marker::Sync + 'static + Clone + Copy>(
-    time: Res<Time>,
+    clock: Res<GameClock>,
     mut query: Query<(
         &mut AnimationController<T>,
         &mut AnimationTimer,
@@ -77,7 +79,7 @@ pub fn update_animation_frames<T: Send + std::marker::Sync + 'static + Clone + C
     )>,
 ) {
     for (controller, mut timer, mut sprite) in &mut query {
-        timer.tick(time.delta());
+        timer.tick(clock.delta());
 
         if timer.just_finished() {
             sprite.index = if sprite.index
@@ -36,6 +36,23 @@ impl<TState: Clone + Copy> AnimationInfoBuilder<TState> {
         self
     }
 
+    // Plays frames already used by another state instead of taking new ones
+    pub fn add_reused_frames(
+        &mut self,
+        state: TState,
+        start_index: usize,
+        frame_count: usize,
+        duration: Duration,
+    ) -> &mut Self {
+        self.infos.push(AnimationStateInfoBlock::Reused(
+            state,
+            start_index,
+            frame_count,
+            duration,
+        ));
+        self
+    }
+
     pub fn build(&self) -> Vec<AnimationStateInfo<TState>> {
         build_animation_state_info(&self.infos)
     }
@@ -45,35 +62,41 @@ impl<TState: Clone + Copy> AnimationInfoBuilder<TState> {
 enum AnimationStateInfoBlock<TState: Clone + Copy> {
     Single(TState),
     Frames(TState, usize, Duration),
+    Reused(TState, usize, usize, Duration),
 }
 
 fn build_animation_state_info<TState: Clone + Copy>(
     blocks: &Vec<AnimationStateInfoBlock<TState>>,
 ) -> Vec<AnimationStateInfo<TState>> {
-    let mut infos: Vec<AnimationStateInfo<TState>> = blocks
+    // fix indices, reused frames don't take up new ones
+    let mut index = 0;
+    let mut next_frames = |count: usize| {
+        let start_index = index;
+        index += count;
+        start_index
+    };
+
+    blocks
         .iter()
         .map(|block| match block {
             AnimationStateInfoBlock::Single(state) => AnimationStateInfo {
                 id: *state,
-                start_index: 0,
+                start_index: next_frames(1),
                 frame_count: 1,
                 frame_duration: Duration::ZERO,
             },
             AnimationStateInfoBlock::Frames(state, count, duration) => AnimationStateInfo {
                 id: *state,
-                start_index: 0,
+                start_index: next_frames(*count),
+                frame_count: *count,
+                frame_duration: *duration,
+            },
+            AnimationStateInfoBlock::Reused(state, start, count, duration) => AnimationStateInfo {
+                id: *state,
+                start_index: *start,
                 frame_count: *count,
                 frame_duration: *duration,
             },
         })
-        .collect();
-
-    // fix indices
-    let mut index = 0;
-    for info in infos.iter_mut() {
-        info.start_index = index;
-        index += info.frame_count;
-    }
-
-    infos
+        .collect()
 }
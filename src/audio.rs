@@ -11,7 +11,7 @@ pub struct InternalAudioPlugin;
 impl Plugin for InternalAudioPlugin {
     fn build(&self, app: &mut App) {
         app.add_plugins(AudioPlugin)
-            .add_systems(OnEnter(GameState::Menu), start_music)
+            .add_systems(OnExit(GameState::Loading), start_music)
             .add_systems(OnEnter(GameState::Playing), start_audio)
             .add_audio_channel::<FX>()
             .add_audio_channel::<Music>()
@@ -0,0 +1,114 @@
+use bevy::prelude::*;
+
+use crate::{
+    save::SaveData,
+    util::clock::{GameClock, TimeLayer},
+};
+
+// How far the camera moves and turns at full trauma
+const MAX_SHAKE_OFFSET: f32 = 12.;
+const MAX_SHAKE_ANGLE: f32 = 0.05;
+// Trauma lost per second, so a full shake settles in under a second
+const TRAUMA_DECAY: f32 = 1.5;
+// Zoom punches snap back quicker than shakes
+const ZOOM_DECAY: f32 = 4.;
+// How fast the gameplay clock runs during a hit-stop
+const HIT_STOP_SPEED: f32 = 0.05;
+
+// Adds trauma, the shake grows with its square so small hits stay subtle
+#[derive(Event)]
+pub struct ScreenShakeEvent {
+    pub trauma: f32,
+}
+
+// Nearly freezes the game for `duration` real seconds
+#[derive(Event)]
+pub struct HitStopEvent {
+    pub duration: f32,
+}
+
+// Zooms in by `amount` of the view and eases back out
+#[derive(Event)]
+pub struct ZoomPunchEvent {
+    pub amount: f32,
+}
+
+#[derive(Resource, Default)]
+pub struct CameraEffects {
+    trauma: f32,
+    zoom: f32,
+    hit_stop: f32,
+}
+
+pub struct CameraEffectsPlugin;
+
+impl Plugin for CameraEffectsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<CameraEffects>()
+            .add_event::<ScreenShakeEvent>()
+            .add_event::<HitStopEvent>()
+            .add_event::<ZoomPunchEvent>()
+            .add_systems(
+                Update,
+                (read_camera_events, apply_camera_effects, apply_hit_stop).chain(),
+            );
+    }
+}
+
+fn read_camera_events(
+    mut effects: ResMut<CameraEffects>,
+    mut shake_ev: EventReader<ScreenShakeEvent>,
+    mut hit_stop_ev: EventReader<HitStopEvent>,
+    mut zoom_ev: EventReader<ZoomPunchEvent>,
+) {
+    for shake in shake_ev.iter() {
+        effects.trauma = (effects.trauma + shake.trauma).min(1.);
+    }
+
+    for hit_stop in hit_stop_ev.iter() {
+        effects.hit_stop = effects.hit_stop.max(hit_stop.duration);
+    }
+
+    for zoom in zoom_ev.iter() {
+        effects.zoom = effects.zoom.max(zoom.amount);
+    }
+}
+
+// The camera otherwise sits still at the origin, so the effects are its whole offset
+fn apply_camera_effects(
+    mut q_camera: Query<(&mut Transform, &mut OrthographicProjection), With<Camera2d>>,
+    mut effects: ResMut<CameraEffects>,
+    save: Res<SaveData>,
+    time: Res<Time>,
+) {
+    let delta = time.raw_delta_seconds();
+    effects.trauma = (effects.trauma - TRAUMA_DECAY * delta).max(0.);
+    effects.zoom = (effects.zoom - effects.zoom * ZOOM_DECAY * delta).max(0.);
+
+    let shake = match save.settings.screen_shake {
+        true => effects.trauma * effects.trauma,
+        false => 0.,
+    };
+
+    // Overlapping sines instead of random jumps, so the shake moves smoothly
+    let t = time.raw_elapsed_seconds();
+    let noise = |seed: f32| ((t * 31. + seed).sin() + (t * 57. + seed * 2.).sin() * 0.5) / 1.5;
+
+    for (mut transform, mut projection) in q_camera.iter_mut() {
+        transform.translation.x = MAX_SHAKE_OFFSET * shake * noise(0.);
+        transform.translation.y = MAX_SHAKE_OFFSET * shake * noise(10.);
+        transform.rotation = Quat::from_rotation_z(MAX_SHAKE_ANGLE * shake * noise(20.));
+        projection.scale = 1. - effects.zoom;
+    }
+}
+
+// Its own layer of the clock, so a hit-stop during a time slow slows it down further
+fn apply_hit_stop(mut effects: ResMut<CameraEffects>, mut clock: ResMut<GameClock>) {
+    match effects.hit_stop > 0. {
+        true => {
+            effects.hit_stop -= clock.real_delta_seconds();
+            clock.set_layer(TimeLayer::HitStop, HIT_STOP_SPEED);
+        }
+        false => clock.clear_layer(TimeLayer::HitStop),
+    }
+}
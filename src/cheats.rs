@@ -1,13 +1,18 @@
 use bevy::prelude::*;
 
 use crate::{
+    actions::input_map::{ActionInput, InputAction},
     collision::collider_debug::ColliderDebugSpriteState,
     combat::health::DeathEvent,
     experience::experience::{Experience, LevelUpEvent},
     player::Player,
+    util::clock::{GameClock, TimeLayer},
     GameState, enemies::spawn_menu::SpawnMenuState,
 };
 
+// How much faster the game runs while fast forwarding
+const FAST_FORWARD_SPEED: f32 = 4.;
+
 pub struct CheatsPlugin;
 
 impl Plugin for CheatsPlugin {
@@ -18,39 +23,47 @@ impl Plugin for CheatsPlugin {
 
 fn cheats(
     q_player: Query<(Entity, &Player, &Experience)>,
-    keyboard_input: Res<Input<KeyCode>>,
+    input: ActionInput,
     mut levelup: EventWriter<LevelUpEvent>,
     mut death_ev: EventWriter<DeathEvent>,
     state: Res<State<ColliderDebugSpriteState>>,
     mut next_state: ResMut<NextState<ColliderDebugSpriteState>>,
     spawn_menu_state : Res<State<SpawnMenuState>>,
     mut next_spawn_menu_state : ResMut<NextState<SpawnMenuState>>,
+    mut clock: ResMut<GameClock>,
 ) {
     let (player_entity, _player, xp) = q_player.single();
 
-    if keyboard_input.just_pressed(KeyCode::L) {
+    if input.just_pressed(InputAction::CheatLevelUp) {
         levelup.send(LevelUpEvent {
             new_level: xp.level + 1,
         });
     }
 
-    if keyboard_input.just_pressed(KeyCode::K) {
+    if input.just_pressed(InputAction::CheatDie) {
         death_ev.send(DeathEvent {
             entity: player_entity,
         });
     }
 
-    if keyboard_input.just_pressed(KeyCode::M) {
+    if input.just_pressed(InputAction::CheatSpawnMenu) {
         next_spawn_menu_state.set(match spawn_menu_state.get() {
             SpawnMenuState::Off => SpawnMenuState::On,
             SpawnMenuState::On => SpawnMenuState::Off,
         })
     }
 
-    if keyboard_input.just_pressed(KeyCode::J) {
+    if input.just_pressed(InputAction::CheatColliders) {
         match state.get() {
             ColliderDebugSpriteState::Off => next_state.set(ColliderDebugSpriteState::On),
             ColliderDebugSpriteState::On => next_state.set(ColliderDebugSpriteState::Off),
         };
     }
+
+    if input.just_pressed(InputAction::CheatFastForward) {
+        match clock.has_layer(TimeLayer::FastForward) {
+            true => clock.clear_layer(TimeLayer::FastForward),
+            false => clock.set_layer(TimeLayer::FastForward, FAST_FORWARD_SPEED),
+        }
+    }
 }
@@ -25,6 +25,17 @@ impl Collider {
     pub fn shape(&self) -> ColliderShape {
         self.shape
     }
+
+    pub fn scaled(&self, factor: f32) -> Collider {
+        Collider {
+            shape: match self.shape {
+                ColliderShape::Rect(size) => ColliderShape::Rect(size * factor),
+                ColliderShape::Circle(radius) => ColliderShape::Circle(radius * factor),
+            },
+            spatial_coord: self.spatial_coord,
+            initialized: self.initialized,
+        }
+    }
 }
 
 #[derive(Clone, Copy, Debug)]
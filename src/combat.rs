@@ -1,13 +1,19 @@
 use bevy::prelude::*;
 
-use crate::{animation::AppAnimationSetup, GameState};
+use crate::{animation::AppAnimationSetup, GameState, InGame};
 
 use self::{
     fire::{fire_update, FireAnimation, extinguish_errant_fire},
     health::{check_death, DeathEvent, TookDamageEvent},
     healthbar::update_healthbars,
     knockback::knockback_update,
+    lifetime::{cull_out_of_range, update_lifetimes},
+    pool::{forget_despawned, recycle_released, reset_pool, EntityPool},
     projectile::{projectile_collision_check, ProjectileHitEvent},
+    projectile_behavior::{
+        boomerang_update, bounce_off_edges, deflect_projectiles, homing_update,
+        split_projectiles, ProjectileDeflectEvent,
+    },
     z_sort::update_z_sort, deathrattle::DeathrattlePlugin,
 };
 
@@ -15,10 +21,13 @@ pub mod fire;
 pub mod health;
 pub mod healthbar;
 pub mod knockback;
+pub mod lifetime;
+pub mod pool;
 pub mod projectile;
+pub mod projectile_behavior;
 pub mod teams;
 pub mod z_sort;
-mod deathrattle;
+pub mod deathrattle;
 
 pub struct CombatPlugin;
 
@@ -34,6 +43,13 @@ impl Plugin for CombatPlugin {
                 extinguish_errant_fire,
                 update_z_sort,
                 knockback_update.after(projectile_collision_check),
+                homing_update,
+                bounce_off_edges,
+                boomerang_update,
+                deflect_projectiles.after(projectile_collision_check),
+                split_projectiles.after(projectile_collision_check),
+                update_lifetimes,
+                cull_out_of_range,
             )
                 .run_if(in_state(GameState::Playing)),
         )
@@ -41,6 +57,11 @@ impl Plugin for CombatPlugin {
         .add_event::<DeathEvent>()
         .add_event::<TookDamageEvent>()
         .add_event::<ProjectileHitEvent>()
+        .add_event::<ProjectileDeflectEvent>()
+        .init_resource::<EntityPool>()
+        .add_systems(Update, forget_despawned)
+        .add_systems(Last, recycle_released)
+        .add_systems(OnExit(InGame::GameOver), reset_pool)
         .add_plugins(DeathrattlePlugin);
     }
 }
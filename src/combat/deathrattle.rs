@@ -4,9 +4,9 @@ use bevy::prelude::*;
 use bevy_kira_audio::AudioControl;
 use rand::Rng;
 
-use crate::{enemies::enemy::EnemyDeathEvent, animation::{Animation, info::{AnimationStateInfo, AnimationInfoBuilder}, AppAnimationSetup, make_animation_bundle, AnimationStateStorage}, player::{Player, ability::Ability}, loading::{TextureAssets, AudioAssets}, movement::pause::ActionPauseState, GameState, collision::collider::Collider, util::rng::{RNG, GlobalSeed}, audio::FXChannel};
+use crate::{camera::{ScreenShakeEvent, ZoomPunchEvent}, enemies::enemy::EnemyDeathEvent, animation::{Animation, info::{AnimationStateInfo, AnimationInfoBuilder}, AppAnimationSetup, make_animation_bundle, AnimationStateStorage}, player::{Player, ability::Ability}, loading::{TextureAssets, AudioAssets}, GameState, InGame, collision::collider::Collider, util::{clock::clock_is_running, rng::{RNG, GlobalSeed}}, audio::FXChannel};
 
-use super::{projectile::{Projectile, DamageTarget, PiercingMode}, teams::Team};
+use super::{health::{DamageSource, HealthType}, lifetime::Lifetime, pool::{EntityPool, PoolKind}, projectile::{Projectile, DamageTarget, PiercingMode}, teams::Team};
 
 pub struct DeathrattlePlugin;
 
@@ -14,8 +14,9 @@ impl Plugin for DeathrattlePlugin {
     fn build(&self, app: &mut App) {
         app
             .add_animation::<ExplosionAnimation>()
-            .add_systems(Update, deathrattle_update.run_if(in_state(GameState::Playing)))
-            .add_systems(OnEnter(GameState::Playing), init_rng);
+            .add_systems(Update, deathrattle_update.run_if(in_state(GameState::Playing).and_then(clock_is_running)))
+            .add_systems(OnEnter(GameState::Playing), init_rng)
+            .add_systems(OnExit(InGame::GameOver), init_rng);
     }
 }
 
@@ -42,21 +43,59 @@ pub const CHANCE_OF_EXPLOSION : f32 = 0.20;
 #[derive(Resource)]
 struct DeathrattleRNG(RNG);
 
-#[derive(Component)]
-struct Explosion(pub Timer);
+pub fn explosion_atlas(
+    textures : &Res<TextureAssets>,
+    texture_atlases : &mut ResMut<Assets<TextureAtlas>>,
+) -> Handle<TextureAtlas> {
+    texture_atlases.add(TextureAtlas::from_grid(
+        textures.explosion.clone(),
+        Vec2 { x: 64., y: 64. },
+        5,
+        1,
+        None,
+        None,
+    ))
+}
+
+pub fn spawn_explosion(
+    location : Vec3,
+    dmg : HealthType,
+    damage_target : DamageTarget,
+    animations : &Res<AnimationStateStorage<ExplosionAnimation>>,
+    texture_atlas_handle : Handle<TextureAtlas>,
+    pool : &mut EntityPool,
+    commands : &mut Commands,
+) {
+    pool.spawn(PoolKind::Explosion, commands).insert(Projectile{
+        damage_target,
+        dmg,
+        piercing_mode: PiercingMode::All,
+        entities_hit: vec![],
+        is_alive: true,
+        source: DamageSource::Explosion,
+    }).insert(make_animation_bundle(
+        ExplosionAnimation, 
+        animations, 
+        texture_atlas_handle, 
+        location, 
+        1.0
+    ))
+    .insert(Collider::new_circle(50.))
+    .insert(Lifetime::from_seconds(5. * 1. / 16.));
+}
 
 fn deathrattle_update(
     q_player : Query<&Player>,
-    mut q_explosion : Query<(Entity, &mut Collider, &mut Explosion), Without<Player>>,
     mut death_ev : EventReader<EnemyDeathEvent>,
     animations : Res<AnimationStateStorage<ExplosionAnimation>>,
     textures: Res<TextureAssets>,
     mut texture_atlases: ResMut<Assets<TextureAtlas>>,
-    pause : Res<ActionPauseState>,
-    time : Res<Time>,
     audio : Res<AudioAssets>,
     fx : Res<FXChannel>,
     mut rng : ResMut<DeathrattleRNG>,
+    mut pool : ResMut<EntityPool>,
+    mut shake_ev : EventWriter<ScreenShakeEvent>,
+    mut zoom_ev : EventWriter<ZoomPunchEvent>,
     mut commands : Commands,
 ) {
     let player = q_player.single();
@@ -65,48 +104,29 @@ fn deathrattle_update(
         return;
     }
 
-    if pause.is_paused {
-        return;
-    }
-
-    let texture_atlas = TextureAtlas::from_grid(
-        textures.explosion.clone(),
-        Vec2 { x: 64., y: 64. },
-        5,
-        1,
-        None,
-        None,
-    );
-    let texture_atlas_handle = texture_atlases.add(texture_atlas);
+    let texture_atlas_handle = explosion_atlas(&textures, &mut texture_atlases);
 
     for death in death_ev.iter() {
-        if rng.0.0.gen_range(0. .. 1.) > CHANCE_OF_EXPLOSION {
+        let roll = rng.0.0.gen_range(0. .. 1.);
+
+        // Inferno makes every burning enemy go off
+        let always_explodes = death.burning && player.abilities.contains(&Ability::Inferno);
+
+        if !always_explodes && roll > CHANCE_OF_EXPLOSION {
             continue;
         }
 
         fx.play(audio.explosion.clone());
-        commands.spawn(Projectile{
-            damage_target: DamageTarget::Team(Team::Enemy),
-            dmg: player.damage() * 3,
-            piercing_mode: PiercingMode::All,
-            entities_hit: vec![],
-            is_alive: true,
-        }).insert(make_animation_bundle(
-            ExplosionAnimation, 
-            &animations, 
-            texture_atlas_handle.clone(), 
-            death.location, 
-            1.0
-        ))
-        .insert(Collider::new_circle(50.))
-        .insert(Explosion(Timer::from_seconds(5. * 1. / 16., TimerMode::Once)));
-    }
-
-    for (entity, mut collider, mut timer) in q_explosion.iter_mut() {
-        timer.0.tick(time.delta());
-
-        if timer.0.finished() {
-            commands.entity(entity).despawn_recursive();
-        }
+        shake_ev.send(ScreenShakeEvent { trauma: 0.3 });
+        zoom_ev.send(ZoomPunchEvent { amount: 0.03 });
+        spawn_explosion(
+            death.location,
+            player.damage() * 3,
+            DamageTarget::Team(Team::Enemy),
+            &animations,
+            texture_atlas_handle.clone(),
+            &mut pool,
+            &mut commands,
+        );
     }
 }
\ No newline at end of file
@@ -8,12 +8,12 @@ use crate::{
     },
     enemies::enemy::Enemy,
     loading::TextureAssets,
-    movement::pause::ActionPauseState,
     player::{ability::Ability, Player},
+    util::clock::GameClock,
 };
 
 use super::{
-    health::{Health, HealthType, TookDamageEvent},
+    health::{DamageSource, Health, HealthType, TookDamageEvent},
     projectile::ProjectileHitEvent,
 };
 
@@ -53,19 +53,14 @@ pub fn fire_update(
     mut projectile_hit: EventReader<ProjectileHitEvent>,
     animations: Res<AnimationStateStorage<FireAnimation>>,
     textures: Res<TextureAssets>,
-    pause: Res<ActionPauseState>,
     mut texture_atlases: ResMut<Assets<TextureAtlas>>,
-    time: Res<Time>,
+    clock: Res<GameClock>,
     mut commands: Commands,
 ) {
-    if pause.is_paused {
-        return;
-    }
-
     let player = q_player.single();
 
     for (mut fire, parent) in q_fire.iter_mut() {
-        fire.timer.tick(time.delta());
+        fire.timer.tick(clock.delta());
 
         if !fire.timer.just_finished() {
             continue;
@@ -78,9 +73,9 @@ pub fn fire_update(
                 .iter()
                 .filter(|ability| ability == &&Ability::HotterFire)
                 .count() as HealthType;
-            let damage = base_damage + hotter * 2;
+            let damage = (base_damage + hotter * 2) * player.character.fire_mult();
 
-            health.take_damage(parent.get(), &mut took_damage_ev, damage)
+            health.take_damage(parent.get(), &mut took_damage_ev, damage, DamageSource::Fire)
         }
     }
 
@@ -9,10 +9,50 @@ pub struct Health {
     pub is_invincible: bool,
 }
 
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum DamageSource {
+    Bullet,
+    // Pieces of split bullets, kept apart so they don't count as shots
+    Shrapnel,
+    Fire,
+    Explosion,
+    Thorns,
+    Shockwave,
+    Enemy,
+}
+
+impl DamageSource {
+    pub fn all() -> Vec<DamageSource> {
+        vec![
+            DamageSource::Bullet,
+            DamageSource::Shrapnel,
+            DamageSource::Fire,
+            DamageSource::Explosion,
+            DamageSource::Thorns,
+            DamageSource::Shockwave,
+            DamageSource::Enemy,
+        ]
+    }
+
+    pub fn name(&self) -> String {
+        match self {
+            DamageSource::Bullet => "Bullets",
+            DamageSource::Shrapnel => "Shrapnel",
+            DamageSource::Fire => "Fire",
+            DamageSource::Explosion => "Explosions",
+            DamageSource::Thorns => "Thorns",
+            DamageSource::Shockwave => "Shockwaves",
+            DamageSource::Enemy => "Enemies",
+        }
+        .to_string()
+    }
+}
+
 #[derive(Event)]
 pub struct TookDamageEvent {
     pub entity: Entity,
     pub amount: HealthType,
+    pub source: DamageSource,
 }
 
 impl Health {
@@ -29,6 +69,7 @@ impl Health {
         my_entity: Entity,
         took_damage_ev: &mut EventWriter<TookDamageEvent>,
         dmg: HealthType,
+        source: DamageSource,
     ) {
         if self.is_invincible {
             return;
@@ -43,6 +84,7 @@ impl Health {
         took_damage_ev.send(TookDamageEvent {
             entity: my_entity,
             amount: dmg,
+            source,
         });
     }
 
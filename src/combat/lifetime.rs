@@ -0,0 +1,65 @@
+use bevy::prelude::*;
+
+use crate::util::clock::GameClock;
+
+use super::pool::EntityPool;
+
+// Anything flying further than this from where it started is off the screen for good
+pub const PROJECTILE_RANGE: f32 = 1000.;
+
+// Removes the entity once the timer runs out, handing it back to the pool if it came from one
+#[derive(Component)]
+pub struct Lifetime(pub Timer);
+
+impl Lifetime {
+    pub fn from_seconds(seconds: f32) -> Lifetime {
+        Lifetime(Timer::from_seconds(seconds, TimerMode::Once))
+    }
+
+    pub fn percent_left(&self) -> f32 {
+        self.0.percent_left()
+    }
+}
+
+// Removes the entity once it gets `range` away from `origin`
+#[derive(Component)]
+pub struct MaxRange {
+    pub origin: Vec2,
+    pub range: f32,
+}
+
+impl MaxRange {
+    pub fn new(origin: Vec3, range: f32) -> MaxRange {
+        MaxRange {
+            origin: origin.truncate(),
+            range,
+        }
+    }
+}
+
+pub fn update_lifetimes(
+    mut q_lifetimes: Query<(Entity, &mut Lifetime)>,
+    mut pool: ResMut<EntityPool>,
+    clock: Res<GameClock>,
+    mut commands: Commands,
+) {
+    for (entity, mut lifetime) in q_lifetimes.iter_mut() {
+        lifetime.0.tick(clock.delta());
+
+        if lifetime.0.just_finished() {
+            pool.release(entity, &mut commands);
+        }
+    }
+}
+
+pub fn cull_out_of_range(
+    q_ranged: Query<(Entity, &MaxRange, &Transform)>,
+    mut pool: ResMut<EntityPool>,
+    mut commands: Commands,
+) {
+    for (entity, max_range, transform) in q_ranged.iter() {
+        if transform.translation.truncate().distance(max_range.origin) > max_range.range {
+            pool.release(entity, &mut commands);
+        }
+    }
+}
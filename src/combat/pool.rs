@@ -0,0 +1,240 @@
+use std::collections::HashMap;
+
+use bevy::{ecs::system::EntityCommands, prelude::*};
+
+use crate::{
+    collision::collider::Collider,
+    enemies::patterns::{PatternHoming, WaveMotion},
+    movement::velocity::Velocity,
+    particles::emitter::{Particle, ParticleEmitter},
+    util::despawn_on_exit::DespawnOnExit,
+};
+
+use super::{
+    knockback::Knockback,
+    lifetime::{Lifetime, MaxRange},
+    projectile::Projectile,
+    projectile_behavior::{Boomerang, Bounce, Homing, Ricochet, Split},
+};
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum PoolKind {
+    Bullet,
+    EnemyBullet,
+    Explosion,
+    Afterimage,
+    Particle,
+    Emitter,
+}
+
+// Marks entities owned by the pool, so despawning them elsewhere is noticed
+#[derive(Component)]
+pub struct Pooled;
+
+// Everything that would make a parked entity still act in the world
+type Recycled = (
+    Projectile,
+    Velocity,
+    Collider,
+    Knockback,
+    Lifetime,
+    MaxRange,
+    Homing,
+    Bounce,
+    Ricochet,
+    Boomerang,
+    Split,
+    WaveMotion,
+    PatternHoming,
+    Particle,
+    ParticleEmitter,
+);
+
+/*
+Bullets and effects come and go by the hundreds, so instead of despawning them
+they get hidden and parked here until the next one of the same kind is spawned
+*/
+#[derive(Resource, Default)]
+pub struct EntityPool {
+    free: HashMap<PoolKind, Vec<Entity>>,
+    // Released this frame, events can still name them until the frame is over
+    released: Vec<Entity>,
+    slots: HashMap<Entity, Slot>,
+}
+
+struct Slot {
+    kind: PoolKind,
+    is_free: bool,
+}
+
+impl EntityPool {
+    // A parked entity of that kind if there is one, a new one otherwise
+    pub fn spawn<'w, 's, 'a>(
+        &mut self,
+        kind: PoolKind,
+        commands: &'a mut Commands<'w, 's>,
+    ) -> EntityCommands<'w, 's, 'a> {
+        let entity = match self.free.entry(kind).or_default().pop() {
+            Some(entity) => entity,
+            None => commands.spawn((Pooled, DespawnOnExit::run())).id(),
+        };
+
+        self.slots.insert(
+            entity,
+            Slot {
+                kind,
+                is_free: false,
+            },
+        );
+        commands.entity(entity)
+    }
+
+    // Parks pooled entities and despawns everything else
+    pub fn release(&mut self, entity: Entity, commands: &mut Commands) {
+        match self.slots.get_mut(&entity) {
+            // Already parked, releasing twice would hand it out twice
+            Some(Slot { is_free: true, .. }) => (),
+            Some(slot) => {
+                slot.is_free = true;
+                self.released.push(entity);
+                commands
+                    .entity(entity)
+                    .remove::<Recycled>()
+                    .insert(Visibility::Hidden);
+            }
+            None => commands.entity(entity).despawn_recursive(),
+        }
+    }
+}
+
+// Released entities can only be handed out again from the next frame on
+pub fn recycle_released(mut pool: ResMut<EntityPool>) {
+    let pool = &mut *pool;
+
+    for entity in pool.released.drain(..) {
+        if let Some(slot) = pool.slots.get(&entity) {
+            pool.free.entry(slot.kind).or_default().push(entity);
+        }
+    }
+}
+
+// Pooled entities go with the run, so nothing parked can be handed out after a restart
+pub fn reset_pool(mut pool: ResMut<EntityPool>) {
+    *pool = EntityPool::default();
+}
+
+// Pooled entities despawned by something else can't be handed out again
+pub fn forget_despawned(mut pool: ResMut<EntityPool>, mut removed: RemovedComponents<Pooled>) {
+    for entity in removed.iter() {
+        if let Some(slot) = pool.slots.remove(&entity) {
+            pool.free
+                .entry(slot.kind)
+                .or_default()
+                .retain(|parked| *parked != entity);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::VecDeque;
+
+    use bevy::utils::HashSet;
+
+    use super::*;
+
+    // 1000 bullets on screen at once, each one living for a third of a second
+    const BULLETS_PER_FRAME: usize = 50;
+    const LIFETIME_FRAMES: usize = 20;
+    const FRAMES: usize = 200;
+
+    #[derive(Resource)]
+    struct Stress {
+        use_pool: bool,
+        alive: VecDeque<Vec<Entity>>,
+        // Every entity a bullet was ever given, a fresh one per bullet means an allocation
+        handed_out: HashSet<Entity>,
+    }
+
+    fn fire_bullets(
+        mut stress: ResMut<Stress>,
+        mut pool: ResMut<EntityPool>,
+        mut commands: Commands,
+    ) {
+        if stress.alive.len() == LIFETIME_FRAMES {
+            for entity in stress.alive.pop_front().unwrap() {
+                match stress.use_pool {
+                    true => pool.release(entity, &mut commands),
+                    false => commands.entity(entity).despawn_recursive(),
+                }
+            }
+        }
+
+        let fired: Vec<Entity> = (0..BULLETS_PER_FRAME)
+            .map(|_| match stress.use_pool {
+                true => pool
+                    .spawn(PoolKind::Bullet, &mut commands)
+                    .insert(Velocity::ZERO)
+                    .id(),
+                false => commands.spawn(Velocity::ZERO).id(),
+            })
+            .collect();
+
+        stress.handed_out.extend(fired.iter().copied());
+        stress.alive.push_back(fired);
+    }
+
+    fn run_stress(use_pool: bool) -> usize {
+        let mut app = App::new();
+        app.init_resource::<EntityPool>()
+            .insert_resource(Stress {
+                use_pool,
+                alive: VecDeque::new(),
+                handed_out: HashSet::new(),
+            })
+            .add_systems(Update, (fire_bullets, forget_despawned))
+            .add_systems(Last, recycle_released);
+
+        for _ in 0..FRAMES {
+            app.update();
+        }
+
+        app.world.resource::<Stress>().handed_out.len()
+    }
+
+    #[test]
+    fn pool_stops_allocating_once_warmed_up() {
+        let despawned = run_stress(false);
+        let pooled = run_stress(true);
+
+        assert_eq!(despawned, BULLETS_PER_FRAME * FRAMES);
+        // What is alive at once, plus the frame released entities wait before coming back
+        assert!(pooled <= BULLETS_PER_FRAME * (LIFETIME_FRAMES + 1));
+    }
+
+    #[test]
+    fn released_entities_come_back_the_next_frame() {
+        let mut world = World::new();
+        let mut pool = EntityPool::default();
+        let mut queue = bevy::ecs::system::CommandQueue::default();
+
+        let first = {
+            let mut commands = Commands::new(&mut queue, &world);
+            let entity = pool.spawn(PoolKind::Bullet, &mut commands).id();
+            pool.release(entity, &mut commands);
+            let second = pool.spawn(PoolKind::Bullet, &mut commands).id();
+            assert_ne!(entity, second);
+            entity
+        };
+        queue.apply(&mut world);
+
+        world.insert_resource(pool);
+        Schedule::default()
+            .add_systems(recycle_released)
+            .run(&mut world);
+
+        let mut pool = world.remove_resource::<EntityPool>().unwrap();
+        let mut commands = Commands::new(&mut queue, &world);
+        assert_eq!(pool.spawn(PoolKind::Bullet, &mut commands).id(), first);
+    }
+}
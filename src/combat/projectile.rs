@@ -3,14 +3,18 @@ use bevy::prelude::*;
 use crate::collision::collider::CollisionStartEvent;
 use crate::combat::health::HealthType;
 
-use super::health::{Dead, Health, TookDamageEvent};
+use super::health::{DamageSource, Dead, Health, TookDamageEvent};
+use super::pool::EntityPool;
+use super::projectile_behavior::{Bounce, Deflection, ProjectileDeflectEvent, Ricochet};
 use super::teams::{Team, TeamMember};
 
+#[derive(Clone, Copy)]
 pub enum DamageTarget {
     All,
     Team(Team),
 }
 
+#[derive(Clone, Copy)]
 pub enum PiercingMode {
     None,
     Count(usize),
@@ -24,6 +28,7 @@ pub struct Projectile {
     pub piercing_mode: PiercingMode,
     pub entities_hit: Vec<Entity>,
     pub is_alive: bool,
+    pub source: DamageSource,
 }
 
 #[derive(Event)]
@@ -33,41 +38,54 @@ pub struct ProjectileHitEvent {
 }
 
 pub fn projectile_collision_check(
-    mut q_projectiles: Query<&mut Projectile, Without<Dead>>,
+    mut q_projectiles: Query<
+        (&mut Projectile, Option<&mut Bounce>, Option<&mut Ricochet>),
+        Without<Dead>,
+    >,
     mut q_hittable: Query<(&mut Health, &TeamMember)>,
     mut ev_collision: EventReader<CollisionStartEvent>,
     mut ev_hit: EventWriter<ProjectileHitEvent>,
     mut ev_dmg: EventWriter<TookDamageEvent>,
+    mut ev_deflect: EventWriter<ProjectileDeflectEvent>,
+    mut pool: ResMut<EntityPool>,
     mut commands: Commands,
 ) {
     for ev_is_colliding in ev_collision.iter() {
-        if let (Ok(bullet), Ok((health, member))) = (
+        if let (Ok((bullet, bounce, ricochet)), Ok((health, member))) = (
             q_projectiles.get_mut(ev_is_colliding.collision.entity_a),
             q_hittable.get_mut(ev_is_colliding.collision.entity_b),
         ) {
             handle_projectile_collision(
                 ev_is_colliding.collision.entity_a,
                 bullet,
+                bounce,
+                ricochet,
                 ev_is_colliding.collision.entity_b,
                 health,
                 member.team,
                 &mut ev_hit,
                 &mut ev_dmg,
+                &mut ev_deflect,
+                &mut pool,
                 &mut commands,
             );
         }
-        if let (Ok(bullet), Ok((health, member))) = (
+        if let (Ok((bullet, bounce, ricochet)), Ok((health, member))) = (
             q_projectiles.get_mut(ev_is_colliding.collision.entity_b),
             q_hittable.get_mut(ev_is_colliding.collision.entity_a),
         ) {
             handle_projectile_collision(
                 ev_is_colliding.collision.entity_b,
                 bullet,
+                bounce,
+                ricochet,
                 ev_is_colliding.collision.entity_a,
                 health,
                 member.team,
                 &mut ev_hit,
                 &mut ev_dmg,
+                &mut ev_deflect,
+                &mut pool,
                 &mut commands,
             );
         }
@@ -77,11 +95,15 @@ pub fn projectile_collision_check(
 fn handle_projectile_collision(
     projectile_entity: Entity,
     mut projectile: Mut<Projectile>,
+    bounce: Option<Mut<Bounce>>,
+    ricochet: Option<Mut<Ricochet>>,
     hit_entity: Entity,
     mut health: Mut<Health>,
     hit_team: Team,
     ev_hit: &mut EventWriter<ProjectileHitEvent>,
     ev_dmg: &mut EventWriter<TookDamageEvent>,
+    ev_deflect: &mut EventWriter<ProjectileDeflectEvent>,
+    pool: &mut EntityPool,
     commands: &mut Commands,
 ) {
     if !projectile.is_alive {
@@ -92,12 +114,13 @@ fn handle_projectile_collision(
         return; // Don't hit twice
     }
 
+    let is_obstacle = hit_team == Team::None;
+
     match projectile.damage_target {
         DamageTarget::All => {
             // Will hit
         }
         DamageTarget::Team(team_to_hit) => {
-            let is_obstacle = hit_team == Team::None;
             let can_hit_team = team_to_hit == hit_team;
 
             if !is_obstacle && !can_hit_team {
@@ -113,7 +136,7 @@ fn handle_projectile_collision(
     });
 
     projectile.entities_hit.push(hit_entity);
-    health.take_damage(hit_entity, ev_dmg, projectile.dmg);
+    health.take_damage(hit_entity, ev_dmg, projectile.dmg, projectile.source);
 
     let is_dead = match projectile.piercing_mode {
         PiercingMode::None => true,
@@ -121,8 +144,25 @@ fn handle_projectile_collision(
         PiercingMode::All => false,
     };
 
-    if is_dead {
-        commands.entity(projectile_entity).despawn_recursive();
+    // Bouncing off obstacles and ricocheting off enemies keep it going instead
+    let deflection = match (is_dead, is_obstacle) {
+        (false, _) => None,
+        (true, true) => bounce
+            .is_some_and(|mut bounce| bounce.try_use())
+            .then_some(Deflection::Bounce),
+        (true, false) => ricochet
+            .is_some_and(|mut ricochet| ricochet.try_use())
+            .then_some(Deflection::Ricochet),
+    };
+
+    if let Some(deflection) = deflection {
+        ev_deflect.send(ProjectileDeflectEvent {
+            projectile: projectile_entity,
+            victim: hit_entity,
+            deflection,
+        });
+    } else if is_dead {
+        pool.release(projectile_entity, commands);
         projectile.is_alive = false;
     }
 }
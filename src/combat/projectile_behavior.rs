@@ -0,0 +1,344 @@
+use bevy::{prelude::*, window::PrimaryWindow};
+
+use crate::{
+    collision::collider::Collider,
+    enemies::{enemy::Enemy, spatial::EnemyGrid},
+    movement::velocity::Velocity,
+    player::Player,
+    util::{clock::GameClock, radians::Radian},
+};
+
+use super::{
+    health::DamageSource,
+    knockback::Knockback,
+    lifetime::{MaxRange, PROJECTILE_RANGE},
+    pool::{EntityPool, PoolKind},
+    projectile::{PiercingMode, Projectile, ProjectileHitEvent},
+};
+
+// Ricochets only jump to enemies this close to the one hit
+const RICOCHET_RANGE: f32 = 200.;
+// Returning boomerangs vanish once they get this close to the player
+const BOOMERANG_CATCH_RADIUS: f32 = 20.;
+// Angle between the pieces of a split projectile, in degrees
+const SPLIT_SPREAD: f32 = 30.;
+
+/*
+Behaviors are separate components so they stack on the same projectile, like a
+homing boomerang that splits on hit
+*/
+
+// Steers toward the closest enemy in range, turn rate in degrees per second
+#[derive(Component)]
+pub struct Homing {
+    pub turn_rate: f32,
+    pub acquire_radius: f32,
+}
+
+// Bounces off the edges of the screen and obstacles, `count` times
+#[derive(Component)]
+pub struct Bounce {
+    pub count: u32,
+}
+
+// Jumps to the next enemy instead of dying on hit, `count` times
+#[derive(Component)]
+pub struct Ricochet {
+    pub count: u32,
+}
+
+// Flies out until the timer is done, then comes back to the player
+#[derive(Component)]
+pub struct Boomerang {
+    pub timer: Timer,
+    pub is_returning: bool,
+}
+
+impl Boomerang {
+    pub fn new(out_time: f32) -> Boomerang {
+        Boomerang {
+            timer: Timer::from_seconds(out_time, TimerMode::Once),
+            is_returning: false,
+        }
+    }
+}
+
+// Breaks into `count` weaker pieces, on every hit or only once it dies
+#[derive(Component)]
+pub struct Split {
+    pub on_hit: bool,
+    pub count: u32,
+}
+
+impl Bounce {
+    pub fn try_use(&mut self) -> bool {
+        if self.count == 0 {
+            return false;
+        }
+
+        self.count -= 1;
+        true
+    }
+}
+
+impl Ricochet {
+    pub fn try_use(&mut self) -> bool {
+        if self.count == 0 {
+            return false;
+        }
+
+        self.count -= 1;
+        true
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Deflection {
+    Bounce,
+    Ricochet,
+}
+
+// Sent instead of killing a projectile whose hit was turned into a deflection
+#[derive(Event)]
+pub struct ProjectileDeflectEvent {
+    pub projectile: Entity,
+    pub victim: Entity,
+    pub deflection: Deflection,
+}
+
+// The closest enemy around `position` that the projectile hasn't hit yet
+fn closest_enemy(
+    position: Vec2,
+    radius: f32,
+    projectile: &Projectile,
+    grid: &EnemyGrid,
+    q_enemies: &Query<&Transform, (With<Enemy>, Without<Projectile>)>,
+) -> Option<Vec2> {
+    grid.around(position, radius)
+        .filter(|entity| !projectile.entities_hit.contains(entity))
+        .filter_map(|entity| q_enemies.get(entity).ok())
+        .map(|transform| transform.translation.truncate())
+        .filter(|enemy| enemy.distance(position) < radius)
+        .min_by(|a, b| a.distance(position).total_cmp(&b.distance(position)))
+}
+
+pub fn homing_update(
+    mut q_homing: Query<(&Homing, &Projectile, &Transform, &mut Velocity)>,
+    q_enemies: Query<&Transform, (With<Enemy>, Without<Projectile>)>,
+    grid: Res<EnemyGrid>,
+    clock: Res<GameClock>,
+) {
+    for (homing, projectile, transform, mut velocity) in q_homing.iter_mut() {
+        let position = transform.translation.truncate();
+        let Some(target) = closest_enemy(
+            position,
+            homing.acquire_radius,
+            projectile,
+            &grid,
+            &q_enemies,
+        ) else {
+            continue;
+        };
+
+        let direction = target - position;
+        let current = velocity.vec.y.atan2(velocity.vec.x);
+        let desired = direction.y.atan2(direction.x);
+        let diff = Radian::from(desired - current).normalize_to_half().angle;
+        let max_turn = homing.turn_rate.to_radians() * clock.delta_seconds();
+        let new_angle = current + diff.clamp(-max_turn, max_turn);
+
+        velocity.vec = Vec2::new(new_angle.cos(), new_angle.sin()) * velocity.vec.length();
+    }
+}
+
+pub fn bounce_off_edges(
+    mut q_bounce: Query<(&mut Bounce, &Transform, &mut Velocity)>,
+    q_windows: Query<&Window, With<PrimaryWindow>>,
+) {
+    let window = q_windows.single();
+    let half_size = Vec2::new(window.width(), window.height()) / 2.;
+
+    for (mut bounce, transform, mut velocity) in q_bounce.iter_mut() {
+        let position = transform.translation.truncate();
+
+        // Only while heading further out, so it doesn't flip back and forth past the edge
+        let leaving_x = position.x.abs() > half_size.x && position.x * velocity.vec.x > 0.;
+        let leaving_y = position.y.abs() > half_size.y && position.y * velocity.vec.y > 0.;
+
+        if !(leaving_x || leaving_y) || !bounce.try_use() {
+            continue;
+        }
+
+        if leaving_x {
+            velocity.vec.x = -velocity.vec.x;
+        }
+
+        if leaving_y {
+            velocity.vec.y = -velocity.vec.y;
+        }
+    }
+}
+
+pub fn deflect_projectiles(
+    mut q_projectiles: Query<(&mut Projectile, &Transform, &mut Velocity)>,
+    q_victims: Query<&Transform, Without<Projectile>>,
+    q_enemies: Query<&Transform, (With<Enemy>, Without<Projectile>)>,
+    mut deflect_ev: EventReader<ProjectileDeflectEvent>,
+    grid: Res<EnemyGrid>,
+    mut pool: ResMut<EntityPool>,
+    mut commands: Commands,
+) {
+    for ev in deflect_ev.iter() {
+        let (Ok((mut projectile, transform, mut velocity)), Ok(victim)) = (
+            q_projectiles.get_mut(ev.projectile),
+            q_victims.get(ev.victim),
+        ) else {
+            continue;
+        };
+
+        let position = transform.translation.truncate();
+
+        match ev.deflection {
+            Deflection::Bounce => {
+                // Obstacles are treated as round, bouncing away from their center
+                let normal = (position - victim.translation.truncate()).normalize_or_zero();
+
+                if velocity.vec.dot(normal) < 0. {
+                    velocity.vec -= 2. * velocity.vec.dot(normal) * normal;
+                }
+
+                // The same obstacle can be bounced off again later
+                projectile
+                    .entities_hit
+                    .retain(|entity| *entity != ev.victim);
+            }
+            Deflection::Ricochet => {
+                let victim_position = victim.translation.truncate();
+
+                match closest_enemy(
+                    victim_position,
+                    RICOCHET_RANGE,
+                    &projectile,
+                    &grid,
+                    &q_enemies,
+                ) {
+                    Some(target) => {
+                        velocity.vec =
+                            (target - position).normalize_or_zero() * velocity.vec.length();
+                    }
+                    // Nobody to jump to, the hit ends it after all
+                    None => {
+                        projectile.is_alive = false;
+                        pool.release(ev.projectile, &mut commands);
+                    }
+                }
+            }
+        }
+    }
+}
+
+pub fn boomerang_update(
+    mut q_boomerangs: Query<(
+        Entity,
+        &mut Boomerang,
+        &mut Projectile,
+        &Transform,
+        &mut Velocity,
+    )>,
+    q_player: Query<&Transform, (With<Player>, Without<Boomerang>)>,
+    mut pool: ResMut<EntityPool>,
+    clock: Res<GameClock>,
+    mut commands: Commands,
+) {
+    let player_position = q_player.single().translation.truncate();
+
+    for (entity, mut boomerang, mut projectile, transform, mut velocity) in q_boomerangs.iter_mut()
+    {
+        boomerang.timer.tick(clock.delta());
+
+        if !boomerang.timer.finished() || !projectile.is_alive {
+            continue;
+        }
+
+        // Everything can be hit again on the way back
+        if !boomerang.is_returning {
+            boomerang.is_returning = true;
+            projectile.entities_hit.clear();
+        }
+
+        let diff = player_position - transform.translation.truncate();
+
+        if diff.length() < BOOMERANG_CATCH_RADIUS {
+            projectile.is_alive = false;
+            pool.release(entity, &mut commands);
+            continue;
+        }
+
+        velocity.vec = diff.normalize_or_zero() * velocity.vec.length();
+    }
+}
+
+pub fn split_projectiles(
+    q_projectiles: Query<(
+        &Projectile,
+        &Split,
+        &Transform,
+        &Velocity,
+        &Handle<Image>,
+        &Collider,
+        Option<&Knockback>,
+    )>,
+    mut hit_ev: EventReader<ProjectileHitEvent>,
+    mut pool: ResMut<EntityPool>,
+    mut commands: Commands,
+) {
+    for ev in hit_ev.iter() {
+        let Ok((projectile, split, transform, velocity, texture, collider, knockback)) =
+            q_projectiles.get(ev.projectile)
+        else {
+            continue;
+        };
+
+        if !split.on_hit && projectile.is_alive {
+            continue;
+        }
+
+        let forward = velocity.vec.normalize_or_zero();
+
+        for i in 0..split.count {
+            let offset = (split.count as f32 - 1.) / -2. + i as f32;
+            let direction = Vec2::from_angle((SPLIT_SPREAD * offset).to_radians()).rotate(forward);
+
+            // Pieces don't split again, and skip the enemy that was just hit
+            let mut piece = pool.spawn(PoolKind::Bullet, &mut commands);
+            piece
+                .insert(SpriteBundle {
+                    texture: texture.clone(),
+                    transform: *transform,
+                    ..Default::default()
+                })
+                .insert(Projectile {
+                    damage_target: projectile.damage_target,
+                    dmg: (projectile.dmg / 2).max(1),
+                    piercing_mode: PiercingMode::None,
+                    entities_hit: vec![ev.victim],
+                    is_alive: true,
+                    source: match projectile.source {
+                        DamageSource::Bullet => DamageSource::Shrapnel,
+                        source => source,
+                    },
+                })
+                .insert(Velocity {
+                    vec: direction * velocity.vec.length(),
+                })
+                .insert(collider.clone())
+                .insert(MaxRange::new(transform.translation, PROJECTILE_RANGE));
+
+            if let Some(knockback) = knockback {
+                piece.insert(Knockback {
+                    force: knockback.force / 2.,
+                });
+            }
+        }
+    }
+}
@@ -1,22 +1,30 @@
 use bevy::{prelude::*, time::Stopwatch};
 
-use crate::{animation::AppAnimationSetup, GameState};
+use crate::{animation::AppAnimationSetup, combat::lifetime::update_lifetimes, util::clock::clock_is_running, GameState, InGame};
 
 use self::{
+    affix::AffixPlugin,
     ai::{follow_player, move_and_shoot_ai, ChargeShootEvent, ShootEvent},
     beholder::{beholder_update, BeholderAnimation, BeholderProjectileAnimation, spawn_beholder_prince, spawn_beholder},
     enemy::{death_loop, spread_enemies, EnemyDeathEvent},
     imp::{ImpAnimation, spawn_imp_queen, spawn_imp},
+    patterns::{pattern_emitter_update, pattern_homing_update, spawn_pattern_rng, wave_motion_update},
     reaper::{reaper_blade_update, reaper_update, ReaperAnimation, ReaperBladeAnimation, spawn_reaper},
+    telegraph::TelegraphPlugin,
     spawning::{spawn_loop, spawn_spawn_rng, SpawnInfo, EnemySpawnEvent}, spawn_menu::SpawnMenuPlugin,
+    spatial::{update_enemy_grid, EnemyGrid},
 };
 
+pub mod affix;
 pub mod ai;
 pub mod beholder;
 pub mod enemy;
 pub mod imp;
+pub mod patterns;
 pub mod reaper;
 pub mod spawning;
+pub mod spatial;
+pub mod telegraph;
 pub mod zombie;
 pub mod spawn_menu;
 
@@ -28,14 +36,17 @@ impl Plugin for EnemiesPlugin {
             .add_systems(
                 Update,
                 (
-                    follow_player,
+                    follow_player.run_if(clock_is_running),
                     move_and_shoot_ai,
                     death_loop,
                     spread_enemies,
                     spawn_loop,
                     beholder_update,
+                    pattern_emitter_update.run_if(clock_is_running),
+                    wave_motion_update,
+                    pattern_homing_update,
                     reaper_update,
-                    reaper_blade_update,
+                    reaper_blade_update.after(update_lifetimes),
 
                     spawn_imp,
                     spawn_imp_queen,
@@ -45,7 +56,10 @@ impl Plugin for EnemiesPlugin {
                 )
                     .run_if(in_state(GameState::Playing)),
             )
-            .add_systems(OnEnter(GameState::Playing), spawn_spawn_rng)
+            .add_systems(Update, update_enemy_grid.run_if(in_state(GameState::Playing)))
+            .add_systems(OnEnter(GameState::Playing), (spawn_spawn_rng, spawn_pattern_rng))
+            // Play Again starts over from the seed, like a new run
+            .add_systems(OnExit(InGame::GameOver), (spawn_spawn_rng, spawn_pattern_rng))
             .add_animation::<ImpAnimation>()
             .add_animation::<BeholderAnimation>()
             .add_animation::<BeholderProjectileAnimation>()
@@ -54,10 +68,11 @@ impl Plugin for EnemiesPlugin {
             .add_event::<EnemySpawnEvent>()
             .add_event::<ShootEvent>()
             .add_event::<ChargeShootEvent>()
+            .init_resource::<EnemyGrid>()
             .insert_resource(SpawnInfo {
                 timer: Timer::from_seconds(3., TimerMode::Repeating),
                 game: Stopwatch::new(),
                 count: 0,
-            }).add_plugins(SpawnMenuPlugin);
+            }).add_plugins((SpawnMenuPlugin, AffixPlugin, TelegraphPlugin));
     }
 }
@@ -0,0 +1,380 @@
+use std::time::Duration;
+
+use bevy::prelude::*;
+use bevy_kira_audio::AudioControl;
+use rand::{seq::IteratorRandom, Rng};
+
+use crate::{
+    animation::AnimationStateStorage,
+    audio::FXChannel,
+    collision::collider::Collider,
+    combat::{
+        deathrattle::{explosion_atlas, spawn_explosion, ExplosionAnimation},
+        health::{Health, HealthType},
+        pool::EntityPool,
+        projectile::{DamageTarget, ProjectileHitEvent},
+        teams::Team,
+    },
+    loading::{AudioAssets, TextureAssets},
+    util::{
+        clock::clock_is_running,
+        rng::{GlobalSeed, RNG},
+    },
+    GameState, InGame,
+};
+
+use super::{
+    ai::{FollowPlayerAI, MoveAndShootAI},
+    enemy::{Enemy, EnemyDeathEvent, EnemyType},
+    spawning::EnemySpawnEvent,
+};
+
+pub struct AffixPlugin;
+
+impl Plugin for AffixPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(GameState::Playing), spawn_affix_rng)
+            .add_systems(OnExit(InGame::GameOver), spawn_affix_rng)
+            .add_systems(
+                Update,
+                (
+                    apply_affixes,
+                    shield_update,
+                    vampiric_update.run_if(clock_is_running),
+                    explosive_update,
+                    splitting_update,
+                )
+                    .run_if(in_state(GameState::Playing)),
+            );
+    }
+}
+
+/*
+Elite modifiers rolled onto normal spawns once the run goes on long enough
+*/
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Affix {
+    Hasted,
+    Armored,
+    Vampiric,
+    Explosive,
+    Shielded,
+    Splitting,
+}
+
+impl Affix {
+    pub fn all() -> Vec<Affix> {
+        vec![
+            Affix::Hasted,
+            Affix::Armored,
+            Affix::Vampiric,
+            Affix::Explosive,
+            Affix::Shielded,
+            Affix::Splitting,
+        ]
+    }
+
+    pub fn name(&self) -> String {
+        match self {
+            Affix::Hasted => "Hasted",
+            Affix::Armored => "Armored",
+            Affix::Vampiric => "Vampiric",
+            Affix::Explosive => "Explosive",
+            Affix::Shielded => "Shielded",
+            Affix::Splitting => "Splitting",
+        }
+        .to_string()
+    }
+
+    pub fn tint(&self) -> Color {
+        match self {
+            Affix::Hasted => Color::rgb(1.0, 1.0, 0.6),
+            Affix::Armored => Color::rgb(0.6, 0.7, 0.8),
+            Affix::Vampiric => Color::rgb(1.0, 0.5, 0.5),
+            Affix::Explosive => Color::rgb(1.0, 0.7, 0.3),
+            Affix::Shielded => Color::rgb(0.6, 0.8, 1.0),
+            Affix::Splitting => Color::rgb(0.7, 1.0, 0.6),
+        }
+    }
+
+    pub fn health_mult(&self) -> f32 {
+        match self {
+            Affix::Armored => 2.0,
+            Affix::Vampiric => 1.25,
+            _ => 1.,
+        }
+    }
+
+    pub fn speed_mult(&self) -> f32 {
+        match self {
+            Affix::Hasted => 1.6,
+            Affix::Armored => 0.8,
+            _ => 1.,
+        }
+    }
+
+    pub fn size_mult(&self) -> f32 {
+        match self {
+            Affix::Armored => 1.2,
+            Affix::Splitting => 1.15,
+            _ => 1.,
+        }
+    }
+}
+
+pub const AFFIX_XP_BONUS: f32 = 0.5;
+
+#[derive(Component, Clone)]
+pub struct Affixes(pub Vec<Affix>);
+
+impl Affixes {
+    pub fn contains(&self, affix: Affix) -> bool {
+        self.0.contains(&affix)
+    }
+
+    pub fn speed_mult(&self) -> f32 {
+        self.0
+            .iter()
+            .fold(1., |speed, affix| speed * affix.speed_mult())
+    }
+}
+
+pub fn affix_names(affixes: &[Affix]) -> String {
+    affixes
+        .iter()
+        .map(|affix| affix.name())
+        .collect::<Vec<String>>()
+        .join(" ")
+}
+
+pub struct AffixThreshold {
+    // Seconds into the run
+    pub after: f32,
+    pub chance: f32,
+    pub max_affixes: usize,
+}
+
+pub const AFFIX_THRESHOLDS: [AffixThreshold; 3] = [
+    AffixThreshold {
+        after: 120.,
+        chance: 0.1,
+        max_affixes: 1,
+    },
+    AffixThreshold {
+        after: 300.,
+        chance: 0.2,
+        max_affixes: 2,
+    },
+    AffixThreshold {
+        after: 480.,
+        chance: 0.3,
+        max_affixes: 3,
+    },
+];
+
+#[derive(Resource)]
+pub struct AffixRNG(pub RNG);
+
+pub fn spawn_affix_rng(seed: Res<GlobalSeed>, mut commands: Commands) {
+    commands.insert_resource(AffixRNG(RNG::new(&seed.0, "affix")))
+}
+
+pub fn roll_affixes(elapsed: Duration, rng: &mut AffixRNG) -> Vec<Affix> {
+    let threshold = match AFFIX_THRESHOLDS
+        .iter()
+        .filter(|threshold| threshold.after <= elapsed.as_secs_f32())
+        .last()
+    {
+        Some(threshold) => threshold,
+        None => return vec![],
+    };
+
+    let mut affixes = vec![];
+
+    while affixes.len() < threshold.max_affixes
+        && rng.0 .0.gen_range(0. ..1.) < threshold.chance
+    {
+        match Affix::all()
+            .into_iter()
+            .filter(|affix| !affixes.contains(affix))
+            .choose(&mut rng.0 .0)
+        {
+            Some(affix) => affixes.push(affix),
+            None => break,
+        }
+    }
+
+    affixes
+}
+
+// What a splitting enemy breaks into when it dies
+pub fn split_type(enemy_type: EnemyType) -> EnemyType {
+    match enemy_type {
+        EnemyType::ImpQueen | EnemyType::Reaper => EnemyType::Imp,
+        EnemyType::BeholderPrince => EnemyType::Beholder,
+        _ => enemy_type,
+    }
+}
+
+pub const SHIELD_HITS: u32 = 3;
+
+#[derive(Component)]
+pub struct Shield {
+    pub hits: u32,
+}
+
+fn apply_affixes(
+    mut q_enemies: Query<
+        (
+            Entity,
+            &Affixes,
+            &mut Enemy,
+            &mut Health,
+            &mut Collider,
+            &mut Transform,
+            &mut TextureAtlasSprite,
+            Option<&mut FollowPlayerAI>,
+            Option<&mut MoveAndShootAI>,
+        ),
+        Added<Affixes>,
+    >,
+    mut commands: Commands,
+) {
+    for (
+        entity,
+        affixes,
+        mut enemy,
+        mut health,
+        mut collider,
+        mut transform,
+        mut sprite,
+        follow_ai,
+        shoot_ai,
+    ) in q_enemies.iter_mut()
+    {
+        if affixes.0.is_empty() {
+            continue;
+        }
+
+        for affix in affixes.0.iter() {
+            health.max = (health.max as f32 * affix.health_mult()) as HealthType;
+            health.value = health.max;
+
+            *collider = collider.scaled(affix.size_mult());
+            transform.scale.x *= affix.size_mult();
+            transform.scale.y *= affix.size_mult();
+
+            if *affix == Affix::Shielded {
+                health.is_invincible = true;
+                commands.entity(entity).insert(Shield { hits: SHIELD_HITS });
+            }
+        }
+
+        if let Some(mut ai) = follow_ai {
+            ai.speed *= affixes.speed_mult();
+        }
+
+        if let Some(mut ai) = shoot_ai {
+            ai.speed *= affixes.speed_mult();
+        }
+
+        enemy.xp = (enemy.xp as f32 * (1. + AFFIX_XP_BONUS * affixes.0.len() as f32)) as u32;
+        sprite.color = affixes.0[0].tint();
+    }
+}
+
+fn shield_update(
+    mut q_shields: Query<(Entity, &mut Shield, &mut Health)>,
+    mut hit_ev: EventReader<ProjectileHitEvent>,
+    mut commands: Commands,
+) {
+    for hit in hit_ev.iter() {
+        if let Ok((entity, mut shield, mut health)) = q_shields.get_mut(hit.victim) {
+            if shield.hits == 0 {
+                continue;
+            }
+
+            shield.hits -= 1;
+
+            if shield.hits == 0 {
+                health.is_invincible = false;
+                commands.entity(entity).remove::<Shield>();
+            }
+        }
+    }
+}
+
+pub const VAMPIRIC_RADIUS: f32 = 150.;
+
+// Vampiric enemies feed on the deaths of those around them
+fn vampiric_update(
+    mut q_vampires: Query<(&Affixes, &Transform, &mut Health), With<Enemy>>,
+    mut death_ev: EventReader<EnemyDeathEvent>,
+) {
+    for death in death_ev.iter() {
+        for (affixes, transform, mut health) in q_vampires.iter_mut() {
+            if !affixes.contains(Affix::Vampiric) {
+                continue;
+            }
+
+            if transform.translation.truncate().distance(death.location.truncate())
+                > VAMPIRIC_RADIUS
+            {
+                continue;
+            }
+
+            health.value = health.max.min(health.value + (health.max / 4).max(1));
+        }
+    }
+}
+
+fn explosive_update(
+    mut death_ev: EventReader<EnemyDeathEvent>,
+    animations: Res<AnimationStateStorage<ExplosionAnimation>>,
+    textures: Res<TextureAssets>,
+    mut texture_atlases: ResMut<Assets<TextureAtlas>>,
+    audio: Res<AudioAssets>,
+    fx: Res<FXChannel>,
+    mut pool: ResMut<EntityPool>,
+    mut commands: Commands,
+) {
+    for death in death_ev.iter() {
+        if !death.affixes.contains(&Affix::Explosive) {
+            continue;
+        }
+
+        fx.play(audio.explosion.clone());
+        spawn_explosion(
+            death.location,
+            1,
+            DamageTarget::Team(Team::Player),
+            &animations,
+            explosion_atlas(&textures, &mut texture_atlases),
+            &mut pool,
+            &mut commands,
+        );
+    }
+}
+
+pub const SPLIT_COUNT: u32 = 2;
+
+fn splitting_update(
+    mut death_ev: EventReader<EnemyDeathEvent>,
+    mut spawn_ev: EventWriter<EnemySpawnEvent>,
+) {
+    for death in death_ev.iter() {
+        if !death.affixes.contains(&Affix::Splitting) {
+            continue;
+        }
+
+        for i in 0..SPLIT_COUNT {
+            let offset = (i as f32 - (SPLIT_COUNT - 1) as f32 / 2.) * 30.;
+
+            spawn_ev.send(EnemySpawnEvent {
+                enemy_type: split_type(death.enemy.enemy_type),
+                position: death.location.truncate() + Vec2 { x: offset, y: 0. },
+                affixes: vec![],
+            });
+        }
+    }
+}
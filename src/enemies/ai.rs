@@ -3,9 +3,9 @@ use std::f32::consts::PI;
 use bevy::prelude::*;
 
 use crate::{
-    movement::{pause::ActionPauseState, velocity::Velocity},
+    movement::velocity::Velocity,
     player::Player,
-    util::radians::Radian,
+    util::{clock::GameClock, radians::Radian},
 };
 
 #[derive(Component)]
@@ -20,12 +20,7 @@ pub struct FollowPlayerAI {
 pub fn follow_player(
     mut q_enemies: Query<(&Transform, &FollowPlayerAI, &mut Velocity)>,
     q_player: Query<&Transform, (With<Player>, Without<FollowPlayerAI>)>,
-    pause: Res<ActionPauseState>,
 ) {
-    if pause.is_paused {
-        return;
-    }
-
     let player_transform = q_player.single();
 
     for (transform, ai, mut velocity) in q_enemies.iter_mut() {
@@ -105,18 +100,13 @@ pub fn move_and_shoot_ai(
     q_player: Query<(Entity, &Transform), (With<Player>, Without<FollowPlayerAI>)>,
     mut charge_ev: EventWriter<ChargeShootEvent>,
     mut shoot_ev: EventWriter<ShootEvent>,
-    pause: Res<ActionPauseState>,
-    time: Res<Time>,
+    clock: Res<GameClock>,
 ) {
-    if pause.is_paused {
-        return;
-    }
-
     let (player_entity, player_transform) = q_player.single();
 
     for (entity, transform, mut ai, mut velocity) in q_enemies.iter_mut() {
-        ai.charge_timer.tick(time.delta());
-        ai.refresh_timer.tick(time.delta());
+        ai.charge_timer.tick(clock.delta());
+        ai.refresh_timer.tick(clock.delta());
 
         if ai.state == MoveAndShootAIState::Move
             && (player_transform.translation.distance(transform.translation) <= ai.shoot_distance)
@@ -1,7 +1,6 @@
-use std::{f32::consts::PI, time::Duration};
+use std::time::Duration;
 
 use bevy::prelude::*;
-use bevy_kira_audio::AudioControl;
 
 use crate::{
     animation::{
@@ -9,25 +8,27 @@ use crate::{
         info::{AnimationInfoBuilder, AnimationStateInfo},
         make_animation_bundle, Animation, AnimationStateChangeEvent, AnimationStateStorage,
     },
-    audio::FXChannel,
     collision::collider::Collider,
     combat::{
         health::Health,
         healthbar::{HealthBar, HEALTH_BAR_SEGMENTS},
-        projectile::{DamageTarget, PiercingMode, Projectile},
         teams::{Team, TeamMember},
         z_sort::ZSort,
     },
     constants::SortingLayers,
-    loading::{AudioAssets, TextureAssets},
+    loading::TextureAssets,
     movement::velocity::Velocity,
     player::Player,
-    util::{radians::Radian, with_z::WithZ},
+    util::{despawn_on_exit::DespawnOnExit, with_z::WithZ},
 };
 
 use super::{
+    affix::Affixes,
     ai::{ChargeShootEvent, MoveAndShootAI, ShootEvent},
-    enemy::{Enemy, EnemyBundle, EnemyType}, spawning::EnemySpawnEvent,
+    enemy::{Enemy, EnemyBundle, EnemyType},
+    patterns::{choose_pattern, BulletPattern, PatternEmitter, PatternRNG},
+    spawning::{EnemySpawnEvent, SpawnInfo},
+    telegraph::TelegraphEvent,
 };
 
 #[derive(PartialEq, Eq, Hash, Clone, Copy)]
@@ -73,118 +74,63 @@ impl Animation<BeholderProjectileAnimation> for BeholderProjectileAnimation {
 #[derive(Component)]
 pub struct BeholderPrince;
 
+// The pattern picked when charging, fired once the charge is done
+#[derive(Component)]
+pub struct ChargedPattern(pub BulletPattern);
+
 pub fn beholder_update(
     q_beholders: Query<
-        (Entity, &Transform, &AnimationController<BeholderAnimation>),
+        (
+            Entity,
+            &Enemy,
+            &MoveAndShootAI,
+            Option<&ChargedPattern>,
+            &AnimationController<BeholderAnimation>,
+        ),
         Without<Player>,
     >,
-    q_beholder_prince: Query<&BeholderPrince>,
-    q_player: Query<(Entity, &Transform), With<Player>>,
+    q_player: Query<Entity, With<Player>>,
     mut shoot_ev: EventReader<ShootEvent>,
     mut charge_ev: EventReader<ChargeShootEvent>,
     mut animate: EventWriter<AnimationStateChangeEvent<BeholderAnimation>>,
-    beholder_projetile_animations: Res<AnimationStateStorage<BeholderProjectileAnimation>>,
-    textures: Res<TextureAssets>,
-    mut texture_atlases: ResMut<Assets<TextureAtlas>>,
-    fx: Res<FXChannel>,
-    audio: Res<AudioAssets>,
+    mut telegraph_ev: EventWriter<TelegraphEvent>,
+    spawn_info: Res<SpawnInfo>,
+    mut rng: ResMut<PatternRNG>,
     mut commands: Commands,
 ) {
-    let texture_atlas = TextureAtlas::from_grid(
-        textures.beholder_projectile.clone(),
-        Vec2 { x: 32., y: 32. },
-        4,
-        1,
-        None,
-        None,
-    );
-    let texture_atlas_handle = texture_atlases.add(texture_atlas);
-
     for charge in charge_ev.iter() {
-        if let Ok((entity, _, _)) = q_beholders.get(charge.entity) {
+        if let Ok((entity, enemy, ai, _, _)) = q_beholders.get(charge.entity) {
             animate.send(AnimationStateChangeEvent {
                 id: entity,
                 state_id: BeholderAnimation::Shoot,
-            })
+            });
+
+            if let Some(pattern) =
+                choose_pattern(enemy.enemy_type, spawn_info.game.elapsed(), &mut rng)
+            {
+                telegraph_ev.send(TelegraphEvent {
+                    owner: entity,
+                    shape: pattern.telegraph(),
+                    duration: ai.charge_timer.duration(),
+                    target: q_player.get_single().ok(),
+                });
+                commands.entity(entity).insert(ChargedPattern(pattern));
+            }
         }
     }
 
     for shoot in shoot_ev.iter() {
-        if let Ok((entity, transform, _)) = q_beholders.get(shoot.entity) {
+        if let Ok((entity, _, _, charged_pattern, _)) = q_beholders.get(shoot.entity) {
             animate.send(AnimationStateChangeEvent {
                 id: entity,
                 state_id: BeholderAnimation::Flying,
             });
-            let (_player_entity, player_transform) = q_player.single();
 
-            let direction =
-                player_transform.translation.truncate() - transform.translation.truncate();
-            // obtain angle to target with respect to x-axis.
-            let angle_to_target = Radian::from(direction.y.atan2(direction.x) - PI / 2.);
-            let direction_vec = angle_to_target.unit_vector();
-
-            fx.play(audio.fireball.clone());
-
-            commands
-                .spawn(make_animation_bundle(
-                    BeholderProjectileAnimation::Flying,
-                    &beholder_projetile_animations,
-                    texture_atlas_handle.clone(),
-                    transform.translation,
-                    1.,
-                ))
-                .insert(Projectile {
-                    dmg: 1,
-                    damage_target: DamageTarget::Team(Team::Player),
-                    piercing_mode: PiercingMode::None,
-                    entities_hit: vec![],
-                    is_alive: true,
-                })
-                .insert(Velocity {
-                    vec: direction_vec * 40.,
-                })
-                .insert(Collider::new_circle(15.));
-
-            if q_beholder_prince.get(entity).is_ok() {
+            if let Some(charged_pattern) = charged_pattern {
                 commands
-                    .spawn(make_animation_bundle(
-                        BeholderProjectileAnimation::Flying,
-                        &beholder_projetile_animations,
-                        texture_atlas_handle.clone(),
-                        transform.translation,
-                        1.,
-                    ))
-                    .insert(Projectile {
-                        dmg: 1,
-                        damage_target: DamageTarget::Team(Team::Player),
-                        piercing_mode: PiercingMode::None,
-                        entities_hit: vec![],
-                        is_alive: true,
-                    })
-                    .insert(Velocity {
-                        vec: (angle_to_target + Radian::from_degrees(10.)).unit_vector() * 40.,
-                    })
-                    .insert(Collider::new_circle(15.));
-
-                commands
-                    .spawn(make_animation_bundle(
-                        BeholderProjectileAnimation::Flying,
-                        &beholder_projetile_animations,
-                        texture_atlas_handle.clone(),
-                        transform.translation,
-                        1.,
-                    ))
-                    .insert(Projectile {
-                        dmg: 1,
-                        damage_target: DamageTarget::Team(Team::Player),
-                        piercing_mode: PiercingMode::None,
-                        entities_hit: vec![],
-                        is_alive: true,
-                    })
-                    .insert(Velocity {
-                        vec: (angle_to_target - Radian::from_degrees(10.)).unit_vector() * 40.,
-                    })
-                    .insert(Collider::new_circle(15.));
+                    .entity(entity)
+                    .insert(PatternEmitter::new(charged_pattern.0))
+                    .remove::<ChargedPattern>();
             }
         }
     }
@@ -236,7 +182,9 @@ pub fn spawn_beholder(
                 health: Health::new(25),
                 collider: Collider::new_circle(20.),
                 team: TeamMember { team: Team::Enemy },
+                scope: DespawnOnExit::run(),
             })
+            .insert(Affixes(spawn_event.affixes.clone()))
             .insert(MoveAndShootAI::new(20., 3., 200., 6. / 8., 2.))
             .insert(make_animation_bundle(
                 BeholderAnimation::Flying,
@@ -314,8 +262,10 @@ pub fn spawn_beholder_prince(
                 health: Health::new(200),
                 collider: Collider::new_circle(20.),
                 team: TeamMember { team: Team::Enemy },
+                scope: DespawnOnExit::run(),
             })
             .insert(BeholderPrince)
+            .insert(Affixes(spawn_event.affixes.clone()))
             .insert(MoveAndShootAI::new(20., 5., 300., 6. / 8., 3.))
             .insert(make_animation_bundle(
                 BeholderAnimation::Flying,
@@ -5,20 +5,27 @@ use rand::Rng;
 
 
 use crate::audio::FXChannel;
+use crate::camera::{HitStopEvent, ScreenShakeEvent, ZoomPunchEvent};
 use crate::collision::collider::{Collider, IsCollidingEvent};
 
+use crate::combat::fire::Fire;
 use crate::combat::health::{DeathEvent, Health};
 use crate::combat::teams::TeamMember;
 use crate::combat::z_sort::ZSort;
+use crate::constants::DISTANCE_SCALING;
 
 use crate::loading::{AudioAssets, TextureAssets};
 
 use crate::movement::velocity::Velocity;
+use crate::util::despawn_on_exit::DespawnOnExit;
 use crate::util::pitch_rng::PitchRNG;
+use crate::InGame;
 
+use super::affix::{Affix, Affixes};
 
 
-#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
 pub enum EnemyType {
     Imp,
     ImpQueen,
@@ -48,6 +55,13 @@ impl EnemyType {
         }
     }
 
+    pub fn is_boss(&self) -> bool {
+        matches!(
+            self,
+            EnemyType::ImpQueen | EnemyType::BeholderPrince | EnemyType::Reaper
+        )
+    }
+
     pub fn sprite_size(&self) -> Vec2 {
         match self {
             EnemyType::Reaper => Vec2 { x: 64.0, y: 64.0 },
@@ -84,6 +98,7 @@ pub struct EnemyBundle {
     pub velocity: Velocity,
     pub collider: Collider,
     pub team: TeamMember,
+    pub scope: DespawnOnExit<InGame>,
 }
 
 #[derive(Component, Clone)]
@@ -97,11 +112,14 @@ pub struct EnemyDeathEvent {
     pub entity: Entity,
     pub enemy: Enemy,
     pub location: Vec3,
+    pub affixes: Vec<Affix>,
+    pub burning: bool,
 }
 
 impl Enemy {
-    pub fn estimate_position(&self, transform: &Transform, _time: f32) -> Vec2 {
-        transform.translation.truncate()
+    // Where the enemy will be in `time` seconds if it keeps going the same way
+    pub fn estimate_position(&self, transform: &Transform, velocity: &Velocity, time: f32) -> Vec2 {
+        transform.translation.truncate() + velocity.vec * time * DISTANCE_SCALING
     }
 }
 
@@ -109,14 +127,18 @@ impl Enemy {
 pub fn death_loop(
     mut ememy_death_event: EventWriter<EnemyDeathEvent>,
     mut death_event: EventReader<DeathEvent>,
-    mut q_enemies: Query<(Entity, &Enemy, &Transform)>,
+    mut q_enemies: Query<(Entity, &Enemy, &Transform, Option<&Affixes>, Option<&Children>)>,
+    q_fire: Query<(), With<Fire>>,
     fx_channel: Res<FXChannel>,
     audio: Res<AudioAssets>,
     mut pitch_rng: ResMut<PitchRNG>,
+    mut shake_ev: EventWriter<ScreenShakeEvent>,
+    mut hit_stop_ev: EventWriter<HitStopEvent>,
+    mut zoom_ev: EventWriter<ZoomPunchEvent>,
     mut commands: Commands,
 ) {
     for death_ev in death_event.iter() {
-        if let Ok((entity, enemy, transform)) = q_enemies.get_mut(death_ev.entity) {
+        if let Ok((entity, enemy, transform, affixes, children)) = q_enemies.get_mut(death_ev.entity) {
             fx_channel.play(match enemy.enemy_type {
                 EnemyType::Imp | EnemyType::ImpQueen => match pitch_rng.0 .0.gen_range(0..4) {
                     0 => audio.imp_death.clone(),
@@ -129,11 +151,20 @@ pub fn death_loop(
                 EnemyType::Reaper => audio.reaper_death.clone(),
             });
 
+            if enemy.enemy_type.is_boss() {
+                shake_ev.send(ScreenShakeEvent { trauma: 0.8 });
+                hit_stop_ev.send(HitStopEvent { duration: 0.15 });
+                zoom_ev.send(ZoomPunchEvent { amount: 0.1 });
+            }
+
             commands.entity(entity).despawn_recursive();
             ememy_death_event.send(EnemyDeathEvent {
                 entity,
                 enemy: enemy.clone(),
                 location: transform.translation,
+                affixes: affixes.map_or(vec![], |affixes| affixes.0.clone()),
+                burning: children
+                    .is_some_and(|children| children.iter().any(|child| q_fire.contains(*child))),
             });
         }
     }
@@ -16,9 +16,11 @@ use crate::{
     constants::SortingLayers,
     loading::TextureAssets,
     movement::velocity::Velocity,
+    util::despawn_on_exit::DespawnOnExit,
 };
 
 use super::{
+    affix::Affixes,
     ai::FollowPlayerAI,
     enemy::{Enemy, EnemyBundle, EnemyType}, spawning::EnemySpawnEvent,
 };
@@ -85,7 +87,9 @@ pub fn spawn_imp(
                 health: Health::new(15),
                 collider: Collider::new_rect(Vec2 { x: 50., y: 20. }),
                 team: TeamMember { team: Team::Enemy },
+                scope: DespawnOnExit::run(),
             })
+            .insert(Affixes(spawn_ev.affixes.clone()))
             .insert(FollowPlayerAI {
                 speed: 15.,
                 corrective_force: 1.0,
@@ -166,8 +170,10 @@ pub fn spawn_imp_queen(
                 health: Health::new(150),
                 collider: Collider::new_rect(Vec2 { x: 50., y: 20. }),
                 team: TeamMember { team: Team::Enemy },
+                scope: DespawnOnExit::run(),
             })
             .insert(ImpQueen)
+            .insert(Affixes(spawn_ev.affixes.clone()))
             .insert(FollowPlayerAI {
                 speed: 12.,
                 corrective_force: 3.0,
@@ -0,0 +1,490 @@
+use std::{f32::consts::PI, time::Duration};
+
+use bevy::prelude::*;
+use bevy_kira_audio::AudioControl;
+use rand::seq::SliceRandom;
+
+use crate::{
+    animation::{make_animation_bundle, AnimationStateStorage},
+    audio::FXChannel,
+    collision::collider::Collider,
+    combat::{
+        health::{DamageSource, HealthType},
+        lifetime::{MaxRange, PROJECTILE_RANGE},
+        pool::{EntityPool, PoolKind},
+        projectile::{DamageTarget, PiercingMode, Projectile},
+        teams::Team,
+    },
+    loading::{AudioAssets, TextureAssets},
+    movement::velocity::Velocity,
+    player::Player,
+    util::{
+        clock::GameClock,
+        radians::Radian,
+        rng::{GlobalSeed, RNG},
+    },
+};
+
+use super::{
+    beholder::BeholderProjectileAnimation,
+    enemy::EnemyType,
+    telegraph::TelegraphShape,
+};
+
+/*
+Bullet patterns describe enemy attacks as data, so that any shooter can pick one
+from the library instead of spawning its projectiles by hand
+*/
+
+// The shape of a single volley, relative to the direction of the target
+#[derive(Clone, Copy, Debug)]
+pub enum PatternShape {
+    // A single shot straight at the target
+    Aimed,
+    // Shots evenly spaced around the full circle
+    Radial { count: u32 },
+    // Shots fanned out around the aim direction, `angle` degrees apart
+    Spread { count: u32, angle: f32 },
+    // Radial volleys that rotate by `turn` degrees each volley
+    Spiral { arms: u32, turn: f32 },
+    // Aimed shots that weave side to side
+    Wave {
+        count: u32,
+        amplitude: f32,
+        frequency: f32,
+    },
+    // Shots that burst outwards, then track the target after `delay` seconds
+    DelayedHoming {
+        count: u32,
+        delay: f32,
+        turn_rate: f32,
+    },
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct BulletPattern {
+    pub shape: PatternShape,
+    pub volleys: u32,
+    pub volley_delay: f32,
+    pub speed: f32,
+    pub dmg: HealthType,
+}
+
+pub const PATTERN_PROJECTILE_RADIUS: f32 = 15.;
+// How far ahead of the shooter a pattern's telegraph reaches
+pub const PATTERN_TELEGRAPH_RANGE: f32 = 200.;
+
+// Patterns get one tier harder for every interval survived
+pub const PATTERN_SCALING_INTERVAL: f32 = 120.;
+pub const MAX_PATTERN_TIER: u32 = 5;
+// Gap between the volleys scaling adds to a pattern that only fired one at a time
+const MIN_VOLLEY_DELAY: f32 = 0.25;
+
+impl BulletPattern {
+    pub fn new(shape: PatternShape, speed: f32) -> BulletPattern {
+        BulletPattern {
+            shape,
+            volleys: 1,
+            volley_delay: 0.,
+            speed,
+            dmg: 1,
+        }
+    }
+
+    pub fn with_volleys(mut self, volleys: u32, volley_delay: f32) -> BulletPattern {
+        self.volleys = volleys;
+        self.volley_delay = volley_delay;
+        self
+    }
+
+    pub fn scaled(&self, elapsed: Duration) -> BulletPattern {
+        let tier = ((elapsed.as_secs_f32() / PATTERN_SCALING_INTERVAL) as u32).min(MAX_PATTERN_TIER);
+
+        let shape = match self.shape {
+            PatternShape::Aimed => PatternShape::Aimed,
+            PatternShape::Radial { count } => PatternShape::Radial {
+                count: count + 2 * tier,
+            },
+            PatternShape::Spread { count, angle } => PatternShape::Spread {
+                count: count + tier,
+                angle,
+            },
+            PatternShape::Spiral { arms, turn } => PatternShape::Spiral {
+                arms: arms + tier / 2,
+                turn,
+            },
+            PatternShape::Wave {
+                count,
+                amplitude,
+                frequency,
+            } => PatternShape::Wave {
+                count: count + tier / 2,
+                amplitude,
+                frequency,
+            },
+            PatternShape::DelayedHoming {
+                count,
+                delay,
+                turn_rate,
+            } => PatternShape::DelayedHoming {
+                count: count + tier / 2,
+                delay,
+                turn_rate: turn_rate * (1. + 0.2 * tier as f32),
+            },
+        };
+
+        // A zero delay would fire the extra volleys on consecutive frames, stacked into one
+        let extra_volleys = tier / 2;
+        let volley_delay = match extra_volleys > 0 {
+            true => self.volley_delay.max(MIN_VOLLEY_DELAY),
+            false => self.volley_delay,
+        };
+
+        BulletPattern {
+            shape,
+            volleys: self.volleys + extra_volleys,
+            volley_delay,
+            speed: self.speed * (1. + 0.1 * tier as f32),
+            dmg: self.dmg,
+        }
+    }
+
+    // The ground indicator warning of this pattern before it is fired
+    pub fn telegraph(&self) -> TelegraphShape {
+        let collider = Collider::new_circle(PATTERN_PROJECTILE_RADIUS);
+
+        match self.shape {
+            PatternShape::Aimed | PatternShape::Wave { .. } => {
+                TelegraphShape::swept(&collider, PATTERN_TELEGRAPH_RANGE)
+            }
+            PatternShape::Spread { count, angle } => TelegraphShape::Cone {
+                length: PATTERN_TELEGRAPH_RANGE,
+                angle: angle * (count - 1) as f32,
+            },
+            PatternShape::Radial { .. }
+            | PatternShape::Spiral { .. }
+            | PatternShape::DelayedHoming { .. } => TelegraphShape::Circle {
+                radius: PATTERN_TELEGRAPH_RANGE / 2.,
+            },
+        }
+    }
+
+    // The directions of each shot in the given volley, aim being the direction of the target
+    pub fn directions(&self, aim: Radian, volley: u32) -> Vec<Radian> {
+        match self.shape {
+            PatternShape::Aimed => vec![aim],
+            PatternShape::Radial { count } => (0..count)
+                .map(|i| aim + Radian::FULL * (i as f32 / count as f32))
+                .collect(),
+            PatternShape::Spread { count, angle } => (0..count)
+                .map(|i| {
+                    aim + Radian::from_degrees(angle) * ((count - 1) as f32 / -2. + i as f32)
+                })
+                .collect(),
+            PatternShape::Spiral { arms, turn } => (0..arms)
+                .map(|i| {
+                    aim + Radian::from_degrees(turn * volley as f32)
+                        + Radian::FULL * (i as f32 / arms as f32)
+                })
+                .collect(),
+            PatternShape::Wave { count, .. } => (0..count).map(|_| aim).collect(),
+            PatternShape::DelayedHoming { count, .. } => (0..count)
+                .map(|i| aim + Radian::FULL * (i as f32 / count as f32))
+                .collect(),
+        }
+    }
+}
+
+pub struct PatternEntry {
+    // Seconds into the run before this pattern can be picked
+    pub unlocks_at: f32,
+    pub pattern: BulletPattern,
+}
+
+pub fn pattern_library(enemy_type: EnemyType) -> Vec<PatternEntry> {
+    match enemy_type {
+        EnemyType::Beholder => vec![
+            PatternEntry {
+                unlocks_at: 0.,
+                pattern: BulletPattern::new(PatternShape::Aimed, 40.),
+            },
+            PatternEntry {
+                unlocks_at: 120.,
+                pattern: BulletPattern::new(
+                    PatternShape::Spread {
+                        count: 3,
+                        angle: 10.,
+                    },
+                    40.,
+                ),
+            },
+            PatternEntry {
+                unlocks_at: 240.,
+                pattern: BulletPattern::new(
+                    PatternShape::Wave {
+                        count: 1,
+                        amplitude: 30.,
+                        frequency: 4.,
+                    },
+                    40.,
+                )
+                .with_volleys(3, 0.3),
+            },
+        ],
+        EnemyType::BeholderPrince => vec![
+            PatternEntry {
+                unlocks_at: 0.,
+                pattern: BulletPattern::new(
+                    PatternShape::Spread {
+                        count: 3,
+                        angle: 10.,
+                    },
+                    40.,
+                ),
+            },
+            PatternEntry {
+                unlocks_at: 0.,
+                pattern: BulletPattern::new(PatternShape::Radial { count: 8 }, 35.),
+            },
+            PatternEntry {
+                unlocks_at: 120.,
+                pattern: BulletPattern::new(
+                    PatternShape::Spiral {
+                        arms: 4,
+                        turn: 15.,
+                    },
+                    35.,
+                )
+                .with_volleys(6, 0.15),
+            },
+            PatternEntry {
+                unlocks_at: 180.,
+                pattern: BulletPattern::new(
+                    PatternShape::Wave {
+                        count: 3,
+                        amplitude: 40.,
+                        frequency: 3.,
+                    },
+                    40.,
+                ),
+            },
+            PatternEntry {
+                unlocks_at: 240.,
+                pattern: BulletPattern::new(
+                    PatternShape::DelayedHoming {
+                        count: 3,
+                        delay: 1.,
+                        turn_rate: 90.,
+                    },
+                    30.,
+                ),
+            },
+        ],
+        _ => vec![],
+    }
+}
+
+#[derive(Resource)]
+pub struct PatternRNG(pub RNG);
+
+pub fn spawn_pattern_rng(seed: Res<GlobalSeed>, mut commands: Commands) {
+    commands.insert_resource(PatternRNG(RNG::new(&seed.0, "patterns")))
+}
+
+// Picks a pattern for the enemy type that is unlocked at the given point in the run
+pub fn choose_pattern(
+    enemy_type: EnemyType,
+    elapsed: Duration,
+    rng: &mut PatternRNG,
+) -> Option<BulletPattern> {
+    let unlocked: Vec<BulletPattern> = pattern_library(enemy_type)
+        .into_iter()
+        .filter(|entry| entry.unlocks_at <= elapsed.as_secs_f32())
+        .map(|entry| entry.pattern)
+        .collect();
+
+    unlocked
+        .choose(&mut rng.0 .0)
+        .map(|pattern| pattern.scaled(elapsed))
+}
+
+/*
+Fires the volleys of a pattern from the entity it is attached to
+*/
+#[derive(Component)]
+pub struct PatternEmitter {
+    pub pattern: BulletPattern,
+    volleys_fired: u32,
+    timer: Timer,
+}
+
+impl PatternEmitter {
+    pub fn new(pattern: BulletPattern) -> PatternEmitter {
+        PatternEmitter {
+            pattern,
+            volleys_fired: 0,
+            timer: Timer::from_seconds(pattern.volley_delay, TimerMode::Repeating),
+        }
+    }
+}
+
+#[derive(Component)]
+pub struct WaveMotion {
+    direction: Vec2,
+    speed: f32,
+    amplitude: f32,
+    frequency: f32,
+    phase: f32,
+    elapsed: f32,
+}
+
+#[derive(Component)]
+pub struct PatternHoming {
+    delay: Timer,
+    // In degrees per second
+    turn_rate: f32,
+}
+
+pub fn pattern_emitter_update(
+    mut q_emitters: Query<(Entity, &Transform, &mut PatternEmitter), Without<Player>>,
+    q_player: Query<&Transform, With<Player>>,
+    animations: Res<AnimationStateStorage<BeholderProjectileAnimation>>,
+    textures: Res<TextureAssets>,
+    mut texture_atlases: ResMut<Assets<TextureAtlas>>,
+    fx: Res<FXChannel>,
+    audio: Res<AudioAssets>,
+    clock: Res<GameClock>,
+    mut pool: ResMut<EntityPool>,
+    mut commands: Commands,
+) {
+    let player_transform = q_player.single();
+
+    for (entity, transform, mut emitter) in q_emitters.iter_mut() {
+        emitter.timer.tick(clock.delta());
+
+        if emitter.volleys_fired > 0 && !emitter.timer.just_finished() {
+            continue;
+        }
+
+        let direction = player_transform.translation.truncate() - transform.translation.truncate();
+        // obtain angle to target with respect to x-axis.
+        let angle_to_target = Radian::from(direction.y.atan2(direction.x) - PI / 2.);
+
+        let texture_atlas = TextureAtlas::from_grid(
+            textures.beholder_projectile.clone(),
+            Vec2 { x: 32., y: 32. },
+            4,
+            1,
+            None,
+            None,
+        );
+        let texture_atlas_handle = texture_atlases.add(texture_atlas);
+
+        fx.play(audio.fireball.clone());
+
+        let pattern = emitter.pattern;
+        let directions = pattern.directions(angle_to_target, emitter.volleys_fired);
+        let shot_count = directions.len();
+
+        for (index, shot_direction) in directions.into_iter().enumerate() {
+            let direction_vec = shot_direction.unit_vector();
+
+            let mut projectile = pool.spawn(PoolKind::EnemyBullet, &mut commands);
+
+            projectile
+                .insert(make_animation_bundle(
+                    BeholderProjectileAnimation::Flying,
+                    &animations,
+                    texture_atlas_handle.clone(),
+                    transform.translation,
+                    1.,
+                ))
+                .insert(Projectile {
+                    dmg: pattern.dmg,
+                    damage_target: DamageTarget::Team(Team::Player),
+                    piercing_mode: PiercingMode::None,
+                    entities_hit: vec![],
+                    is_alive: true,
+                    source: DamageSource::Enemy,
+                })
+                .insert(Velocity {
+                    vec: direction_vec * pattern.speed,
+                })
+                .insert(Collider::new_circle(PATTERN_PROJECTILE_RADIUS))
+                .insert(MaxRange::new(transform.translation, PROJECTILE_RANGE));
+
+            match pattern.shape {
+                PatternShape::Wave {
+                    amplitude,
+                    frequency,
+                    ..
+                } => {
+                    projectile.insert(WaveMotion {
+                        direction: direction_vec,
+                        speed: pattern.speed,
+                        amplitude,
+                        frequency,
+                        phase: Radian::FULL.angle * index as f32 / shot_count as f32,
+                        elapsed: 0.,
+                    });
+                }
+                PatternShape::DelayedHoming {
+                    delay, turn_rate, ..
+                } => {
+                    projectile.insert(PatternHoming {
+                        delay: Timer::from_seconds(delay, TimerMode::Once),
+                        turn_rate,
+                    });
+                }
+                _ => (),
+            }
+        }
+
+        emitter.volleys_fired += 1;
+
+        if emitter.volleys_fired >= pattern.volleys {
+            commands.entity(entity).remove::<PatternEmitter>();
+        }
+    }
+}
+
+pub fn wave_motion_update(
+    mut q_waves: Query<(&mut WaveMotion, &mut Velocity)>,
+    clock: Res<GameClock>,
+) {
+    for (mut wave, mut velocity) in q_waves.iter_mut() {
+        wave.elapsed += clock.delta_seconds();
+
+        // The lateral velocity is the derivative of a sine offset
+        let lateral = wave.amplitude
+            * wave.frequency
+            * (wave.frequency * wave.elapsed + wave.phase).cos();
+
+        velocity.vec = wave.direction * wave.speed + wave.direction.perp() * lateral;
+    }
+}
+
+pub fn pattern_homing_update(
+    mut q_homing: Query<(&mut PatternHoming, &Transform, &mut Velocity), Without<Player>>,
+    q_player: Query<&Transform, With<Player>>,
+    clock: Res<GameClock>,
+) {
+    let player_transform = q_player.single();
+
+    for (mut homing, transform, mut velocity) in q_homing.iter_mut() {
+        homing.delay.tick(clock.delta());
+
+        if !homing.delay.finished() {
+            continue;
+        }
+
+        let direction = player_transform.translation.truncate() - transform.translation.truncate();
+        let current = velocity.vec.y.atan2(velocity.vec.x);
+        let desired = direction.y.atan2(direction.x);
+        let diff = Radian::from(desired - current).normalize_to_half().angle;
+        let max_turn = homing.turn_rate.to_radians() * clock.delta_seconds();
+        let new_angle = current + diff.clamp(-max_turn, max_turn);
+
+        velocity.vec = Vec2::new(new_angle.cos(), new_angle.sin()) * velocity.vec.length();
+    }
+}
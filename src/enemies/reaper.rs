@@ -12,8 +12,9 @@ use crate::{
     audio::FXChannel,
     collision::collider::Collider,
     combat::{
-        health::Health,
+        health::{DamageSource, Health},
         healthbar::{HealthBar, HEALTH_BAR_SEGMENTS},
+        lifetime::Lifetime,
         projectile::{DamageTarget, PiercingMode, Projectile},
         teams::{Team, TeamMember},
         z_sort::ZSort,
@@ -21,14 +22,18 @@ use crate::{
     constants::SortingLayers,
     loading::{AudioAssets, TextureAssets},
     movement::velocity::Velocity,
-    player::Player, util::with_z::WithZ,
+    player::Player, util::{despawn_on_exit::DespawnOnExit, with_z::WithZ},
 };
 
 use super::{
+    affix::Affixes,
     ai::{ChargeShootEvent, MoveAndShootAI, ShootEvent},
     enemy::{Enemy, EnemyBundle, EnemyType}, spawning::EnemySpawnEvent,
+    telegraph::{Telegraph, TelegraphShape},
 };
 
+pub const REAPER_BLADE_RADIUS: f32 = 60.;
+
 #[derive(PartialEq, Eq, Hash, Clone, Copy)]
 pub enum ReaperAnimation {
     Flying,
@@ -66,28 +71,23 @@ impl Animation<ReaperBladeAnimation> for ReaperBladeAnimation {
 #[derive(Component)]
 pub struct ReaperBlade {
     pub parent: Entity,
-    pub timer: Timer,
 }
 
+// Runs after the lifetimes tick, the blade itself goes away with its lifetime
 pub fn reaper_blade_update(
-    mut q_blade: Query<(Entity, &mut ReaperBlade)>,
-    mut q_ai: Query<&mut MoveAndShootAI, Without<ReaperBlade>>,
-    time: Res<Time>,
+    q_blade: Query<(&ReaperBlade, &Lifetime)>,
+    mut q_ai: Query<(&mut MoveAndShootAI, Option<&Affixes>), Without<ReaperBlade>>,
     mut animate: EventWriter<AnimationStateChangeEvent<ReaperAnimation>>,
-    mut commands: Commands,
 ) {
-    for (entity, mut blade) in q_blade.iter_mut() {
-        blade.timer.tick(time.delta());
-
-        if blade.timer.just_finished() {
+    for (blade, lifetime) in q_blade.iter() {
+        if lifetime.0.just_finished() {
             animate.send(AnimationStateChangeEvent {
                 id: blade.parent,
                 state_id: ReaperAnimation::Flying,
             });
-            commands.entity(entity).despawn_recursive();
 
-            if let Ok(mut ai) = q_ai.get_mut(blade.parent) {
-                ai.speed = 40.;
+            if let Ok((mut ai, affixes)) = q_ai.get_mut(blade.parent) {
+                ai.speed = 40. * affixes.map_or(1., |affixes| affixes.speed_mult());
             }
         }
     }
@@ -153,18 +153,18 @@ pub fn reaper_update(
                     transform.translation,
                     1.2,
                 ))
-                .insert(ReaperBlade {
-                    parent: entity,
-                    timer: Timer::from_seconds(0.5, TimerMode::Once),
-                })
+                .insert(ReaperBlade { parent: entity })
+                .insert(DespawnOnExit::run())
+                .insert(Lifetime::from_seconds(0.5))
                 .insert(Projectile {
                     dmg: 1,
                     damage_target: DamageTarget::Team(Team::Player),
                     piercing_mode: PiercingMode::All,
                     entities_hit: vec![],
                     is_alive: true,
+                    source: DamageSource::Enemy,
                 })
-                .insert(Collider::new_circle(60.));
+                .insert(Collider::new_circle(REAPER_BLADE_RADIUS));
         }
     }
 }
@@ -216,8 +216,14 @@ pub fn spawn_reaper(
                 health: Health::new(300),
                 collider: Collider::new_circle(30.),
                 team: TeamMember { team: Team::Enemy },
+                scope: DespawnOnExit::run(),
             })
+            .insert(Affixes(spawn_ev.affixes.clone()))
             .insert(MoveAndShootAI::new(40., 10., 60., 1. / 2., 2.))
+            .insert(Telegraph {
+                shape: TelegraphShape::from_collider(&Collider::new_circle(REAPER_BLADE_RADIUS)),
+                aims_at_player: false,
+            })
             .insert(make_animation_bundle(
                 ReaperAnimation::Flying,
                 &animations,
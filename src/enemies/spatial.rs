@@ -0,0 +1,42 @@
+use std::collections::HashMap;
+
+use bevy::prelude::*;
+
+use crate::collision::collider::vec2_to_spatial_coord;
+
+use super::enemy::Enemy;
+
+/*
+Enemies bucketed by the same grid as the collision system, so looking for
+enemies around a point only goes through the nearby cells
+*/
+#[derive(Resource, Default)]
+pub struct EnemyGrid(HashMap<(i32, i32), Vec<Entity>>);
+
+impl EnemyGrid {
+    // Enemies in the cells overlapping the circle, some can be a bit outside of it
+    pub fn around(&self, center: Vec2, radius: f32) -> impl Iterator<Item = Entity> + '_ {
+        let (min_x, min_y) = vec2_to_spatial_coord(center - Vec2::splat(radius));
+        let (max_x, max_y) = vec2_to_spatial_coord(center + Vec2::splat(radius));
+
+        (min_x..=max_x)
+            .flat_map(move |x| (min_y..=max_y).map(move |y| (x, y)))
+            .filter_map(|coord| self.0.get(&coord))
+            .flatten()
+            .copied()
+    }
+}
+
+pub fn update_enemy_grid(
+    mut grid: ResMut<EnemyGrid>,
+    q_enemies: Query<(Entity, &Transform), With<Enemy>>,
+) {
+    grid.0.clear();
+
+    for (entity, transform) in q_enemies.iter() {
+        grid.0
+            .entry(vec2_to_spatial_coord(transform.translation.truncate()))
+            .or_default()
+            .push(entity);
+    }
+}
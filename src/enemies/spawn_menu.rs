@@ -120,7 +120,7 @@ fn spawn_button_update(
 ) {
     for clicked in clicked_ev.iter() {
         if let Ok(button) = q_buttons.get(clicked.entity) {
-            spawn_ev.send(EnemySpawnEvent { enemy_type: button.0, position: default() });
+            spawn_ev.send(EnemySpawnEvent { enemy_type: button.0, position: default(), affixes: vec![] });
             next_state.set(SpawnMenuState::Off);
         }
     }
@@ -7,11 +7,14 @@ use crate::{
     animation::AnimationStateStorage,
     constants::SortingLayers,
     loading::TextureAssets,
-    movement::pause::ActionPauseState,
-    util::rng::{GlobalSeed, RNG},
+    util::{
+        clock::GameClock,
+        rng::{GlobalSeed, RNG},
+    },
 };
 
 use super::{
+    affix::{affix_names, roll_affixes, Affix, AffixRNG},
     beholder::{spawn_beholder, spawn_beholder_prince, BeholderAnimation},
     enemy::EnemyType,
     imp::{spawn_imp, spawn_imp_queen, ImpAnimation},
@@ -29,6 +32,7 @@ pub struct SpawnInfo {
 pub struct EnemySpawnEvent {
     pub enemy_type : EnemyType,
     pub position : Vec2,
+    pub affixes : Vec<Affix>,
 }
 
 #[derive(Resource)]
@@ -48,18 +52,14 @@ pub fn spawn_spawn_rng(
 pub fn spawn_loop(
     q_window: Query<&Window, With<PrimaryWindow>>,
     mut spawn_info: ResMut<SpawnInfo>,
-    time: Res<Time>,
-    pause: Res<ActionPauseState>,
+    clock: Res<GameClock>,
     mut rng: ResMut<SpawningRNG>,
+    mut affix_rng: ResMut<AffixRNG>,
     mut spawn_ev : EventWriter<EnemySpawnEvent>,
 ) {
-    if pause.is_paused {
-        return;
-    }
-
     let window = q_window.single();
-    spawn_info.timer.tick(time.delta());
-    spawn_info.game.tick(time.delta());
+    spawn_info.timer.tick(clock.delta());
+    spawn_info.game.tick(clock.delta());
 
     let scaling_factor: f32 = 1.01;
     let needed_difficulty =
@@ -116,8 +116,9 @@ pub fn spawn_loop(
             match available.choose(&mut rng.0 .0) {
                 Some(enemy) => {
                     curr_difficulty += enemy.difficulty();
-                    info!("Spawning {:?}, difficult {}/{}", enemy, curr_difficulty, needed_difficulty);
-                    spawn_ev.send(EnemySpawnEvent { enemy_type: *enemy, position: position.truncate() });
+                    let affixes = roll_affixes(spawn_info.game.elapsed(), &mut affix_rng);
+                    info!("Spawning {} {:?}, difficult {}/{}", affix_names(&affixes), enemy, curr_difficulty, needed_difficulty);
+                    spawn_ev.send(EnemySpawnEvent { enemy_type: *enemy, position: position.truncate(), affixes });
                 }
                 None => return,
             }
@@ -0,0 +1,327 @@
+use std::{f32::consts::PI, time::Duration};
+
+use bevy::{prelude::*, sprite::Anchor};
+
+use crate::{
+    collision::collider::{Collider, ColliderShape},
+    constants::SortingLayers,
+    loading::DebugTextureAssets,
+    palette::Palette,
+    player::Player,
+    util::{clock::GameClock, despawn_on_exit::DespawnOnExit, radians::Radian},
+    GameState,
+};
+
+use super::ai::{ChargeShootEvent, MoveAndShootAI};
+
+pub struct TelegraphPlugin;
+
+impl Plugin for TelegraphPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<TelegraphEvent>().add_systems(
+            Update,
+            (
+                telegraph_on_charge,
+                spawn_telegraphs.after(telegraph_on_charge),
+                telegraph_update,
+            )
+                .run_if(in_state(GameState::Playing)),
+        );
+    }
+}
+
+/*
+Ground indicators warning the player of where an attack is about to land.
+They are drawn around their owner and fill up until the attack goes off
+*/
+#[derive(Clone, Copy, Debug)]
+pub enum TelegraphShape {
+    Circle { radius: f32 },
+    // Starts at the owner and extends towards the aim
+    Line { length: f32, width: f32 },
+    // Centered on the aim, `angle` degrees wide
+    Cone { length: f32, angle: f32 },
+}
+
+impl TelegraphShape {
+    // The area covered by the collider itself, e.g. a blade swinging around its owner
+    pub fn from_collider(collider: &Collider) -> TelegraphShape {
+        match collider.shape() {
+            ColliderShape::Circle(radius) => TelegraphShape::Circle { radius },
+            ColliderShape::Rect(size) => TelegraphShape::Line {
+                length: size.y,
+                width: size.x,
+            },
+        }
+    }
+
+    // The path a projectile with this collider sweeps over `length` units of travel
+    pub fn swept(collider: &Collider, length: f32) -> TelegraphShape {
+        TelegraphShape::Line {
+            length,
+            width: match collider.shape() {
+                ColliderShape::Circle(radius) => radius * 2.,
+                ColliderShape::Rect(size) => size.x,
+            },
+        }
+    }
+}
+
+const TELEGRAPH_TEXTURE_SIZE: f32 = 128.;
+const CONE_RAY_WIDTH: f32 = 6.;
+// Degrees between each ray drawn for a cone
+const CONE_RAY_SPACING: f32 = 10.;
+
+#[derive(Clone, Copy, PartialEq)]
+enum PieceKind {
+    Circle,
+    Ray,
+}
+
+impl PieceKind {
+    fn anchor(&self) -> Anchor {
+        match self {
+            PieceKind::Circle => Anchor::Center,
+            PieceKind::Ray => Anchor::BottomCenter,
+        }
+    }
+}
+
+// A single sprite of a telegraph, relative to the telegraph's position and aim
+struct TelegraphPiece {
+    kind: PieceKind,
+    size: Vec2,
+    rotation: f32,
+}
+
+impl TelegraphShape {
+    fn pieces(&self) -> Vec<TelegraphPiece> {
+        match *self {
+            TelegraphShape::Circle { radius } => vec![TelegraphPiece {
+                kind: PieceKind::Circle,
+                size: Vec2::splat(radius * 2.),
+                rotation: 0.,
+            }],
+            TelegraphShape::Line { length, width } => vec![TelegraphPiece {
+                kind: PieceKind::Ray,
+                size: Vec2 {
+                    x: width,
+                    y: length,
+                },
+                rotation: 0.,
+            }],
+            TelegraphShape::Cone { length, angle } => {
+                let rays = ((angle / CONE_RAY_SPACING) as u32).max(2) + 1;
+
+                (0..rays)
+                    .map(|i| TelegraphPiece {
+                        kind: PieceKind::Ray,
+                        size: Vec2 {
+                            x: CONE_RAY_WIDTH,
+                            y: length,
+                        },
+                        rotation: Radian::from_degrees(
+                            angle * (i as f32 / (rays - 1) as f32 - 0.5),
+                        )
+                        .angle,
+                    })
+                    .collect()
+            }
+        }
+    }
+}
+
+/*
+Any attack can warn the player by sending one of these, usually when it starts charging
+*/
+#[derive(Event)]
+pub struct TelegraphEvent {
+    pub owner: Entity,
+    pub shape: TelegraphShape,
+    pub duration: Duration,
+    // The telegraph keeps aiming at this entity, if any
+    pub target: Option<Entity>,
+}
+
+// Registers the telegraph an enemy shows while its MoveAndShootAI charges
+#[derive(Component, Clone, Copy)]
+pub struct Telegraph {
+    pub shape: TelegraphShape,
+    pub aims_at_player: bool,
+}
+
+#[derive(Component)]
+pub struct TelegraphIndicator {
+    pub owner: Entity,
+    pub target: Option<Entity>,
+    pub timer: Timer,
+}
+
+#[derive(Component)]
+struct TelegraphFill {
+    full_scale: Vec3,
+    kind: PieceKind,
+}
+
+fn telegraph_on_charge(
+    q_telegraphs: Query<(&Telegraph, &MoveAndShootAI)>,
+    q_player: Query<Entity, With<Player>>,
+    mut charge_ev: EventReader<ChargeShootEvent>,
+    mut telegraph_ev: EventWriter<TelegraphEvent>,
+) {
+    for charge in charge_ev.iter() {
+        if let Ok((telegraph, ai)) = q_telegraphs.get(charge.entity) {
+            telegraph_ev.send(TelegraphEvent {
+                owner: charge.entity,
+                shape: telegraph.shape,
+                duration: ai.charge_timer.duration(),
+                target: match telegraph.aims_at_player {
+                    true => q_player.get_single().ok(),
+                    false => None,
+                },
+            });
+        }
+    }
+}
+
+fn aim_rotation(from: Vec3, to: Vec3) -> Quat {
+    let direction = to.truncate() - from.truncate();
+    // obtain angle to target with respect to x-axis.
+    let angle_to_target = Radian::from(direction.y.atan2(direction.x) - PI / 2.);
+    Quat::from_rotation_z(angle_to_target.angle)
+}
+
+fn spawn_telegraphs(
+    q_transforms: Query<&Transform>,
+    mut telegraph_ev: EventReader<TelegraphEvent>,
+    textures: Res<DebugTextureAssets>,
+    palette: Res<Palette>,
+    mut commands: Commands,
+) {
+    for telegraph in telegraph_ev.iter() {
+        let owner_transform = match q_transforms.get(telegraph.owner) {
+            Ok(transform) => transform,
+            Err(_) => continue,
+        };
+
+        let rotation = match telegraph.target.map(|target| q_transforms.get(target)) {
+            Some(Ok(target_transform)) => {
+                aim_rotation(owner_transform.translation, target_transform.translation)
+            }
+            _ => Quat::IDENTITY,
+        };
+
+        commands
+            .spawn(SpatialBundle {
+                transform: Transform {
+                    translation: owner_transform
+                        .translation
+                        .truncate()
+                        .extend(SortingLayers::BehindAction.into()),
+                    rotation,
+                    ..default()
+                },
+                ..default()
+            })
+            .insert(TelegraphIndicator {
+                owner: telegraph.owner,
+                target: telegraph.target,
+                timer: Timer::new(telegraph.duration, TimerMode::Once),
+            })
+            .insert(DespawnOnExit::run())
+            .with_children(|parent| {
+                for piece in telegraph.shape.pieces() {
+                    let texture = match piece.kind {
+                        PieceKind::Circle => textures.circle.clone(),
+                        PieceKind::Ray => textures.rect.clone(),
+                    };
+                    let full_scale = (piece.size / TELEGRAPH_TEXTURE_SIZE).extend(1.);
+
+                    parent.spawn(SpriteBundle {
+                        sprite: Sprite {
+                            color: palette.red.with_a(0.25),
+                            anchor: piece.kind.anchor(),
+                            ..default()
+                        },
+                        texture: texture.clone(),
+                        transform: Transform {
+                            rotation: Quat::from_rotation_z(piece.rotation),
+                            scale: full_scale,
+                            ..default()
+                        },
+                        ..default()
+                    });
+
+                    parent
+                        .spawn(SpriteBundle {
+                            sprite: Sprite {
+                                color: palette.red.with_a(0.5),
+                                anchor: piece.kind.anchor(),
+                                ..default()
+                            },
+                            texture,
+                            transform: Transform {
+                                translation: Vec3 {
+                                    x: 0.,
+                                    y: 0.,
+                                    z: 0.01,
+                                },
+                                rotation: Quat::from_rotation_z(piece.rotation),
+                                scale: Vec3::ZERO,
+                            },
+                            ..default()
+                        })
+                        .insert(TelegraphFill {
+                            full_scale,
+                            kind: piece.kind,
+                        });
+                }
+            });
+    }
+}
+
+fn telegraph_update(
+    mut q_telegraphs: Query<(Entity, &mut TelegraphIndicator, &mut Transform, &Children)>,
+    q_transforms: Query<&Transform, (Without<TelegraphIndicator>, Without<TelegraphFill>)>,
+    mut q_fills: Query<(&TelegraphFill, &mut Transform), Without<TelegraphIndicator>>,
+    clock: Res<GameClock>,
+    mut commands: Commands,
+) {
+    for (entity, mut telegraph, mut transform, children) in q_telegraphs.iter_mut() {
+        telegraph.timer.tick(clock.delta());
+
+        // The attack went off, or its owner died before it could
+        let owner_transform = match q_transforms.get(telegraph.owner) {
+            Ok(owner_transform) if !telegraph.timer.finished() => owner_transform,
+            _ => {
+                commands.entity(entity).despawn_recursive();
+                continue;
+            }
+        };
+
+        transform.translation.x = owner_transform.translation.x;
+        transform.translation.y = owner_transform.translation.y;
+
+        if let Some(Ok(target_transform)) =
+            telegraph.target.map(|target| q_transforms.get(target))
+        {
+            transform.rotation =
+                aim_rotation(owner_transform.translation, target_transform.translation);
+        }
+
+        let progress = telegraph.timer.percent();
+
+        for child in children.iter() {
+            if let Ok((fill, mut fill_transform)) = q_fills.get_mut(*child) {
+                fill_transform.scale = match fill.kind {
+                    PieceKind::Circle => fill.full_scale * progress,
+                    PieceKind::Ray => Vec3 {
+                        x: fill.full_scale.x,
+                        y: fill.full_scale.y * progress,
+                        z: 1.,
+                    },
+                };
+            }
+        }
+    }
+}
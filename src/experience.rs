@@ -1,16 +1,28 @@
 use bevy::prelude::*;
 
-use crate::{animation::AppAnimationSetup, GameState};
+use crate::{
+    animation::AppAnimationSetup,
+    movement::{
+        edge_teleport::edge_teleporting, fake_magnetic::fake_magnet_update,
+        velocity::velocity_update,
+    },
+    util::despawn_on_exit::AppDespawnOnExit,
+    GameState, InGame,
+};
 
 use self::{
     ability_selection::{
-        ability_frame_update, create_ability_selection_rng, on_select_ability,
-        start_ability_selection, AbilityFrameAnimation,
+        ability_frame_update, create_ability_selection_rng, grant_selection_charges,
+        on_select_ability, on_selection_action, open_ability_selection, queue_ability_selections,
+        start_ability_selection, tint_ability_frames, AbilityFrameAnimation, AbilityTakenEvent,
+        SelectionCharges,
     },
-    experience::{experience_update, LevelUpEvent},
+    experience::{experience_update, LevelUpEvent, PendingSelections},
     taken_abilities::{update_description, update_taken_positions},
     xp_bar::{manage_xp_bar_sprites, spawn_xp_bar, XPBarAnimation},
-    xp_crystal::{create_xp_crystal_rng, drop_crystals, xp_crystal_update},
+    xp_crystal::{
+        create_xp_crystal_rng, drop_crystals, merge_crystals, xp_crystal_update, XPCollectedEvent,
+    },
 };
 
 pub mod ability_selection;
@@ -31,23 +43,45 @@ impl Plugin for ExperiencePlugin {
                 spawn_xp_bar,
             ),
         )
+        // Play Again starts over from the seed, like a new run
+        .add_systems(
+            OnExit(InGame::GameOver),
+            (create_xp_crystal_rng, create_ability_selection_rng),
+        )
         .add_systems(
             Update,
             (
                 drop_crystals,
-                xp_crystal_update,
+                // After movement, so a crystal can never be both merged and collected
+                xp_crystal_update
+                    .after(velocity_update)
+                    .after(fake_magnet_update)
+                    .after(edge_teleporting),
+                merge_crystals.after(xp_crystal_update),
                 manage_xp_bar_sprites,
                 experience_update,
-                start_ability_selection,
+                queue_ability_selections.after(experience_update),
+                start_ability_selection
+                    .after(queue_ability_selections)
+                    .run_if(in_state(InGame::Running)),
                 ability_frame_update,
                 update_taken_positions,
-                on_select_ability,
+                on_select_ability.run_if(in_state(InGame::LevelUp)),
+                on_selection_action.run_if(in_state(InGame::LevelUp)),
+                grant_selection_charges,
+                tint_ability_frames,
                 update_description,
             )
                 .run_if(in_state(GameState::Playing)),
         )
+        .add_systems(OnEnter(InGame::LevelUp), open_ability_selection)
+        .despawn_on_exit(InGame::LevelUp)
+        .init_resource::<SelectionCharges>()
+        .init_resource::<PendingSelections>()
         .add_animation::<XPBarAnimation>()
         .add_animation::<AbilityFrameAnimation>()
-        .add_event::<LevelUpEvent>();
+        .add_event::<LevelUpEvent>()
+        .add_event::<XPCollectedEvent>()
+        .add_event::<AbilityTakenEvent>();
     }
 }
@@ -1,28 +1,48 @@
-use bevy::{prelude::*, sprite::Anchor, text::Text2dBounds, window::PrimaryWindow};
-use rand::seq::IteratorRandom;
+use std::time::Duration;
+
+use bevy::{
+    ecs::system::SystemParam, prelude::*, sprite::Anchor, text::Text2dBounds,
+    window::PrimaryWindow,
+};
+use rand::seq::{IteratorRandom, SliceRandom};
 
 use crate::{
+    actions::input_map::{ActionInput, InputAction, InputMap},
     animation::{
         controller::AnimationController,
         info::{AnimationInfoBuilder, AnimationStateInfo},
         make_animation_bundle, Animation, AnimationStateChangeEvent, AnimationStateStorage,
     },
     collision::collider::Collider,
-    combat::health::Health,
+    combat::health::{Health, HealthType},
     constants::SortingLayers,
     loading::{AbilityTextures, FontAssets},
-    movement::pause::ActionPauseState,
     palette::Palette,
-    player::{ability::Ability, Player},
+    pickups::ChestOpenedEvent,
+    player::{
+        ability::{
+            rarity::{build_path_steps, Rarity},
+            Ability,
+        },
+        Player,
+    },
     ui::{
         grid::{Grid, GridBundle},
+        clickable::{Clickable, ClickedEvent},
         hoverable::{HoveredEvent, UnhoveredEvent},
-        selection_group::{SelectionElement, SelectionEvent, SelectionGroup}, element::Sized, alignment::AlignedBundle,
+        selection_group::{SelectionElement, SelectionEvent, SelectionGroup}, element::{Sized, SizeConstraint, SizeVec2}, alignment::{AlignedBundle, Alignment, AnchorBundle}, offset::Offset,
     },
-    util::rng::{GlobalSeed, RNG},
+    util::{
+        despawn_on_exit::DespawnOnExit,
+        rng::{GlobalSeed, RNG},
+    },
+    InGame,
 };
 
-use super::{experience::LevelUpEvent, taken_abilities::spawn_taken};
+use super::{
+    experience::{Experience, LevelUpEvent, PendingSelections},
+    taken_abilities::spawn_taken,
+};
 
 #[derive(Component)]
 pub struct AbilitySelectionMenuItem;
@@ -30,22 +50,52 @@ pub struct AbilitySelectionMenuItem;
 #[derive(Resource)]
 pub struct AbilityRNG(pub RNG);
 
+#[derive(Event)]
+pub struct AbilityTakenEvent {
+    pub ability: Ability,
+}
+
 pub fn create_ability_selection_rng(seed: Res<GlobalSeed>, mut commands: Commands) {
     commands.insert_resource(AbilityRNG(RNG::new(seed.0.as_str(), "ability_rng")))
 }
 
+// Every rarity has its own frames, hovered or not
 #[derive(PartialEq, Eq, Hash, Clone, Copy)]
 pub enum AbilityFrameAnimation {
-    Hovered,
-    NonHovered,
+    Hovered(Rarity),
+    NonHovered(Rarity),
+}
+
+impl AbilityFrameAnimation {
+    pub fn rarity(&self) -> Rarity {
+        match self {
+            AbilityFrameAnimation::Hovered(rarity) | AbilityFrameAnimation::NonHovered(rarity) => {
+                *rarity
+            }
+        }
+    }
 }
 
 impl Animation<AbilityFrameAnimation> for AbilityFrameAnimation {
     fn get_states() -> Vec<AnimationStateInfo<AbilityFrameAnimation>> {
-        AnimationInfoBuilder::new()
-            .add_single(AbilityFrameAnimation::NonHovered)
-            .add_single(AbilityFrameAnimation::Hovered)
-            .build()
+        let mut builder = AnimationInfoBuilder::new();
+        builder
+            .add_single(AbilityFrameAnimation::NonHovered(Rarity::Common))
+            .add_single(AbilityFrameAnimation::Hovered(Rarity::Common));
+
+        // Rarer frames reuse the common ones, tinted in their rarity's color
+        for rarity in [Rarity::Rare, Rarity::Epic, Rarity::Legendary] {
+            builder
+                .add_reused_frames(
+                    AbilityFrameAnimation::NonHovered(rarity),
+                    0,
+                    1,
+                    Duration::ZERO,
+                )
+                .add_reused_frames(AbilityFrameAnimation::Hovered(rarity), 1, 1, Duration::ZERO);
+        }
+
+        builder.build()
     }
 }
 
@@ -56,43 +106,228 @@ pub fn ability_frame_update(
     mut unhover_events: EventReader<UnhoveredEvent>,
 ) {
     for hover_ev in hover_events.iter() {
-        if let Ok((entity, _)) = q_frames.get(hover_ev.entity) {
+        if let Ok((entity, controller)) = q_frames.get(hover_ev.entity) {
             animation_update.send(AnimationStateChangeEvent {
                 id: entity,
-                state_id: AbilityFrameAnimation::Hovered,
+                state_id: AbilityFrameAnimation::Hovered(controller.get_state().rarity()),
             });
         }
     }
 
     for unhover_ev in unhover_events.iter() {
-        if let Ok((entity, _)) = q_frames.get(unhover_ev.entity) {
+        if let Ok((entity, controller)) = q_frames.get(unhover_ev.entity) {
             animation_update.send(AnimationStateChangeEvent {
                 id: entity,
-                state_id: AbilityFrameAnimation::NonHovered,
+                state_id: AbilityFrameAnimation::NonHovered(controller.get_state().rarity()),
             });
         }
     }
 }
 
+// The tint goes with the frame's state, so it is set again whenever the state changes
+pub fn tint_ability_frames(
+    mut q_frames: Query<
+        (
+            &AnimationController<AbilityFrameAnimation>,
+            &mut TextureAtlasSprite,
+        ),
+        Changed<AnimationController<AbilityFrameAnimation>>,
+    >,
+) {
+    for (controller, mut sprite) in q_frames.iter_mut() {
+        sprite.color = controller.get_state().rarity().color();
+    }
+}
+
 #[derive(Component)]
 pub struct AbilitySelection {
     abilities: Vec<Ability>,
 }
 
+pub const BASE_REROLLS: u32 = 1;
+pub const BASE_BANISHES: u32 = 1;
+pub const SKIP_HEAL: HealthType = 1;
+// Part of the current threshold given back when skipping at full health
+pub const SKIP_XP_FRACTION: f32 = 0.25;
+
+/*
+What the player can do instead of simply picking, refilled at the start of every run
+*/
+#[derive(Resource, Default)]
+pub struct SelectionCharges {
+    pub rerolls: u32,
+    pub banishes: u32,
+    // Never offered again this run
+    pub banished: Vec<Ability>,
+}
+
+impl SelectionCharges {
+    pub fn new(extra_rerolls: u32) -> SelectionCharges {
+        SelectionCharges {
+            rerolls: BASE_REROLLS + extra_rerolls,
+            banishes: BASE_BANISHES,
+            banished: vec![],
+        }
+    }
+}
+
+#[derive(Component, Clone, Copy, PartialEq, Eq)]
+pub enum SelectionAction {
+    Reroll,
+    Banish,
+    Skip,
+}
+
+impl SelectionAction {
+    pub fn all() -> Vec<SelectionAction> {
+        vec![
+            SelectionAction::Reroll,
+            SelectionAction::Banish,
+            SelectionAction::Skip,
+        ]
+    }
+
+    pub fn action(&self) -> InputAction {
+        match self {
+            SelectionAction::Reroll => InputAction::Reroll,
+            SelectionAction::Banish => InputAction::Banish,
+            SelectionAction::Skip => InputAction::Skip,
+        }
+    }
+
+    pub fn label(&self, charges: &SelectionCharges, input_map: &InputMap) -> String {
+        let key = input_map.key_label(self.action());
+
+        match self {
+            SelectionAction::Reroll => format!("[{}] Reroll ({})", key, charges.rerolls),
+            SelectionAction::Banish => format!("[{}] Banish ({})", key, charges.banishes),
+            SelectionAction::Skip => format!("[{}] Skip", key),
+        }
+    }
+
+    pub fn is_available(&self, charges: &SelectionCharges) -> bool {
+        match self {
+            SelectionAction::Reroll => charges.rerolls > 0,
+            SelectionAction::Banish => charges.banishes > 0,
+            SelectionAction::Skip => true,
+        }
+    }
+}
+
+// Abilities that hand out extra charges when taken
+pub fn grant_selection_charges(
+    mut taken_ev: EventReader<AbilityTakenEvent>,
+    mut charges: ResMut<SelectionCharges>,
+) {
+    for taken in taken_ev.iter() {
+        if taken.ability == Ability::FickleFate {
+            charges.rerolls += 2;
+            charges.banishes += 1;
+        }
+    }
+}
+
+// Every draw goes through the ability RNG so a seed always offers the same picks
+fn roll_abilities(
+    player: &Player,
+    charges: &SelectionCharges,
+    excluded: &[Ability],
+    count: usize,
+    rng: &mut AbilityRNG,
+) -> Vec<Ability> {
+    let candidates: Vec<Ability> = Ability::all()
+        .into_iter()
+        .filter(|ability| {
+            ability.is_available(&player.abilities)
+                && player.character.allows(*ability)
+                && !charges.banished.contains(ability)
+                && !excluded.contains(ability)
+        })
+        .collect();
+
+    // One offer always continues a build path the player started, when there is one
+    let mut offers: Vec<Ability> = build_path_steps(&player.abilities)
+        .into_iter()
+        .filter(|step| candidates.contains(step))
+        .choose_multiple(&mut rng.0 .0, count.min(1));
+
+    let rest: Vec<Ability> = candidates
+        .into_iter()
+        .filter(|ability| !offers.contains(ability))
+        .collect();
+
+    if let Ok(chosen) = rest.choose_multiple_weighted(
+        &mut rng.0 .0,
+        count - offers.len(),
+        |ability| ability.rarity().weight(player.luck),
+    ) {
+        offers.extend(chosen.copied());
+    }
+
+    offers.shuffle(&mut rng.0 .0);
+    offers
+}
+
+// Gives the player an ability, wherever it came from
+pub fn take_ability(
+    ability: Ability,
+    player: &mut Player,
+    health: &mut Health,
+    window: &Window,
+    textures: &Res<AbilityTextures>,
+    taken_ev: &mut EventWriter<AbilityTakenEvent>,
+    commands: &mut Commands,
+) {
+    player.abilities.push(ability);
+    taken_ev.send(AbilityTakenEvent { ability });
+
+    spawn_taken(
+        ability,
+        player.abilities.len() - 1,
+        window,
+        textures,
+        commands,
+    );
+
+    if ability == Ability::MaxHp {
+        health.max += 1;
+        health.value += 1;
+    }
+
+    if ability == Ability::Potion {
+        health.value = health.max.min(health.value + 2);
+    }
+}
+
+#[derive(SystemParam)]
+pub struct SelectionAssets<'w> {
+    textures: Res<'w, AbilityTextures>,
+    frame_animations: Res<'w, AnimationStateStorage<AbilityFrameAnimation>>,
+    texture_atlases: ResMut<'w, Assets<TextureAtlas>>,
+    font_assets: Res<'w, FontAssets>,
+    palette: Res<'w, Palette>,
+    input_map: Res<'w, InputMap>,
+}
+
+fn close_ability_selection(
+    menu: Entity,
+    q_selection_items: &Query<Entity, With<AbilitySelectionMenuItem>>,
+    commands: &mut Commands,
+) {
+    commands.entity(menu).despawn_recursive();
+
+    for e in q_selection_items.iter() {
+        commands.entity(e).despawn_recursive();
+    }
+}
+
 pub fn on_select_ability(
-    q_menu: Query<(Entity, &AbilitySelection)>,
+    q_menu: Query<&AbilitySelection>,
     q_windows: Query<&Window, With<PrimaryWindow>>,
     mut q_player: Query<(&mut Player, &mut Health), Without<AbilitySelection>>,
-    q_selection_items: Query<
-        Entity,
-        (
-            With<AbilitySelectionMenuItem>,
-            Without<AbilitySelection>,
-            Without<Player>,
-        ),
-    >,
     mut selection_events: EventReader<SelectionEvent>,
-    mut pause: ResMut<ActionPauseState>,
+    mut taken_ev: EventWriter<AbilityTakenEvent>,
+    mut next_in_game: ResMut<NextState<InGame>>,
     textures: Res<AbilityTextures>,
     mut commands: Commands,
 ) {
@@ -100,110 +335,185 @@ pub fn on_select_ability(
     let window = q_windows.single();
 
     for selection_ev in selection_events.iter() {
-        if let Ok((entity, selection)) = q_menu.get(selection_ev.parent) {
-            let ability = selection.abilities[selection_ev.selected_index];
-            player.abilities.push(ability);
-
-            spawn_taken(
-                ability,
-                player.abilities.len() - 1,
+        if let Ok(selection) = q_menu.get(selection_ev.parent) {
+            take_ability(
+                selection.abilities[selection_ev.selected_index],
+                &mut player,
+                &mut health,
                 window,
                 &textures,
+                &mut taken_ev,
                 &mut commands,
             );
 
-            if ability == Ability::MaxHp {
-                health.max += 1;
-                health.value += 1;
-            }
+            next_in_game.set(InGame::Running);
+        }
+    }
+}
 
-            if ability == Ability::Potion {
-                health.value = health.max.min(health.value + 2);
-            }
+pub fn on_selection_action(
+    q_menu: Query<(Entity, &AbilitySelection, &SelectionGroup)>,
+    q_actions: Query<&SelectionAction>,
+    mut q_player: Query<(&Player, &mut Health, &mut Experience)>,
+    q_selection_items: Query<Entity, With<AbilitySelectionMenuItem>>,
+    mut clicked_ev: EventReader<ClickedEvent>,
+    input: ActionInput,
+    mut charges: ResMut<SelectionCharges>,
+    mut rng: ResMut<AbilityRNG>,
+    mut next_in_game: ResMut<NextState<InGame>>,
+    mut assets: SelectionAssets,
+    mut commands: Commands,
+) {
+    let Ok((menu, selection, group)) = q_menu.get_single() else {
+        return;
+    };
+
+    let clicked_action = clicked_ev
+        .iter()
+        .filter_map(|clicked| q_actions.get(clicked.entity).ok().copied())
+        .last();
+    let pressed_action = SelectionAction::all()
+        .into_iter()
+        .find(|action| input.just_pressed(action.action()));
+
+    let action = match clicked_action.or(pressed_action) {
+        Some(action) if action.is_available(&charges) => action,
+        _ => return,
+    };
+
+    let (player, mut health, mut experience) = q_player.single_mut();
+
+    match action {
+        SelectionAction::Reroll => {
+            charges.rerolls -= 1;
 
-            commands.entity(entity).despawn_recursive();
-            pause.is_paused = false;
+            let abilities = roll_abilities(player, &charges, &[], 3, &mut rng);
+            close_ability_selection(menu, &q_selection_items, &mut commands);
+            spawn_ability_selection(abilities, &charges, &mut assets, &mut commands);
+        }
+        SelectionAction::Banish => {
+            let Some(banished) = selection.abilities.get(group.hovered_index).copied() else {
+                return;
+            };
+
+            charges.banishes -= 1;
+            charges.banished.push(banished);
 
-            for e in q_selection_items.iter() {
-                commands.entity(e).despawn_recursive();
+            // Only the banished slot gets redrawn, the other options stay
+            let mut abilities = selection.abilities.clone();
+            match roll_abilities(player, &charges, &selection.abilities, 1, &mut rng).first() {
+                Some(replacement) => abilities[group.hovered_index] = *replacement,
+                None => {
+                    abilities.remove(group.hovered_index);
+                }
             }
+
+            close_ability_selection(menu, &q_selection_items, &mut commands);
+            spawn_ability_selection(abilities, &charges, &mut assets, &mut commands);
+        }
+        SelectionAction::Skip => {
+            match health.value < health.max {
+                true => health.value = health.max.min(health.value + SKIP_HEAL),
+                false => {
+                    experience.curr_experience +=
+                        (experience.threshold as f32 * SKIP_XP_FRACTION) as u32
+                }
+            }
+
+            next_in_game.set(InGame::Running);
         }
     }
 }
 
-pub fn start_ability_selection(
-    mut q_player: Query<&mut Player>,
-    q_windows: Query<&Window, With<PrimaryWindow>>,
+// Chests hand out a pick just like leveling up does
+pub fn queue_ability_selections(
     mut level_up_ev: EventReader<LevelUpEvent>,
-    textures: Res<AbilityTextures>,
-    frame_animations: Res<AnimationStateStorage<AbilityFrameAnimation>>,
-    mut texture_atlases: ResMut<Assets<TextureAtlas>>,
-    mut rng: ResMut<AbilityRNG>,
-    font_assets: Res<FontAssets>,
-    palette: Res<Palette>,
-    mut pause: ResMut<ActionPauseState>,
-    mut commands: Commands,
+    mut chest_ev: EventReader<ChestOpenedEvent>,
+    mut pending: ResMut<PendingSelections>,
 ) {
-    if level_up_ev.iter().len() == 0 {
-        return;
-    }
+    pending.0 += level_up_ev.iter().len() as u32 + chest_ev.iter().len() as u32;
+}
 
-    if pause.is_paused {
+// Only runs while the game is running, so the next one opens once the current selection is closed
+pub fn start_ability_selection(
+    mut pending: ResMut<PendingSelections>,
+    mut next_in_game: ResMut<NextState<InGame>>,
+) {
+    if pending.0 == 0 {
         return;
     }
 
-    pause.is_paused = true;
+    pending.0 -= 1;
+    next_in_game.set(InGame::LevelUp);
+}
 
-    let window = q_windows.single();
-    let player = q_player.single_mut();
+pub fn open_ability_selection(
+    q_player: Query<&Player>,
+    mut rng: ResMut<AbilityRNG>,
+    charges: Res<SelectionCharges>,
+    mut assets: SelectionAssets,
+    mut commands: Commands,
+) {
+    let player = q_player.single();
+    let abilities = roll_abilities(player, &charges, &[], 3, &mut rng);
+
+    spawn_ability_selection(abilities, &charges, &mut assets, &mut commands);
+}
 
+fn spawn_ability_selection(
+    abilities: Vec<Ability>,
+    charges: &SelectionCharges,
+    assets: &mut SelectionAssets,
+    commands: &mut Commands,
+) {
     let texture_atlas = TextureAtlas::from_grid(
-        textures.frame.clone(),
+        assets.textures.frame.clone(),
         Vec2 { x: 32., y: 32. },
         2,
         1,
         None,
         None,
     );
-    let texture_atlas_handle = texture_atlases.add(texture_atlas);
-
-    let all_abilities = Ability::all();
-    let chosen_abilities = all_abilities
-        .iter()
-        .filter(|ability| ability.is_available(&player.abilities))
-        .choose_multiple(&mut rng.0 .0, 3);
+    let texture_atlas_handle = assets.texture_atlases.add(texture_atlas);
+    let textures = &assets.textures;
+    let font_assets = &assets.font_assets;
+    let palette = &assets.palette;
 
     commands
         .spawn(GridBundle {
-            grid: Grid{ grid_size: IVec2 { x: 3, y: 1 } },
+            grid: Grid {
+                grid_size: IVec2 {
+                    x: abilities.len().max(1) as i32,
+                    y: 1,
+                },
+            },
             ..Default::default()
         })
         .insert(AbilitySelection {
-            abilities: chosen_abilities.iter().map(|a| **a).collect(),
+            abilities: abilities.clone(),
         })
         .insert(SelectionGroup {
             is_focused: true,
             hovered_index: 0,
             is_horizontal: true,
         })
+        .insert(DespawnOnExit(InGame::LevelUp))
         .with_children(|parent| {
-            for i in 0..3 {
+            for (i, ability) in abilities.iter().enumerate() {
                 parent
                     .spawn(make_animation_bundle(
                         match i {
-                            0 => AbilityFrameAnimation::Hovered,
-                            _ => AbilityFrameAnimation::NonHovered,
+                            0 => AbilityFrameAnimation::Hovered(ability.rarity()),
+                            _ => AbilityFrameAnimation::NonHovered(ability.rarity()),
                         },
-                        &frame_animations,
+                        &assets.frame_animations,
                         texture_atlas_handle.clone(),
                         Vec3::ZERO,
                         1.,
                     ))
                     .with_children(|parent| {
-                        let ability = chosen_abilities[i as usize];
-
                         parent.spawn(SpriteBundle {
-                            texture: ability.get_texture(&textures),
+                            texture: ability.get_texture(textures),
                             transform: Transform::from_translation(Vec3 {
                                 x: 0.,
                                 y: 0.,
@@ -271,9 +581,102 @@ pub fn start_ability_selection(
                             ..Default::default()
                         });
                     })
-                    .insert(SelectionElement { index: i as usize })
+                    .insert(SelectionElement { index: i })
                     .insert(Collider::new_rect(Vec2 { x: 64., y: 64. }))
                     .insert(AlignedBundle::default());
             }
         });
+
+    // Kept above the bottom of the screen, where thumbs can reach them on a phone
+    commands
+        .spawn(AnchorBundle::new(Alignment::BottomCenter))
+        .insert(Offset {
+            amount: SizeVec2 {
+                x: SizeConstraint::Const(0.),
+                y: SizeConstraint::Const(150.),
+            },
+        })
+        .insert(AbilitySelectionMenuItem)
+        .insert(DespawnOnExit(InGame::LevelUp))
+        .with_children(|parent| {
+            for (i, action) in SelectionAction::all().into_iter().enumerate() {
+                parent
+                    .spawn(Text2dBundle {
+                        text: Text::from_section(
+                            action.label(charges, &assets.input_map),
+                            TextStyle {
+                                font: font_assets.gothic_pxl.clone(),
+                                font_size: 32.,
+                                color: match action.is_available(charges) {
+                                    true => palette.white,
+                                    false => palette.red,
+                                },
+                            },
+                        )
+                        .with_alignment(TextAlignment::Center),
+                        transform: Transform {
+                            translation: Vec3 {
+                                x: (i as f32 - 1.) * 120.,
+                                y: 0.,
+                                z: SortingLayers::UI.into(),
+                            },
+                            rotation: default(),
+                            scale: Vec3 {
+                                x: 0.5,
+                                y: 0.5,
+                                z: 1.,
+                            },
+                        },
+                        ..Default::default()
+                    })
+                    .insert(action)
+                    .insert(Clickable::default())
+                    // Taller than the text so it's easy to tap
+                    .insert(Collider::new_rect(Vec2 { x: 110., y: 40. }));
+            }
+        });
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{player::character::Character, GameState};
+
+    use super::*;
+
+    fn first_offer(app: &mut App) -> Vec<Ability> {
+        let player = Player::new(Character::Hatman);
+        let charges = SelectionCharges::new(0);
+        let mut rng = app.world.resource_mut::<AbilityRNG>();
+
+        roll_abilities(&player, &charges, &[], 3, &mut rng)
+    }
+
+    #[test]
+    fn play_again_rolls_the_same_first_offer() {
+        let mut app = App::new();
+        app.add_state::<GameState>()
+            .add_state::<InGame>()
+            .insert_resource(GlobalSeed("dawn".to_string()))
+            .add_systems(OnEnter(GameState::Playing), create_ability_selection_rng)
+            .add_systems(OnExit(InGame::GameOver), create_ability_selection_rng);
+
+        app.world
+            .resource_mut::<NextState<GameState>>()
+            .set(GameState::Playing);
+        app.update();
+        let first_run = first_offer(&mut app);
+
+        app.world
+            .resource_mut::<NextState<InGame>>()
+            .set(InGame::GameOver);
+        app.update();
+        app.world
+            .resource_mut::<NextState<InGame>>()
+            .set(InGame::Running);
+        app.update();
+        let second_run = first_offer(&mut app);
+
+        assert_eq!(first_run.len(), 3);
+        assert!(first_run == second_run);
+    }
 }
@@ -10,6 +10,8 @@ pub struct Experience {
     pub level: u32,
     pub threshold: u32,
     pub pick_distance: f32,
+    // What multipliers added below a whole point, carried over to the next crystal
+    pub fraction: f32,
 }
 
 #[derive(Event)]
@@ -17,6 +19,39 @@ pub struct LevelUpEvent {
     pub new_level: u32,
 }
 
+/*
+Experience needed to get past each level, the table covers the first levels
+and every level after it takes `growth` times more than the one before
+*/
+pub struct XPCurve {
+    pub table: &'static [u32],
+    pub growth: f32,
+}
+
+pub const XP_CURVE: XPCurve = XPCurve {
+    table: &[20, 30, 45, 67, 100],
+    growth: 1.5,
+};
+
+impl XPCurve {
+    pub fn threshold(&self, level: u32) -> u32 {
+        let level = level as usize;
+
+        if let Some(threshold) = self.table.get(level) {
+            return *threshold;
+        }
+
+        let last = self.table.len() - 1;
+        (last..level).fold(self.table[last], |threshold, _| {
+            (threshold as f32 * self.growth) as u32
+        })
+    }
+}
+
+// Level-ups and chests waiting for their ability selection, shown one after another
+#[derive(Resource, Default)]
+pub struct PendingSelections(pub u32);
+
 pub fn experience_update(
     mut q_xp: Query<&mut Experience>,
     mut level_up_ev: EventWriter<LevelUpEvent>,
@@ -24,16 +59,22 @@ pub fn experience_update(
     audio: Res<AudioAssets>,
 ) {
     let mut xp = q_xp.single_mut();
+    let mut leveled_up = false;
 
-    if xp.curr_experience >= xp.threshold {
+    // A big burst of experience can be worth several levels at once
+    while xp.curr_experience >= xp.threshold {
         xp.curr_experience -= xp.threshold;
         xp.level += 1;
-        xp.threshold = (xp.threshold as f32 * 1.5) as u32;
+        xp.threshold = XP_CURVE.threshold(xp.level);
 
         level_up_ev.send(LevelUpEvent {
             new_level: xp.level,
         });
 
+        leveled_up = true;
+    }
+
+    if leveled_up {
         fx_channel.play(audio.levelup.clone());
     }
 }
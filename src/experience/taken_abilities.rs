@@ -10,7 +10,11 @@ use crate::{
     constants::SortingLayers,
     loading::{AbilityTextures, FontAssets},
     palette::Palette,
-    player::ability::Ability,
+    player::{
+        ability::{fusion::fusion_hints, Ability},
+        Player,
+    },
+    util::despawn_on_exit::DespawnOnExit,
 };
 
 #[derive(Component)]
@@ -37,9 +41,22 @@ pub fn spawn_taken(
             ..Default::default()
         })
         .insert(TakenAbility { index, ability })
+        .insert(DespawnOnExit::run())
         .insert(Collider::new_rect(Vec2 { x: 30., y: 30. }));
 }
 
+// The ability's own description, followed by the recipes it can still go into
+fn description_with_hints(ability: Ability, player_abilities: &[Ability]) -> String {
+    let mut description = ability.get_description();
+
+    for hint in fusion_hints(ability, player_abilities) {
+        description += "\n";
+        description += &hint;
+    }
+
+    description
+}
+
 #[derive(Component)]
 pub struct TakenTitle;
 
@@ -48,6 +65,7 @@ pub struct TakenDescription;
 
 pub fn update_description(
     q_windows: Query<&Window, With<PrimaryWindow>>,
+    q_player: Query<&Player>,
     q_takens: Query<(&Transform, &TakenAbility, &Collider), Without<Window>>,
     mut q_taken_title: Query<
         (Entity, &mut Text),
@@ -67,6 +85,7 @@ pub fn update_description(
     mut commands: Commands,
 ) {
     let window = q_windows.single();
+    let player = q_player.single();
 
     if let Some(cursor_position) = window.cursor_position() {
         let cursor_point = Vec2::new(
@@ -84,7 +103,7 @@ pub fn update_description(
                 q_taken_description.get_single_mut(),
             ) {
                 title.sections[0].value = taken.ability.get_name();
-                desc.sections[0].value = taken.ability.get_description();
+                desc.sections[0].value = description_with_hints(taken.ability, &player.abilities);
             } else {
                 let ability = taken.ability;
                 commands
@@ -121,7 +140,7 @@ pub fn update_description(
                 commands
                     .spawn(Text2dBundle {
                         text: Text::from_section(
-                            ability.get_description(),
+                            description_with_hints(ability, &player.abilities),
                             TextStyle {
                                 font: font_assets.garamond.clone(),
                                 font_size: 48.,
@@ -1,4 +1,4 @@
-use bevy::prelude::*;
+use bevy::{prelude::*, utils::HashMap};
 
 use bevy_kira_audio::AudioControl;
 use rand::Rng;
@@ -14,6 +14,7 @@ use crate::{
     },
     player::Player,
     util::{
+        despawn_on_exit::DespawnOnExit,
         radians::Radian,
         rng::{GlobalSeed, RNG},
     },
@@ -29,6 +30,12 @@ pub struct XPCrystal {
     contained_xp: u32,
 }
 
+#[derive(Event)]
+pub struct XPCollectedEvent {
+    pub amount: u32,
+    pub location: Vec3,
+}
+
 pub fn create_xp_crystal_rng(seed: Res<GlobalSeed>, mut commands: Commands) {
     commands.insert_resource(CrystalRNG(RNG::new(seed.0.as_str(), "crystal_rng")))
 }
@@ -43,6 +50,96 @@ pub struct XPCrystalBundle {
 }
 
 pub const BIG_XP_AMT: u32 = 40;
+pub const GREAT_XP_AMT: u32 = 200;
+// Past this many crystals, crystals lying close together start merging
+pub const MERGE_THRESHOLD: usize = 150;
+pub const MERGE_CELL_SIZE: f32 = 48.;
+// Past this many crystals, the ones furthest away fold into the overflow gem
+pub const CRYSTAL_CAP: usize = 300;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum CrystalTier {
+    Small,
+    Big,
+    Great,
+    // Holds everything that didn't fit under the cap
+    Overflow,
+}
+
+impl CrystalTier {
+    pub fn from_xp(xp: u32) -> CrystalTier {
+        match xp {
+            xp if xp >= GREAT_XP_AMT => CrystalTier::Great,
+            xp if xp >= BIG_XP_AMT => CrystalTier::Big,
+            _ => CrystalTier::Small,
+        }
+    }
+
+    pub fn get_texture(&self, textures: &Res<TextureAssets>) -> Handle<Image> {
+        match self {
+            CrystalTier::Small => textures.crystal.clone(),
+            _ => textures.big_crystal.clone(),
+        }
+    }
+
+    // Higher tiers reuse the big crystal, tinted and grown
+    pub fn tint(&self) -> Color {
+        match self {
+            CrystalTier::Small | CrystalTier::Big => Color::WHITE,
+            CrystalTier::Great => Color::rgb(0.8, 0.5, 1.0),
+            CrystalTier::Overflow => Color::rgb(1.0, 0.75, 0.3),
+        }
+    }
+
+    pub fn size(&self) -> f32 {
+        match self {
+            CrystalTier::Small | CrystalTier::Big => 1.,
+            CrystalTier::Great => 1.5,
+            CrystalTier::Overflow => 2.,
+        }
+    }
+}
+
+#[derive(Component)]
+pub struct OverflowGem;
+
+pub fn spawn_crystal(
+    location: Vec3,
+    xp: u32,
+    tier: CrystalTier,
+    velocity: Vec2,
+    textures: &Res<TextureAssets>,
+    commands: &mut Commands,
+) -> Entity {
+    commands
+        .spawn(XPCrystalBundle {
+            sprite_bundle: SpriteBundle {
+                transform: Transform {
+                    translation: Vec3 {
+                        x: location.x,
+                        y: location.y,
+                        z: SortingLayers::BehindAction.into(),
+                    },
+                    rotation: default(),
+                    scale: SCALING_VEC3 * tier.size(),
+                },
+                sprite: Sprite {
+                    color: tier.tint(),
+                    ..Default::default()
+                },
+                texture: tier.get_texture(textures),
+                ..Default::default()
+            },
+            crystal: XPCrystal { contained_xp: xp },
+            velocity: velocity.into(),
+            friction: Friction { force: 50.0 },
+            magnetic: FakeMagnetic { force: 1_000_000.0 },
+        })
+        .insert(EdgeTeleports)
+        .insert(DespawnOnExit::run())
+        .id()
+}
+
 pub fn drop_crystals(
     mut enemy_death_event: EventReader<EnemyDeathEvent>,
     mut crystal_rng: ResMut<CrystalRNG>,
@@ -58,54 +155,141 @@ pub fn drop_crystals(
                 angle: rng.gen_range(Radian::ZERO.angle..Radian::FULL.angle),
             };
 
-            let is_big = xp_to_spawn >= BIG_XP_AMT;
-
-            commands
-                .spawn(XPCrystalBundle {
-                    sprite_bundle: SpriteBundle {
-                        transform: Transform {
-                            translation: Vec3 {
-                                x: death_ev.location.x,
-                                y: death_ev.location.y,
-                                z: SortingLayers::BehindAction.into(),
-                            },
-                            rotation: default(),
-                            scale: SCALING_VEC3,
-                        },
-                        texture: match is_big {
-                            true => textures.big_crystal.clone(),
-                            false => textures.crystal.clone(),
-                        },
-                        ..Default::default()
-                    },
-                    crystal: XPCrystal {
-                        contained_xp: match is_big {
-                            true => BIG_XP_AMT,
-                            false => 1,
-                        },
-                    },
-                    velocity: (direction.unit_vector() * velocity).into(),
-                    friction: Friction { force: 50.0 },
-                    magnetic: FakeMagnetic { force: 1_000_000.0 },
-                })
-                .insert(EdgeTeleports);
-
-            xp_to_spawn -= match is_big {
+            let xp = match xp_to_spawn >= BIG_XP_AMT {
                 true => BIG_XP_AMT,
                 false => 1,
             };
+
+            spawn_crystal(
+                death_ev.location,
+                xp,
+                CrystalTier::from_xp(xp),
+                direction.unit_vector() * velocity,
+                &textures,
+                &mut commands,
+            );
+
+            xp_to_spawn -= xp;
+        }
+    }
+}
+
+/*
+Keeps the crystal count bounded without losing any experience
+*/
+pub fn merge_crystals(
+    q_crystals: Query<(Entity, &Transform, &XPCrystal, Option<&OverflowGem>), Without<Player>>,
+    q_player: Query<(&Transform, &Experience), With<Player>>,
+    textures: Res<TextureAssets>,
+    mut commands: Commands,
+) {
+    if q_crystals.iter().len() <= MERGE_THRESHOLD {
+        return;
+    }
+
+    let (player_transform, experience) = q_player.single();
+    let player_location = player_transform.translation;
+
+    // Crystals sharing a cell merge into one, the overflow gem stays out of it
+    let mut cells: HashMap<IVec2, Vec<(Entity, Vec3, u32)>> = HashMap::new();
+    let mut overflow = None;
+
+    for (entity, transform, crystal, overflow_gem) in q_crystals.iter() {
+        // Already being picked up this frame
+        if transform.translation.distance(player_location) < experience.pick_distance {
+            continue;
         }
+
+        if overflow_gem.is_some() {
+            overflow = Some((entity, transform.translation, crystal.contained_xp));
+            continue;
+        }
+
+        let cell = (transform.translation.truncate() / MERGE_CELL_SIZE)
+            .floor()
+            .as_ivec2();
+        cells
+            .entry(cell)
+            .or_default()
+            .push((entity, transform.translation, crystal.contained_xp));
     }
+
+    let mut remaining: Vec<(Entity, Vec3, u32)> = vec![];
+
+    for crystals in cells.into_values() {
+        if crystals.len() == 1 {
+            remaining.extend(crystals);
+            continue;
+        }
+
+        let xp: u32 = crystals.iter().map(|(_, _, xp)| xp).sum();
+        let location = crystals
+            .iter()
+            .map(|(_, location, _)| *location)
+            .sum::<Vec3>()
+            / crystals.len() as f32;
+
+        for (entity, _, _) in crystals.iter() {
+            commands.entity(*entity).despawn_recursive();
+        }
+
+        let merged = spawn_crystal(
+            location,
+            xp,
+            CrystalTier::from_xp(xp),
+            Vec2::ZERO,
+            &textures,
+            &mut commands,
+        );
+        remaining.push((merged, location, xp));
+    }
+
+    if remaining.len() < CRYSTAL_CAP {
+        return;
+    }
+
+    // Keep the closest crystals, fold everything else into the overflow gem
+    remaining.sort_by(|(_, a, _), (_, b, _)| {
+        a.distance_squared(player_location)
+            .total_cmp(&b.distance_squared(player_location))
+    });
+
+    let folded = remaining.split_off(CRYSTAL_CAP - 1);
+    let mut overflow_xp: u32 = folded.iter().map(|(_, _, xp)| xp).sum();
+
+    for (entity, _, _) in folded.iter() {
+        commands.entity(*entity).despawn_recursive();
+    }
+
+    let overflow_location = match overflow {
+        Some((entity, location, xp)) => {
+            overflow_xp += xp;
+            commands.entity(entity).despawn_recursive();
+            location
+        }
+        None => folded[0].1,
+    };
+
+    let gem = spawn_crystal(
+        overflow_location,
+        overflow_xp,
+        CrystalTier::Overflow,
+        Vec2::ZERO,
+        &textures,
+        &mut commands,
+    );
+    commands.entity(gem).insert(OverflowGem);
 }
 
 pub fn xp_crystal_update(
     q_crystals: Query<(Entity, &Transform, &XPCrystal), Without<Player>>,
-    mut q_player: Query<(&Transform, &mut Experience), (With<Player>, Without<XPCrystal>)>,
+    mut q_player: Query<(&Transform, &Player, &mut Experience), Without<XPCrystal>>,
+    mut collected_ev: EventWriter<XPCollectedEvent>,
     fx_channel: Res<FXChannel>,
     audio: Res<AudioAssets>,
     mut commands: Commands,
 ) {
-    let (player_transform, mut experience) = q_player.single_mut();
+    let (player_transform, player, mut experience) = q_player.single_mut();
 
     for (entity, crystal_transform, crystal) in q_crystals.iter() {
         let _distance = crystal_transform
@@ -115,11 +299,19 @@ pub fn xp_crystal_update(
             .translation
             .distance(player_transform.translation);
         if distance < experience.pick_distance {
+            let total = crystal.contained_xp as f32 * player.xp_mult() + experience.fraction;
+            let gained = total.floor() as u32;
+            experience.fraction = total.fract();
+
             commands.entity(entity).despawn_recursive();
-            experience.curr_experience += crystal.contained_xp;
-            fx_channel.play(match crystal.contained_xp {
-                BIG_XP_AMT => audio.big_crystal.clone(),
-                _ => audio.coin.clone(),
+            experience.curr_experience += gained;
+            collected_ev.send(XPCollectedEvent {
+                amount: gained,
+                location: crystal_transform.translation,
+            });
+            fx_channel.play(match crystal.contained_xp >= BIG_XP_AMT {
+                true => audio.big_crystal.clone(),
+                false => audio.coin.clone(),
             });
         }
     }
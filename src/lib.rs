@@ -3,6 +3,7 @@
 mod actions;
 mod animation;
 mod audio;
+mod camera;
 mod cheats;
 mod collision;
 mod combat;
@@ -11,18 +12,25 @@ mod enemies;
 mod experience;
 mod loading;
 mod menu;
+mod meta;
 mod movement;
 mod palette;
+mod particles;
+mod pickups;
 mod player;
+mod save;
+mod stats;
 mod ui;
 mod util;
 
 use crate::actions::ActionsPlugin;
 use crate::audio::InternalAudioPlugin;
+use crate::camera::CameraEffectsPlugin;
 use crate::enemies::EnemiesPlugin;
 use crate::loading::LoadingPlugin;
 use crate::menu::MenuPlugin;
 use crate::palette::PalettePlugin;
+use crate::particles::ParticlePlugin;
 use crate::player::PlayerPlugin;
 
 use bevy::app::App;
@@ -33,7 +41,11 @@ use cheats::CheatsPlugin;
 use collision::CollisionPlugin;
 use combat::CombatPlugin;
 use experience::ExperiencePlugin;
+use meta::MetaPlugin;
 use movement::MovementPlugin;
+use pickups::PickupPlugin;
+use save::SavePlugin;
+use stats::StatsPlugin;
 use ui::UIPlugin;
 use util::UtilPlugin;
 
@@ -49,30 +61,55 @@ enum GameState {
     Playing,
     // Here the menu is drawn and waiting for player interaction
     Menu,
+    // Picking who to play as, right before a run
+    CharacterSelect,
+    // Permanent upgrades are bought here between runs
+    Shop,
     // special scene for debugging collision system
     DebugCollision,
 }
 
+// What the run is doing while Playing, everything but Running stops the gameplay clock
+#[derive(States, Default, Clone, Eq, PartialEq, Debug, Hash)]
+pub enum InGame {
+    #[default]
+    Running,
+    Paused,
+    // Picking an ability after leveling up or opening a chest
+    LevelUp,
+    GameOver,
+}
+
 pub struct GamePlugin;
 
 impl Plugin for GamePlugin {
     fn build(&self, app: &mut App) {
-        app.add_state::<GameState>().add_plugins((
-            PalettePlugin,
-            LoadingPlugin,
-            MenuPlugin,
-            ActionsPlugin,
-            InternalAudioPlugin,
-            PlayerPlugin,
-            CombatPlugin,
-            EnemiesPlugin,
-            CollisionPlugin,
-            ExperiencePlugin,
-            UtilPlugin,
-            MovementPlugin,
-            UIPlugin,
-            CheatsPlugin,
-        ));
+        app.add_state::<GameState>()
+            .add_state::<InGame>()
+            .add_plugins((
+                PalettePlugin,
+                LoadingPlugin,
+                MenuPlugin,
+                ActionsPlugin,
+                InternalAudioPlugin,
+                PlayerPlugin,
+                CombatPlugin,
+                EnemiesPlugin,
+                CollisionPlugin,
+                ExperiencePlugin,
+                UtilPlugin,
+                MovementPlugin,
+                UIPlugin,
+                CheatsPlugin,
+            ))
+            .add_plugins((
+                StatsPlugin,
+                SavePlugin,
+                MetaPlugin,
+                PickupPlugin,
+                ParticlePlugin,
+                CameraEffectsPlugin,
+            ));
 
         #[cfg(debug_assertions)]
         {
@@ -167,6 +167,16 @@ pub struct TextureAssets {
     #[asset(path = "textures/hatman_spritesheet.png")]
     pub hatman: Handle<Image>,
 
+    // Until they get sheets of their own, the other characters reuse the hatman's
+    #[asset(path = "textures/hatman_spritesheet.png")]
+    pub pyromancer: Handle<Image>,
+
+    #[asset(path = "textures/hatman_spritesheet.png")]
+    pub sharpshooter: Handle<Image>,
+
+    #[asset(path = "textures/hatman_spritesheet.png")]
+    pub warden: Handle<Image>,
+
     #[asset(path = "textures/ui/bullet_ui.png")]
     pub bullet_ui: Handle<Image>,
 
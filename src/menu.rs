@@ -11,14 +11,24 @@ pub struct MenuPlugin;
 /// The menu is only drawn during the State `GameState::Menu` and is removed when that state is exited
 impl Plugin for MenuPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(OnEnter(GameState::Menu), setup_menu)
+        app.add_systems(Startup, setup_camera)
+            .add_systems(OnEnter(GameState::Menu), setup_menu)
             .add_systems(Update, click_play_button.run_if(in_state(GameState::Menu)))
             .add_systems(OnExit(GameState::Menu), cleanup_menu);
     }
 }
 
-fn setup_menu(mut commands: Commands, font_assets: Res<FontAssets>, palette: Res<Palette>) {
+#[derive(Component)]
+enum MenuButton {
+    Play,
+    Shop,
+}
+
+fn setup_camera(mut commands: Commands) {
     commands.spawn(Camera2dBundle::default());
+}
+
+fn setup_menu(mut commands: Commands, font_assets: Res<FontAssets>, palette: Res<Palette>) {
     commands
         .spawn(NodeBundle {
             style: Style {
@@ -79,6 +89,7 @@ fn setup_menu(mut commands: Commands, font_assets: Res<FontAssets>, palette: Res
                     background_color: palette.dark.into(),
                     ..Default::default()
                 })
+                .insert(MenuButton::Play)
                 .with_children(|parent| {
                     parent.spawn(TextBundle::from_section(
                         "Play",
@@ -89,6 +100,31 @@ fn setup_menu(mut commands: Commands, font_assets: Res<FontAssets>, palette: Res
                         },
                     ));
                 });
+
+            parent
+                .spawn(ButtonBundle {
+                    style: Style {
+                        width: Val::Px(250.0),
+                        height: Val::Px(50.0),
+                        margin: UiRect::all(Val::Auto),
+                        justify_content: JustifyContent::Center,
+                        align_items: AlignItems::Center,
+                        ..Default::default()
+                    },
+                    background_color: palette.dark.into(),
+                    ..Default::default()
+                })
+                .insert(MenuButton::Shop)
+                .with_children(|parent| {
+                    parent.spawn(TextBundle::from_section(
+                        "Shop",
+                        TextStyle {
+                            font: font_assets.gothic.clone(),
+                            font_size: 40.0,
+                            color: palette.white,
+                        },
+                    ));
+                });
         });
 }
 
@@ -97,7 +133,7 @@ fn click_play_button(
     mut state: ResMut<NextState<GameState>>,
     _cd_state: ResMut<NextState<ColliderDebugSpriteState>>,
     mut interaction_query: Query<
-        (&Interaction, &mut BackgroundColor),
+        (&Interaction, &MenuButton, &mut BackgroundColor),
         (
             Changed<Interaction>,
             With<Button>,
@@ -105,11 +141,12 @@ fn click_play_button(
         ),
     >,
 ) {
-    for (interaction, mut color) in &mut interaction_query {
+    for (interaction, button, mut color) in &mut interaction_query {
         match *interaction {
-            Interaction::Pressed => {
-                state.set(GameState::Playing);
-            }
+            Interaction::Pressed => match button {
+                MenuButton::Play => state.set(GameState::CharacterSelect),
+                MenuButton::Shop => state.set(GameState::Shop),
+            },
             Interaction::Hovered => {
                 *color = palette.orange.into();
             }
@@ -0,0 +1,219 @@
+use std::time::Duration;
+
+use bevy::{prelude::*, window::PrimaryWindow};
+use rand::seq::IteratorRandom;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    combat::health::Health,
+    experience::{
+        ability_selection::{take_ability, AbilityRNG, AbilityTakenEvent, SelectionCharges},
+        experience::Experience,
+    },
+    loading::AbilityTextures,
+    player::{ability::Ability, Player},
+    save::SaveData,
+    stats::{RunEndedEvent, RunStats},
+    GameState,
+};
+
+use self::shop::ShopPlugin;
+
+pub mod shop;
+
+pub struct MetaPlugin;
+
+impl Plugin for MetaPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            apply_starting_upgrades.run_if(in_state(GameState::Playing)),
+        )
+        .add_systems(Update, award_souls)
+        .add_plugins(ShopPlugin);
+    }
+}
+
+/*
+Permanent upgrades bought with souls between runs
+*/
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug, Serialize, Deserialize)]
+pub enum MetaUpgrade {
+    StartingHp,
+    StartingAbility,
+    ExtraReroll,
+    PickDistance,
+    Luck,
+}
+
+impl MetaUpgrade {
+    pub fn all() -> Vec<MetaUpgrade> {
+        vec![
+            MetaUpgrade::StartingHp,
+            MetaUpgrade::StartingAbility,
+            MetaUpgrade::ExtraReroll,
+            MetaUpgrade::PickDistance,
+            MetaUpgrade::Luck,
+        ]
+    }
+
+    pub fn name(&self) -> String {
+        match self {
+            MetaUpgrade::StartingHp => "Vigor",
+            MetaUpgrade::StartingAbility => "Head Start",
+            MetaUpgrade::ExtraReroll => "Second Thoughts",
+            MetaUpgrade::PickDistance => "Greed",
+            MetaUpgrade::Luck => "Fortune",
+        }
+        .to_string()
+    }
+
+    pub fn description(&self) -> String {
+        match self {
+            MetaUpgrade::StartingHp => "Start with one more HP",
+            MetaUpgrade::StartingAbility => "Start with a random ability",
+            MetaUpgrade::ExtraReroll => "One more reroll when leveling up",
+            MetaUpgrade::PickDistance => "Pick up experience from further away",
+            MetaUpgrade::Luck => "Rarer abilities show up more often",
+        }
+        .to_string()
+    }
+
+    pub fn max_level(&self) -> u32 {
+        match self {
+            MetaUpgrade::StartingAbility => 1,
+            _ => 3,
+        }
+    }
+
+    // The price of the next level, given the current one
+    pub fn cost(&self, level: u32) -> u32 {
+        match self {
+            MetaUpgrade::StartingHp => 50 * (level + 1),
+            MetaUpgrade::StartingAbility => 150,
+            MetaUpgrade::ExtraReroll => 75 * (level + 1),
+            MetaUpgrade::PickDistance => 40 * (level + 1),
+            MetaUpgrade::Luck => 60 * (level + 1),
+        }
+    }
+}
+
+impl SaveData {
+    pub fn upgrade_level(&self, upgrade: MetaUpgrade) -> u32 {
+        *self.upgrades.get(&upgrade).unwrap_or(&0)
+    }
+
+    pub fn can_buy(&self, upgrade: MetaUpgrade) -> bool {
+        let level = self.upgrade_level(upgrade);
+        level < upgrade.max_level() && self.souls >= upgrade.cost(level)
+    }
+
+    pub fn buy(&mut self, upgrade: MetaUpgrade) -> bool {
+        if !self.can_buy(upgrade) {
+            return false;
+        }
+
+        let level = self.upgrade_level(upgrade);
+        self.souls -= upgrade.cost(level);
+        self.upgrades.insert(upgrade, level + 1);
+        true
+    }
+}
+
+pub const BASE_PICK_DISTANCE: f32 = 10.;
+pub const PICK_DISTANCE_PER_LEVEL: f32 = 10.;
+// Seconds survived per soul
+pub const SURVIVAL_PER_SOUL: f32 = 10.;
+
+pub fn souls_for_run(stats: &RunStats, survived: Duration) -> u32 {
+    let from_kills: f32 = stats
+        .kills
+        .iter()
+        .map(|(enemy_type, kills)| enemy_type.difficulty() / 5. * *kills as f32)
+        .sum();
+
+    (from_kills + survived.as_secs_f32() / SURVIVAL_PER_SOUL) as u32
+}
+
+fn award_souls(
+    mut run_ended_ev: EventReader<RunEndedEvent>,
+    stats: Res<RunStats>,
+    mut save: ResMut<SaveData>,
+) {
+    for run_ended in run_ended_ev.iter() {
+        save.souls += souls_for_run(&stats, run_ended.survived);
+    }
+}
+
+// Marks a player who still needs their upgrades, at the start of every run
+#[derive(Component)]
+pub struct StartingUpgradesPending;
+
+fn apply_starting_upgrades(
+    mut q_player: Query<
+        (
+            Entity,
+            &mut Player,
+            &mut Health,
+            &mut Experience,
+            &mut TextureAtlasSprite,
+        ),
+        With<StartingUpgradesPending>,
+    >,
+    q_windows: Query<&Window, With<PrimaryWindow>>,
+    save: Res<SaveData>,
+    textures: Res<AbilityTextures>,
+    mut rng: ResMut<AbilityRNG>,
+    mut charges: ResMut<SelectionCharges>,
+    mut taken_ev: EventWriter<AbilityTakenEvent>,
+    mut commands: Commands,
+) {
+    for (entity, mut player, mut health, mut experience, mut sprite) in q_player.iter_mut() {
+        sprite.color = player.character.tint();
+
+        health.max = player.character.max_health() + save.upgrade_level(MetaUpgrade::StartingHp);
+        health.value = health.max;
+
+        player.luck = save.upgrade_level(MetaUpgrade::Luck);
+        *charges = SelectionCharges::new(save.upgrade_level(MetaUpgrade::ExtraReroll));
+
+        experience.pick_distance = BASE_PICK_DISTANCE
+            + PICK_DISTANCE_PER_LEVEL * save.upgrade_level(MetaUpgrade::PickDistance) as f32;
+
+        for ability in player.character.starting_abilities() {
+            take_ability(
+                ability,
+                &mut player,
+                &mut health,
+                q_windows.single(),
+                &textures,
+                &mut taken_ev,
+                &mut commands,
+            );
+        }
+
+        if save.upgrade_level(MetaUpgrade::StartingAbility) > 0 {
+            let character = player.character;
+            let starting_ability = Ability::all()
+                .into_iter()
+                .filter(|ability| {
+                    ability.is_available(&player.abilities) && character.allows(*ability)
+                })
+                .choose(&mut rng.0 .0);
+
+            if let Some(ability) = starting_ability {
+                take_ability(
+                    ability,
+                    &mut player,
+                    &mut health,
+                    q_windows.single(),
+                    &textures,
+                    &mut taken_ev,
+                    &mut commands,
+                );
+            }
+        }
+
+        commands.entity(entity).remove::<StartingUpgradesPending>();
+    }
+}
@@ -0,0 +1,215 @@
+use bevy::prelude::*;
+
+use crate::{loading::FontAssets, palette::Palette, save::SaveData, GameState};
+
+use super::MetaUpgrade;
+
+pub struct ShopPlugin;
+
+impl Plugin for ShopPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(GameState::Shop), setup_shop)
+            .add_systems(
+                Update,
+                (click_shop_button, update_shop_text).run_if(in_state(GameState::Shop)),
+            )
+            .add_systems(OnExit(GameState::Shop), cleanup_shop);
+    }
+}
+
+#[derive(Component)]
+enum ShopButton {
+    Buy(MetaUpgrade),
+    Back,
+}
+
+#[derive(Component)]
+struct SoulsText;
+
+#[derive(Component)]
+struct UpgradeText(MetaUpgrade);
+
+fn upgrade_label(save: &SaveData, upgrade: MetaUpgrade) -> String {
+    let level = save.upgrade_level(upgrade);
+
+    match level >= upgrade.max_level() {
+        true => format!("{} {}/{} - Owned", upgrade.name(), level, upgrade.max_level()),
+        false => format!(
+            "{} {}/{} - {} souls",
+            upgrade.name(),
+            level,
+            upgrade.max_level(),
+            upgrade.cost(level)
+        ),
+    }
+}
+
+fn setup_shop(
+    mut commands: Commands,
+    font_assets: Res<FontAssets>,
+    palette: Res<Palette>,
+    save: Res<SaveData>,
+) {
+    commands
+        .spawn(NodeBundle {
+            style: Style {
+                display: Display::Grid,
+                width: Val::Percent(100.),
+                align_items: AlignItems::Center,
+                justify_content: JustifyContent::Center,
+                grid_auto_flow: GridAutoFlow::Row,
+                ..Default::default()
+            },
+            ..Default::default()
+        })
+        .with_children(|parent| {
+            parent.spawn(
+                TextBundle::from_section(
+                    "Shop",
+                    TextStyle {
+                        font: font_assets.gothic_pxl.clone(),
+                        font_size: 100.0,
+                        color: palette.orange,
+                    },
+                )
+                .with_style(Style {
+                    margin: UiRect::all(Val::Auto),
+                    ..default()
+                }),
+            );
+
+            parent
+                .spawn(
+                    TextBundle::from_section(
+                        format!("Souls: {}", save.souls),
+                        TextStyle {
+                            font: font_assets.gothic.clone(),
+                            font_size: 40.0,
+                            color: palette.white,
+                        },
+                    )
+                    .with_style(Style {
+                        margin: UiRect::all(Val::Auto),
+                        ..default()
+                    }),
+                )
+                .insert(SoulsText);
+
+            for upgrade in MetaUpgrade::all() {
+                parent
+                    .spawn(ButtonBundle {
+                        style: Style {
+                            width: Val::Px(450.0),
+                            height: Val::Px(60.0),
+                            margin: UiRect::all(Val::Auto),
+                            flex_direction: FlexDirection::Column,
+                            justify_content: JustifyContent::Center,
+                            align_items: AlignItems::Center,
+                            ..Default::default()
+                        },
+                        background_color: palette.dark.into(),
+                        ..Default::default()
+                    })
+                    .insert(ShopButton::Buy(upgrade))
+                    .with_children(|parent| {
+                        parent
+                            .spawn(TextBundle::from_section(
+                                upgrade_label(&save, upgrade),
+                                TextStyle {
+                                    font: font_assets.gothic.clone(),
+                                    font_size: 30.0,
+                                    color: palette.white,
+                                },
+                            ))
+                            .insert(UpgradeText(upgrade));
+
+                        parent.spawn(TextBundle::from_section(
+                            upgrade.description(),
+                            TextStyle {
+                                font: font_assets.gothic.clone(),
+                                font_size: 20.0,
+                                color: palette.white,
+                            },
+                        ));
+                    });
+            }
+
+            parent
+                .spawn(ButtonBundle {
+                    style: Style {
+                        width: Val::Px(250.0),
+                        height: Val::Px(50.0),
+                        margin: UiRect::all(Val::Auto),
+                        justify_content: JustifyContent::Center,
+                        align_items: AlignItems::Center,
+                        ..Default::default()
+                    },
+                    background_color: palette.dark.into(),
+                    ..Default::default()
+                })
+                .insert(ShopButton::Back)
+                .with_children(|parent| {
+                    parent.spawn(TextBundle::from_section(
+                        "Back",
+                        TextStyle {
+                            font: font_assets.gothic.clone(),
+                            font_size: 40.0,
+                            color: palette.white,
+                        },
+                    ));
+                });
+        });
+}
+
+fn click_shop_button(
+    palette: Res<Palette>,
+    mut state: ResMut<NextState<GameState>>,
+    mut save: ResMut<SaveData>,
+    mut interaction_query: Query<
+        (&Interaction, &ShopButton, &mut BackgroundColor),
+        Changed<Interaction>,
+    >,
+) {
+    for (interaction, button, mut color) in &mut interaction_query {
+        match *interaction {
+            Interaction::Pressed => match button {
+                ShopButton::Buy(upgrade) => {
+                    save.buy(*upgrade);
+                }
+                ShopButton::Back => {
+                    state.set(GameState::Menu);
+                }
+            },
+            Interaction::Hovered => {
+                *color = palette.orange.into();
+            }
+            Interaction::None => {
+                *color = palette.red.into();
+            }
+        }
+    }
+}
+
+fn update_shop_text(
+    save: Res<SaveData>,
+    mut q_souls: Query<&mut Text, (With<SoulsText>, Without<UpgradeText>)>,
+    mut q_upgrades: Query<(&mut Text, &UpgradeText), Without<SoulsText>>,
+) {
+    if !save.is_changed() {
+        return;
+    }
+
+    for mut text in q_souls.iter_mut() {
+        text.sections[0].value = format!("Souls: {}", save.souls);
+    }
+
+    for (mut text, upgrade_text) in q_upgrades.iter_mut() {
+        text.sections[0].value = upgrade_label(&save, upgrade_text.0);
+    }
+}
+
+fn cleanup_shop(mut commands: Commands, ui: Query<Entity, With<Node>>) {
+    for entity in ui.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
@@ -1,19 +1,22 @@
 use bevy::prelude::*;
 
 use self::{
+    controls_menu::{rebind_controls, update_controls_page, Rebinding},
     edge_teleport::edge_teleporting,
     fake_magnetic::fake_magnet_update,
     follow_mouse::follow_mouse_update,
     friction::friction_update,
     magnetic::magnet_update,
     pause::{
-        click_unpause, pause_keypress, update_pause_menu, update_volume_bars, ActionPauseState,
-        PauseMenuState, TogglePauseMenu,
+        change_pause_page, click_unpause, close_pause_menu, navigate_pause_menu, open_controls,
+        open_pause_menu, pause_keypress, change_settings, update_pause_menu, update_volume_bars,
+        OpenPausePage, PauseMenuFocus, PauseMenuPage, TogglePauseMenu,
     },
     velocity::velocity_update,
 };
-use crate::GameState;
+use crate::{util::despawn_on_exit::AppDespawnOnExit, GameState, InGame};
 
+pub mod controls_menu;
 pub mod edge_teleport;
 pub mod fake_magnetic;
 pub mod follow_mouse;
@@ -35,15 +38,34 @@ impl Plugin for MovementPlugin {
                     fake_magnet_update,
                     friction_update,
                     edge_teleporting,
-                    pause_keypress,
                     update_pause_menu,
                     click_unpause,
                     update_volume_bars,
                 )
                     .run_if(in_state(GameState::Playing)),
             )
+            .add_systems(
+                Update,
+                // Pages switch last so the press that switched them isn't read again
+                (
+                    pause_keypress,
+                    rebind_controls,
+                    update_controls_page,
+                    navigate_pause_menu,
+                    change_settings,
+                    open_controls,
+                    change_pause_page,
+                )
+                    .chain()
+                    .run_if(in_state(GameState::Playing)),
+            )
+            .add_systems(OnEnter(InGame::Paused), open_pause_menu)
+            .add_systems(OnExit(InGame::Paused), close_pause_menu)
+            .despawn_on_exit(InGame::Paused)
             .add_event::<TogglePauseMenu>()
-            .insert_resource(PauseMenuState(false))
-            .insert_resource(ActionPauseState { is_paused: false });
+            .add_event::<OpenPausePage>()
+            .init_resource::<PauseMenuFocus>()
+            .init_resource::<PauseMenuPage>()
+            .init_resource::<Rebinding>();
     }
 }
@@ -0,0 +1,273 @@
+use bevy::{prelude::*, sprite::Anchor, text::Text2dBounds};
+
+use crate::{
+    actions::input_map::{ActionInput, InputAction, InputMap},
+    collision::collider::Collider,
+    constants::SortingLayers,
+    palette::Palette,
+    ui::clickable::{Clickable, ClickedEvent},
+    util::despawn_on_exit::DespawnOnExit,
+    InGame,
+};
+
+use super::pause::{
+    OpenPausePage, PauseMenuAssets, PauseMenuComponent, PauseMenuFocus, PauseMenuPage,
+};
+
+// Actions are listed in two columns
+const ROWS_PER_COLUMN: usize = 12;
+const COLUMN_X: f32 = 200.;
+const FIRST_ROW_Y: f32 = 220.;
+const ROW_HEIGHT: f32 = 34.;
+const BUTTONS_Y: f32 = -190.;
+
+// The action waiting for a new binding, the next button pressed is bound to it
+#[derive(Resource, Default)]
+pub struct Rebinding(pub Option<InputAction>);
+
+#[derive(Component)]
+pub struct RebindRow(pub InputAction);
+
+#[derive(Component)]
+pub struct ResetBindingsButton;
+
+#[derive(Component)]
+pub struct ControlsBackButton;
+
+/*
+Rows of the page in focus order: every action, then the reset and back buttons
+*/
+fn row_count() -> usize {
+    InputAction::all().len() + 2
+}
+
+fn reset_row() -> usize {
+    InputAction::all().len()
+}
+
+fn back_row() -> usize {
+    InputAction::all().len() + 1
+}
+
+fn row_label(action: InputAction, input_map: &InputMap, rebinding: &Rebinding) -> String {
+    match rebinding.0 == Some(action) {
+        true => format!("{}: press a button...", action.get_name()),
+        false => format!("{}: {}", action.get_name(), input_map.label(action)),
+    }
+}
+
+fn spawn_text(
+    value: String,
+    position: Vec2,
+    font_size: f32,
+    assets: &PauseMenuAssets,
+    commands: &mut Commands,
+) -> Entity {
+    commands
+        .spawn(Text2dBundle {
+            text: Text::from_section(
+                value,
+                TextStyle {
+                    font: assets.font_assets.gothic_pxl.clone(),
+                    font_size,
+                    color: assets.palette.orange,
+                },
+            )
+            .with_alignment(TextAlignment::Center),
+            text_anchor: Anchor::Center,
+            text_2d_bounds: Text2dBounds {
+                size: Vec2 { x: 380., y: 30. },
+            },
+            transform: Transform::from_translation(position.extend(SortingLayers::UI.into())),
+            ..Default::default()
+        })
+        .insert(PauseMenuComponent)
+        .insert(DespawnOnExit(InGame::Paused))
+        .id()
+}
+
+pub fn spawn_controls_page(assets: &mut PauseMenuAssets, commands: &mut Commands) {
+    spawn_text(
+        "Controls".to_string(),
+        Vec2::new(0., 265.),
+        30.,
+        assets,
+        commands,
+    );
+
+    for (i, action) in InputAction::all().into_iter().enumerate() {
+        let column = (i / ROWS_PER_COLUMN) as f32;
+        let row = (i % ROWS_PER_COLUMN) as f32;
+        let position = Vec2::new(
+            -COLUMN_X + column * COLUMN_X * 2.,
+            FIRST_ROW_Y - row * ROW_HEIGHT,
+        );
+
+        let entity = spawn_text(
+            row_label(action, &assets.input_map, &Rebinding(None)),
+            position,
+            16.,
+            assets,
+            commands,
+        );
+        commands
+            .entity(entity)
+            .insert(Clickable::new())
+            .insert(Collider::new_rect(Vec2 { x: 380., y: 28. }))
+            .insert(RebindRow(action));
+    }
+
+    let reset = spawn_text(
+        "Reset to Defaults".to_string(),
+        Vec2::new(-COLUMN_X, BUTTONS_Y),
+        24.,
+        assets,
+        commands,
+    );
+    commands
+        .entity(reset)
+        .insert(Clickable::new())
+        .insert(Collider::new_rect(Vec2 { x: 250., y: 30. }))
+        .insert(ResetBindingsButton);
+
+    let back = spawn_text(
+        "Back".to_string(),
+        Vec2::new(COLUMN_X, BUTTONS_Y),
+        24.,
+        assets,
+        commands,
+    );
+    commands
+        .entity(back)
+        .insert(Clickable::new())
+        .insert(Collider::new_rect(Vec2 { x: 150., y: 30. }))
+        .insert(ControlsBackButton);
+}
+
+pub fn rebind_controls(
+    q_rows: Query<(Entity, &RebindRow)>,
+    q_reset: Query<Entity, With<ResetBindingsButton>>,
+    q_back: Query<Entity, With<ControlsBackButton>>,
+    mut click_ev: EventReader<ClickedEvent>,
+    mut open_ev: EventWriter<OpenPausePage>,
+    mut rebinding: ResMut<Rebinding>,
+    mut focus: ResMut<PauseMenuFocus>,
+    mut input_map: ResMut<InputMap>,
+    input: ActionInput,
+    in_game: Res<State<InGame>>,
+    page: Res<PauseMenuPage>,
+) {
+    // Read every frame so clicks never pile up for later
+    let clicked: Vec<Entity> = click_ev.iter().map(|clicked| clicked.entity).collect();
+
+    if *in_game.get() != InGame::Paused || *page != PauseMenuPage::Controls {
+        return;
+    }
+
+    if let Some(action) = rebinding.0 {
+        // Back cancels, so it's the one thing that can't be bound from here
+        if input.just_pressed(InputAction::Back) {
+            rebinding.0 = None;
+            return;
+        }
+
+        if let Some(binding) = input.any_just_pressed() {
+            input_map.rebind(action, binding);
+            rebinding.0 = None;
+        }
+
+        return;
+    }
+
+    if input.just_pressed(InputAction::Back) || input.just_pressed(InputAction::Pause) {
+        open_ev.send(OpenPausePage(PauseMenuPage::Settings));
+        return;
+    }
+
+    let actions = InputAction::all();
+
+    if input.just_pressed(InputAction::MenuUp) && focus.0 > 0 {
+        focus.0 -= 1;
+    }
+
+    if input.just_pressed(InputAction::MenuDown) && focus.0 < row_count() - 1 {
+        focus.0 += 1;
+    }
+
+    // Left and right jump between the columns
+    if input.just_pressed(InputAction::MenuLeft) && focus.0 >= ROWS_PER_COLUMN {
+        focus.0 = (focus.0 - ROWS_PER_COLUMN).min(actions.len() - 1);
+    }
+
+    if input.just_pressed(InputAction::MenuRight) && focus.0 + ROWS_PER_COLUMN < actions.len() {
+        focus.0 += ROWS_PER_COLUMN;
+    }
+
+    let confirmed = input.just_pressed(InputAction::Confirm);
+
+    let clicked_row = q_rows
+        .iter()
+        .find(|(entity, _)| clicked.contains(entity))
+        .map(|(_, row)| row.0);
+    let focused_row = actions.get(focus.0).copied().filter(|_| confirmed);
+
+    if let Some(action) = clicked_row.or(focused_row) {
+        rebinding.0 = Some(action);
+        return;
+    }
+
+    let reset_clicked = q_reset.iter().any(|entity| clicked.contains(&entity));
+    if reset_clicked || (confirmed && focus.0 == reset_row()) {
+        input_map.reset();
+    }
+
+    let back_clicked = q_back.iter().any(|entity| clicked.contains(&entity));
+    if back_clicked || (confirmed && focus.0 == back_row()) {
+        open_ev.send(OpenPausePage(PauseMenuPage::Settings));
+    }
+}
+
+// Conflicting bindings are flagged until one of them is changed
+pub fn update_controls_page(
+    mut q_rows: Query<(&RebindRow, &mut Text)>,
+    mut q_buttons: Query<
+        (&mut Text, Option<&ResetBindingsButton>),
+        (
+            Or<(With<ResetBindingsButton>, With<ControlsBackButton>)>,
+            Without<RebindRow>,
+        ),
+    >,
+    input_map: Res<InputMap>,
+    rebinding: Res<Rebinding>,
+    focus: Res<PauseMenuFocus>,
+    palette: Res<Palette>,
+) {
+    let actions = InputAction::all();
+
+    for (row, mut text) in q_rows.iter_mut() {
+        let is_focused = actions.get(focus.0) == Some(&row.0);
+        let has_conflict = !input_map.conflicts(row.0).is_empty();
+
+        text.sections[0].value = match has_conflict {
+            true => format!("{} (conflict)", row_label(row.0, &input_map, &rebinding)),
+            false => row_label(row.0, &input_map, &rebinding),
+        };
+        text.sections[0].style.color = match (is_focused, has_conflict) {
+            (true, _) => palette.white,
+            (false, true) => palette.red,
+            (false, false) => palette.orange,
+        };
+    }
+
+    for (mut text, reset) in q_buttons.iter_mut() {
+        let row = match reset {
+            Some(_) => reset_row(),
+            None => back_row(),
+        };
+
+        text.sections[0].style.color = match focus.0 == row {
+            true => palette.white,
+            false => palette.orange,
+        };
+    }
+}
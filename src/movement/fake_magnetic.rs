@@ -2,9 +2,7 @@ use std::f32::consts::PI;
 
 use bevy::prelude::*;
 
-use crate::{player::{Player, ability::Ability}, util::radians::Radian};
-
-use super::pause::ActionPauseState;
+use crate::{player::{Player, ability::Ability}, util::{clock::GameClock, radians::Radian}};
 
 /*
 Things that are magnetically attracted to the player
@@ -19,16 +17,11 @@ const MINIMUM_FORCE_THRESHOLD: f32 = 0.8;
 pub fn fake_magnet_update(
     q_player: Query<(&Transform, &Player), Without<FakeMagnetic>>,
     mut q_magnetics: Query<(&FakeMagnetic, &mut Transform)>,
-    time: Res<Time>,
-    pause_state: Res<ActionPauseState>,
+    clock: Res<GameClock>,
 ) {
-    if pause_state.is_paused {
-        return;
-    }
-
     let (player_transform, player) = q_player.single();
     for (magnet, mut transform) in q_magnetics.iter_mut() {
-        let mut force = magnet.force * time.delta_seconds()
+        let mut force = magnet.force * clock.delta_seconds()
             / player_transform
                 .translation
                 .distance_squared(transform.translation);
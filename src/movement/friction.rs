@@ -1,23 +1,17 @@
 use bevy::prelude::*;
 
-use super::{pause::ActionPauseState, velocity::Velocity};
+use crate::util::clock::GameClock;
+
+use super::velocity::Velocity;
 
 #[derive(Component)]
 pub struct Friction {
     pub force: f32,
 }
 
-pub fn friction_update(
-    mut q_friction: Query<(&Friction, &mut Velocity)>,
-    time: Res<Time>,
-    pause_state: Res<ActionPauseState>,
-) {
-    if pause_state.is_paused {
-        return;
-    }
-
+pub fn friction_update(mut q_friction: Query<(&Friction, &mut Velocity)>, clock: Res<GameClock>) {
     for (friction, mut velocity) in q_friction.iter_mut() {
-        let force = friction.force * time.delta_seconds();
+        let force = friction.force * clock.delta_seconds();
         let direction_vector = velocity.vec.normalize();
 
         if velocity.vec.length() < force {
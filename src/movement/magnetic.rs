@@ -2,9 +2,12 @@ use std::f32::consts::PI;
 
 use bevy::prelude::*;
 
-use crate::{player::Player, util::radians::Radian};
+use crate::{
+    player::Player,
+    util::{clock::GameClock, radians::Radian},
+};
 
-use super::{pause::ActionPauseState, velocity::Velocity};
+use super::velocity::Velocity;
 
 /*
 Things that are magnetically attracted to the player
@@ -18,13 +21,8 @@ pub struct Magnetic {
 pub fn magnet_update(
     q_player: Query<&Transform, (With<Player>, Without<Magnetic>)>,
     mut q_magnetics: Query<(&Magnetic, &mut Velocity, &Transform)>,
-    time: Res<Time>,
-    pause_state: Res<ActionPauseState>,
+    clock: Res<GameClock>,
 ) {
-    if pause_state.is_paused {
-        return;
-    }
-
     let player_transform = q_player.single();
     for (magnet, mut velocity, transform) in q_magnetics.iter_mut() {
         let direction = player_transform.translation.truncate() - transform.translation.truncate();
@@ -33,6 +31,6 @@ pub fn magnet_update(
         let direction_vec = angle_to_target.unit_vector();
 
         let force = magnet.force / direction.length_squared();
-        velocity.vec += direction_vec * force * time.delta().as_secs_f32();
+        velocity.vec += direction_vec * force * clock.delta().as_secs_f32();
     }
 }
@@ -1,19 +1,23 @@
-use bevy::{math::bool, prelude::*, sprite::Anchor};
+use bevy::{ecs::system::SystemParam, math::bool, prelude::*, sprite::Anchor};
 use bevy_kira_audio::AudioControl;
 
 use crate::{
+    actions::input_map::{ActionInput, InputAction, InputMap},
     audio::{FXChannel, Music, MusicChannel, Volume, FX},
     collision::collider::Collider,
     constants::SortingLayers,
     loading::{FontAssets, TextureAssets},
     palette::Palette,
-    ui::bar::{Bar, BarButtonInfo, BarUpdatedEvent},
+    save::{SaveData, Settings},
+    ui::{
+        bar::{Bar, BarButtonInfo, BarUpdatedEvent},
+        clickable::{Clickable, ClickedEvent},
+    },
+    util::despawn_on_exit::DespawnOnExit,
+    InGame,
 };
 
-#[derive(Resource)]
-pub struct ActionPauseState {
-    pub is_paused: bool,
-}
+use super::controls_menu::{spawn_controls_page, Rebinding};
 
 #[derive(Component)]
 pub struct PauseMenuComponent;
@@ -21,20 +25,60 @@ pub struct PauseMenuComponent;
 #[derive(Event)]
 pub struct TogglePauseMenu;
 
-#[derive(Resource)]
-pub struct PauseMenuState(pub bool);
+#[derive(Resource, Default, Clone, Copy, PartialEq, Eq)]
+pub enum PauseMenuPage {
+    #[default]
+    Settings,
+    Controls,
+}
+
+#[derive(Event)]
+pub struct OpenPausePage(pub PauseMenuPage);
+
+// Which row of the pause menu is focused for keyboard and gamepad navigation
+#[derive(Resource, Default)]
+pub struct PauseMenuFocus(pub usize);
+
+const MUSIC_ROW: usize = 0;
+const FX_ROW: usize = 1;
+const TARGETING_ROW: usize = 2;
+const AUTO_FIRE_ROW: usize = 3;
+const SCREEN_SHAKE_ROW: usize = 4;
+const CONTROLS_ROW: usize = 5;
+const ROW_COUNT: usize = 6;
+
+#[derive(SystemParam)]
+pub struct PauseMenuAssets<'w> {
+    pub textures: Res<'w, TextureAssets>,
+    pub texture_atlases: ResMut<'w, Assets<TextureAtlas>>,
+    pub font_assets: Res<'w, FontAssets>,
+    pub palette: Res<'w, Palette>,
+    pub music_volume: Res<'w, Volume<Music>>,
+    pub fx_volume: Res<'w, Volume<FX>>,
+    pub save: Res<'w, SaveData>,
+    pub input_map: Res<'w, InputMap>,
+}
 
 pub fn pause_keypress(
     mut enter_ev: EventWriter<TogglePauseMenu>,
-    keyboard_input: Res<Input<KeyCode>>,
-    pause_menu_state: Res<PauseMenuState>,
-    pause: Res<ActionPauseState>,
+    input: ActionInput,
+    in_game: Res<State<InGame>>,
+    page: Res<PauseMenuPage>,
 ) {
-    if !pause_menu_state.0 && pause.is_paused {
+    let is_open = match in_game.get() {
+        InGame::Running => false,
+        InGame::Paused => true,
+        InGame::LevelUp | InGame::GameOver => return,
+    };
+
+    // The controls page goes back to the settings instead
+    if is_open && *page == PauseMenuPage::Controls {
         return;
     }
 
-    if keyboard_input.just_pressed(KeyCode::Escape) {
+    let back_pressed = is_open && input.just_pressed(InputAction::Back);
+
+    if input.just_pressed(InputAction::Pause) || back_pressed {
         enter_ev.send(TogglePauseMenu);
     }
 }
@@ -65,40 +109,117 @@ pub struct FXBar;
 #[derive(Component)]
 pub struct MusicBar;
 
+// Settings changed in place, by clicking, tapping or pressing left and right on them
+#[derive(Component, Clone, Copy)]
+pub enum SettingOption {
+    Targeting,
+    AutoFire,
+    ScreenShake,
+}
+
+impl SettingOption {
+    // Steps forward or backward through the values, on/off ones just flip
+    fn change(&self, settings: &mut Settings, step: i32) {
+        match self {
+            SettingOption::Targeting => settings.targeting = settings.targeting.cycled(step),
+            SettingOption::AutoFire => settings.auto_fire = !settings.auto_fire,
+            SettingOption::ScreenShake => settings.screen_shake = !settings.screen_shake,
+        }
+    }
+
+    fn label(&self, settings: &Settings) -> String {
+        let on_off = |is_on: bool| match is_on {
+            true => "On".to_string(),
+            false => "Off".to_string(),
+        };
+
+        match self {
+            SettingOption::Targeting => format!("Targeting: {}", settings.targeting.get_name()),
+            SettingOption::AutoFire => format!("Auto Fire: {}", on_off(settings.auto_fire)),
+            SettingOption::ScreenShake => {
+                format!("Screen Shake: {}", on_off(settings.screen_shake))
+            }
+        }
+    }
+
+    fn row(&self) -> usize {
+        match self {
+            SettingOption::Targeting => TARGETING_ROW,
+            SettingOption::AutoFire => AUTO_FIRE_ROW,
+            SettingOption::ScreenShake => SCREEN_SHAKE_ROW,
+        }
+    }
+}
+
+#[derive(Component)]
+pub struct ControlsButton;
+
+// Level up choices and the game over screen can't be paused over
 pub fn update_pause_menu(
-    menu_items: Query<Entity, With<PauseMenuComponent>>,
     mut enter_ev: EventReader<TogglePauseMenu>,
-    mut pause_menu_state: ResMut<PauseMenuState>,
-    mut pause: ResMut<ActionPauseState>,
-    textures: Res<TextureAssets>,
-    mut texture_atlases: ResMut<Assets<TextureAtlas>>,
-    font_assets: Res<FontAssets>,
-    palette: Res<Palette>,
-    music_volume: Res<Volume<Music>>,
-    fx_volume: Res<Volume<FX>>,
-    mut commands: Commands,
+    in_game: Res<State<InGame>>,
+    mut next_in_game: ResMut<NextState<InGame>>,
 ) {
-    if enter_ev.iter().len() == 0 {
+    // Counting marks them as read
+    if enter_ev.iter().count() == 0 {
         return;
     }
 
-    for _ev in enter_ev.iter() {
-        // let ev be marked as read
+    match in_game.get() {
+        InGame::Running => next_in_game.set(InGame::Paused),
+        InGame::Paused => next_in_game.set(InGame::Running),
+        InGame::LevelUp | InGame::GameOver => (),
     }
+}
 
-    if pause_menu_state.0 {
-        pause.is_paused = false;
-        pause_menu_state.0 = false;
+pub fn open_pause_menu(
+    mut page: ResMut<PauseMenuPage>,
+    mut focus: ResMut<PauseMenuFocus>,
+    mut assets: PauseMenuAssets,
+    mut commands: Commands,
+) {
+    *page = PauseMenuPage::Settings;
+    focus.0 = MUSIC_ROW;
 
-        for e in menu_items.iter() {
-            commands.entity(e).despawn_recursive();
-        }
+    spawn_settings_page(&mut assets, &mut commands);
+}
 
+// The menu itself is despawned on leaving the state
+pub fn close_pause_menu(mut rebinding: ResMut<Rebinding>) {
+    rebinding.0 = None;
+}
+
+// Swaps the content of the pause menu, which stays open
+pub fn change_pause_page(
+    menu_items: Query<Entity, With<PauseMenuComponent>>,
+    mut open_ev: EventReader<OpenPausePage>,
+    mut page: ResMut<PauseMenuPage>,
+    mut focus: ResMut<PauseMenuFocus>,
+    mut rebinding: ResMut<Rebinding>,
+    mut assets: PauseMenuAssets,
+    mut commands: Commands,
+) {
+    let Some(OpenPausePage(next_page)) = open_ev.iter().last() else {
         return;
+    };
+
+    for e in menu_items.iter() {
+        commands.entity(e).despawn_recursive();
     }
 
-    pause.is_paused = true;
-    pause_menu_state.0 = true;
+    *page = *next_page;
+    focus.0 = 0;
+    rebinding.0 = None;
+
+    match next_page {
+        PauseMenuPage::Settings => spawn_settings_page(&mut assets, &mut commands),
+        PauseMenuPage::Controls => spawn_controls_page(&mut assets, &mut commands),
+    }
+}
+
+fn spawn_settings_page(assets: &mut PauseMenuAssets, commands: &mut Commands) {
+    let font_assets = &assets.font_assets;
+    let palette = &assets.palette;
 
     commands
         .spawn(ButtonBundle {
@@ -114,6 +235,7 @@ pub fn update_pause_menu(
             ..Default::default()
         })
         .insert(PauseMenuComponent)
+        .insert(DespawnOnExit(InGame::Paused))
         .with_children(|parent| {
             parent
                 .spawn(TextBundle::from_section(
@@ -127,8 +249,8 @@ pub fn update_pause_menu(
                 .insert(PauseMenuComponent);
         });
 
-    let bar_atlas_handle = texture_atlases.add(TextureAtlas::from_grid(
-        textures.bar.clone(),
+    let bar_atlas_handle = assets.texture_atlases.add(TextureAtlas::from_grid(
+        assets.textures.bar.clone(),
         Vec2 { x: 64., y: 64. },
         11,
         1,
@@ -136,8 +258,8 @@ pub fn update_pause_menu(
         None,
     ));
 
-    let button_atlas_handle = texture_atlases.add(TextureAtlas::from_grid(
-        textures.next_button.clone(),
+    let button_atlas_handle = assets.texture_atlases.add(TextureAtlas::from_grid(
+        assets.textures.next_button.clone(),
         Vec2 { x: 64., y: 64. },
         3,
         1,
@@ -163,7 +285,8 @@ pub fn update_pause_menu(
             }),
             ..Default::default()
         })
-        .insert(PauseMenuComponent);
+        .insert(PauseMenuComponent)
+        .insert(DespawnOnExit(InGame::Paused));
 
     commands
         .spawn(Text2dBundle {
@@ -183,10 +306,11 @@ pub fn update_pause_menu(
             }),
             ..Default::default()
         })
-        .insert(PauseMenuComponent);
+        .insert(PauseMenuComponent)
+        .insert(DespawnOnExit(InGame::Paused));
 
     Bar::spawn(
-        (music_volume.volume() * 10.) as u32,
+        (assets.music_volume.volume() * 10.) as u32,
         10,
         SpriteSheetBundle {
             texture_atlas: bar_atlas_handle.clone(),
@@ -239,12 +363,12 @@ pub fn update_pause_menu(
             has_pressed_state: true,
             collider: Collider::new_rect(Vec2 { x: 20., y: 30. }),
         }),
-        Some((PauseMenuComponent, MusicBar)),
-        &mut commands,
+        Some((PauseMenuComponent, DespawnOnExit(InGame::Paused), MusicBar)),
+        commands,
     );
 
     Bar::spawn(
-        (fx_volume.volume() * 10.) as u32,
+        (assets.fx_volume.volume() * 10.) as u32,
         10,
         SpriteSheetBundle {
             texture_atlas: bar_atlas_handle.clone(),
@@ -297,9 +421,177 @@ pub fn update_pause_menu(
             has_pressed_state: true,
             collider: Collider::new_rect(Vec2 { x: 20., y: 30. }),
         }),
-        Some((PauseMenuComponent, FXBar)),
-        &mut commands,
+        Some((PauseMenuComponent, DespawnOnExit(InGame::Paused), FXBar)),
+        commands,
     );
+
+    // The unpause button sits in the middle, between the toggles
+    for (option, y) in [
+        (SettingOption::Targeting, 40.),
+        (SettingOption::AutoFire, -60.),
+        (SettingOption::ScreenShake, -100.),
+    ] {
+        commands
+            .spawn(Text2dBundle {
+                text: Text::from_section(
+                    option.label(&assets.save.settings),
+                    TextStyle {
+                        font: font_assets.gothic_pxl.clone(),
+                        font_size: 30.,
+                        color: palette.orange,
+                    },
+                ),
+                text_anchor: Anchor::Center,
+                transform: Transform::from_translation(Vec3 {
+                    x: 0.,
+                    y,
+                    z: SortingLayers::UI.into(),
+                }),
+                ..Default::default()
+            })
+            .insert(Clickable::new())
+            .insert(Collider::new_rect(Vec2 { x: 200., y: 30. }))
+            .insert(PauseMenuComponent)
+            .insert(DespawnOnExit(InGame::Paused))
+            .insert(option);
+    }
+
+    commands
+        .spawn(Text2dBundle {
+            text: Text::from_section(
+                "Controls",
+                TextStyle {
+                    font: font_assets.gothic_pxl.clone(),
+                    font_size: 30.,
+                    color: palette.orange,
+                },
+            ),
+            text_anchor: Anchor::Center,
+            transform: Transform::from_translation(Vec3 {
+                x: 0.,
+                y: -140.,
+                z: SortingLayers::UI.into(),
+            }),
+            ..Default::default()
+        })
+        .insert(Clickable::new())
+        .insert(Collider::new_rect(Vec2 { x: 200., y: 30. }))
+        .insert(PauseMenuComponent)
+        .insert(DespawnOnExit(InGame::Paused))
+        .insert(ControlsButton);
+}
+
+pub fn change_settings(
+    mut q_options: Query<(Entity, &SettingOption, &mut Text)>,
+    mut click_ev: EventReader<ClickedEvent>,
+    input: ActionInput,
+    focus: Res<PauseMenuFocus>,
+    mut save: ResMut<SaveData>,
+) {
+    let clicked: Vec<Entity> = click_ev.iter().map(|clicked| clicked.entity).collect();
+    let pressed_step = match (
+        input.just_pressed(InputAction::MenuLeft),
+        input.just_pressed(InputAction::MenuRight) || input.just_pressed(InputAction::Confirm),
+    ) {
+        (true, false) => Some(-1),
+        (false, true) => Some(1),
+        _ => None,
+    };
+
+    for (entity, option, mut text) in q_options.iter_mut() {
+        let step = match clicked.contains(&entity) {
+            true => Some(1),
+            false => pressed_step.filter(|_| focus.0 == option.row()),
+        };
+
+        let Some(step) = step else {
+            continue;
+        };
+
+        option.change(&mut save.settings, step);
+        text.sections[0].value = option.label(&save.settings);
+    }
+}
+
+pub fn open_controls(
+    q_button: Query<Entity, With<ControlsButton>>,
+    mut click_ev: EventReader<ClickedEvent>,
+    mut open_ev: EventWriter<OpenPausePage>,
+    input: ActionInput,
+    focus: Res<PauseMenuFocus>,
+) {
+    let Ok(entity) = q_button.get_single() else {
+        return;
+    };
+
+    let clicked = click_ev.iter().any(|clicked| clicked.entity == entity);
+    let pressed = focus.0 == CONTROLS_ROW && input.just_pressed(InputAction::Confirm);
+
+    if clicked || pressed {
+        open_ev.send(OpenPausePage(PauseMenuPage::Controls));
+    }
+}
+
+/*
+Up and down move between the rows of the settings page and left/right changes
+the focused one
+*/
+pub fn navigate_pause_menu(
+    mut q_bars: Query<
+        (Entity, &mut Bar, &mut TextureAtlasSprite, Option<&MusicBar>),
+        Or<(With<MusicBar>, With<FXBar>)>,
+    >,
+    mut q_texts: Query<
+        (&mut Text, Option<&SettingOption>),
+        Or<(With<SettingOption>, With<ControlsButton>)>,
+    >,
+    mut focus: ResMut<PauseMenuFocus>,
+    mut bar_update: EventWriter<BarUpdatedEvent>,
+    input: ActionInput,
+    in_game: Res<State<InGame>>,
+    page: Res<PauseMenuPage>,
+    palette: Res<Palette>,
+) {
+    if *in_game.get() != InGame::Paused || *page != PauseMenuPage::Settings {
+        return;
+    }
+
+    if input.just_pressed(InputAction::MenuUp) && focus.0 > 0 {
+        focus.0 -= 1;
+    }
+
+    if input.just_pressed(InputAction::MenuDown) && focus.0 < ROW_COUNT - 1 {
+        focus.0 += 1;
+    }
+
+    let step = input.just_pressed(InputAction::MenuRight) as i32
+        - input.just_pressed(InputAction::MenuLeft) as i32;
+
+    for (entity, mut bar, mut sprite, music_bar) in q_bars.iter_mut() {
+        let row = match music_bar {
+            Some(_) => MUSIC_ROW,
+            None => FX_ROW,
+        };
+        let is_focused = focus.0 == row;
+
+        sprite.color = match is_focused {
+            true => palette.orange,
+            false => Color::WHITE,
+        };
+
+        if is_focused && step != 0 {
+            bar.step(entity, step, &mut bar_update);
+        }
+    }
+
+    for (mut text, option) in q_texts.iter_mut() {
+        let row = option.map_or(CONTROLS_ROW, |option| option.row());
+
+        text.sections[0].style.color = match focus.0 == row {
+            true => palette.white,
+            false => palette.orange,
+        };
+    }
 }
 
 pub fn click_unpause(
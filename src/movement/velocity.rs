@@ -1,8 +1,6 @@
 use bevy::prelude::*;
 
-use crate::constants::DISTANCE_SCALING;
-
-use super::pause::ActionPauseState;
+use crate::{constants::DISTANCE_SCALING, util::clock::GameClock};
 
 #[derive(Component)]
 pub struct Velocity {
@@ -13,21 +11,13 @@ impl Velocity {
     pub const ZERO: Velocity = Velocity { vec: Vec2::ZERO };
 }
 
-pub fn velocity_update(
-    mut q_velocity: Query<(&Velocity, &mut Transform)>,
-    time: Res<Time>,
-    pause_state: Res<ActionPauseState>,
-) {
-    if pause_state.is_paused {
-        return;
-    }
-
+pub fn velocity_update(mut q_velocity: Query<(&Velocity, &mut Transform)>, clock: Res<GameClock>) {
     for (velocity, mut transform) in q_velocity.iter_mut() {
         transform.translation += Vec3 {
             x: velocity.vec.x,
             y: velocity.vec.y,
             z: 0.,
-        } * time.delta_seconds()
+        } * clock.delta_seconds()
             * DISTANCE_SCALING;
     }
 }
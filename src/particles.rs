@@ -0,0 +1,172 @@
+use std::f32::consts::PI;
+
+use bevy::prelude::*;
+
+use crate::{
+    combat::{
+        pool::EntityPool,
+        projectile::{projectile_collision_check, Projectile, ProjectileHitEvent},
+    },
+    enemies::enemy::EnemyDeathEvent,
+    experience::xp_crystal::XPCollectedEvent,
+    movement::velocity::Velocity,
+    player::shooting::ShotFiredEvent,
+    util::rng::{GlobalSeed, RNG},
+    GameState, InGame,
+};
+
+use self::emitter::{
+    emit_particles, spawn_emitter, update_particles, PaletteColor, ParticleEffect, ParticleRNG,
+};
+
+pub mod emitter;
+
+pub const HIT_SPARKS: ParticleEffect = ParticleEffect {
+    burst: 6,
+    rate: 0.,
+    lifetime: 0.25,
+    speed: (60., 140.),
+    spread: 0.6,
+    gravity: 0.,
+    colors: &[PaletteColor::White, PaletteColor::Orange],
+    size: (4., 0.),
+};
+
+pub const DEATH_BURST: ParticleEffect = ParticleEffect {
+    burst: 16,
+    rate: 0.,
+    lifetime: 0.6,
+    speed: (40., 160.),
+    spread: PI,
+    gravity: -200.,
+    colors: &[PaletteColor::Orange, PaletteColor::Red, PaletteColor::Dark],
+    size: (6., 0.),
+};
+
+pub const MUZZLE_FLASH: ParticleEffect = ParticleEffect {
+    burst: 5,
+    rate: 0.,
+    lifetime: 0.1,
+    speed: (80., 200.),
+    spread: 0.35,
+    gravity: 0.,
+    colors: &[PaletteColor::White, PaletteColor::Orange],
+    size: (4., 1.),
+};
+
+pub const CRYSTAL_SPARKLE: ParticleEffect = ParticleEffect {
+    burst: 8,
+    rate: 0.,
+    lifetime: 0.35,
+    speed: (30., 90.),
+    spread: PI,
+    gravity: 100.,
+    colors: &[PaletteColor::White, PaletteColor::Orange],
+    size: (3., 0.),
+};
+
+pub const BULLET_TRAIL: ParticleEffect = ParticleEffect {
+    burst: 0,
+    rate: 12.,
+    lifetime: 0.2,
+    speed: (0., 20.),
+    spread: 0.3,
+    gravity: 0.,
+    colors: &[PaletteColor::Orange, PaletteColor::Red],
+    size: (3., 0.),
+};
+
+pub struct ParticlePlugin;
+
+impl Plugin for ParticlePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(GameState::Playing), init_rng)
+            .add_systems(OnExit(InGame::GameOver), init_rng)
+            .add_systems(
+                Update,
+                (
+                    hit_particles.after(projectile_collision_check),
+                    death_particles,
+                    shot_particles,
+                    crystal_particles,
+                    emit_particles,
+                    update_particles,
+                )
+                    .run_if(in_state(GameState::Playing)),
+            );
+    }
+}
+
+fn init_rng(seed: Res<GlobalSeed>, mut commands: Commands) {
+    commands.insert_resource(ParticleRNG(RNG::new(&seed.0, "particles")));
+}
+
+fn hit_particles(
+    q_projectiles: Query<(&Transform, &Velocity), With<Projectile>>,
+    mut hit_ev: EventReader<ProjectileHitEvent>,
+    mut pool: ResMut<EntityPool>,
+    mut commands: Commands,
+) {
+    for hit in hit_ev.iter() {
+        // Explosions and thorns don't fly, their own sprites are enough
+        let Ok((transform, velocity)) = q_projectiles.get(hit.projectile) else {
+            continue;
+        };
+
+        spawn_emitter(
+            transform.translation,
+            HIT_SPARKS,
+            Some(-velocity.vec),
+            &mut pool,
+            &mut commands,
+        );
+    }
+}
+
+fn death_particles(
+    mut death_ev: EventReader<EnemyDeathEvent>,
+    mut pool: ResMut<EntityPool>,
+    mut commands: Commands,
+) {
+    for death in death_ev.iter() {
+        spawn_emitter(
+            death.location,
+            DEATH_BURST,
+            Some(Vec2::Y),
+            &mut pool,
+            &mut commands,
+        );
+    }
+}
+
+fn shot_particles(
+    mut shot_ev: EventReader<ShotFiredEvent>,
+    mut pool: ResMut<EntityPool>,
+    mut commands: Commands,
+) {
+    for shot in shot_ev.iter() {
+        spawn_emitter(
+            shot.origin,
+            MUZZLE_FLASH,
+            Some(shot.direction),
+            &mut pool,
+            &mut commands,
+        );
+    }
+}
+
+fn crystal_particles(
+    mut collected_ev: EventReader<XPCollectedEvent>,
+    mut pool: ResMut<EntityPool>,
+    mut commands: Commands,
+) {
+    for collected in collected_ev.iter() {
+        spawn_emitter(
+            collected.location,
+            CRYSTAL_SPARKLE,
+            Some(Vec2::Y),
+            &mut pool,
+            &mut commands,
+        );
+    }
+}
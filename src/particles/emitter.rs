@@ -0,0 +1,188 @@
+use bevy::prelude::*;
+use rand::Rng;
+
+use crate::{
+    combat::{
+        lifetime::Lifetime,
+        pool::{EntityPool, PoolKind},
+    },
+    constants::SortingLayers,
+    movement::velocity::Velocity,
+    palette::Palette,
+    util::{clock::GameClock, rng::RNG},
+};
+
+#[derive(Resource)]
+pub struct ParticleRNG(pub RNG);
+
+#[derive(Clone, Copy, Debug)]
+pub enum PaletteColor {
+    Dark,
+    Red,
+    Orange,
+    White,
+}
+
+impl PaletteColor {
+    pub fn get(&self, palette: &Palette) -> Color {
+        match self {
+            PaletteColor::Dark => palette.dark,
+            PaletteColor::Red => palette.red,
+            PaletteColor::Orange => palette.orange,
+            PaletteColor::White => palette.white,
+        }
+    }
+}
+
+// What an emitter sends out, speeds in pixels per second and the spread in radians
+#[derive(Clone, Copy, Debug)]
+pub struct ParticleEffect {
+    // Emitted all at once when the emitter starts
+    pub burst: u32,
+    // Emitted every second after that, emitters without a rate are done after the burst
+    pub rate: f32,
+    pub lifetime: f32,
+    pub speed: (f32, f32),
+    // Half the angle of the cone around the emitter direction
+    pub spread: f32,
+    pub gravity: f32,
+    // Blended from first to last over the lifetime of a particle
+    pub colors: &'static [PaletteColor],
+    pub size: (f32, f32),
+}
+
+#[derive(Component)]
+pub struct ParticleEmitter {
+    pub effect: ParticleEffect,
+    // Falls back on going against the velocity of the entity, like a trail
+    pub direction: Option<Vec2>,
+    has_burst: bool,
+    pending: f32,
+}
+
+impl ParticleEmitter {
+    pub fn new(effect: ParticleEffect, direction: Option<Vec2>) -> ParticleEmitter {
+        ParticleEmitter {
+            effect,
+            direction,
+            has_burst: false,
+            pending: 0.,
+        }
+    }
+}
+
+#[derive(Component)]
+pub struct Particle {
+    velocity: Vec2,
+    gravity: f32,
+    colors: &'static [PaletteColor],
+    size: (f32, f32),
+}
+
+// Emitters that only burst are spawned on their own and go away once they did
+pub fn spawn_emitter(
+    position: Vec3,
+    effect: ParticleEffect,
+    direction: Option<Vec2>,
+    pool: &mut EntityPool,
+    commands: &mut Commands,
+) {
+    pool.spawn(PoolKind::Emitter, commands)
+        .insert(TransformBundle::from_transform(
+            Transform::from_translation(position),
+        ))
+        .insert(ParticleEmitter::new(effect, direction));
+}
+
+fn color_over_life(colors: &[PaletteColor], palette: &Palette, t: f32) -> Color {
+    match colors {
+        [] => palette.white,
+        [color] => color.get(palette),
+        _ => {
+            let scaled = t.clamp(0., 1.) * (colors.len() - 1) as f32;
+            let index = (scaled.floor() as usize).min(colors.len() - 2);
+            let from = Vec4::from(colors[index].get(palette).as_rgba_f32());
+            let to = Vec4::from(colors[index + 1].get(palette).as_rgba_f32());
+
+            Color::from(from.lerp(to, scaled - index as f32))
+        }
+    }
+}
+
+pub fn emit_particles(
+    mut q_emitters: Query<(Entity, &mut ParticleEmitter, &Transform, Option<&Velocity>)>,
+    palette: Res<Palette>,
+    mut rng: ResMut<ParticleRNG>,
+    mut pool: ResMut<EntityPool>,
+    clock: Res<GameClock>,
+    mut commands: Commands,
+) {
+    for (entity, mut emitter, transform, velocity) in q_emitters.iter_mut() {
+        let effect = emitter.effect;
+        let mut count = 0;
+
+        if !emitter.has_burst {
+            emitter.has_burst = true;
+            count += effect.burst;
+        }
+
+        emitter.pending += effect.rate * clock.delta_seconds();
+        count += emitter.pending.floor() as u32;
+        emitter.pending = emitter.pending.fract();
+
+        let direction = emitter
+            .direction
+            .or_else(|| velocity.map(|velocity| -velocity.vec))
+            .and_then(|direction| direction.try_normalize())
+            .unwrap_or(Vec2::Y);
+        let position = transform.translation.truncate();
+
+        for _ in 0..count {
+            let angle = rng.0 .0.gen_range(-effect.spread..=effect.spread);
+            let speed = rng.0 .0.gen_range(effect.speed.0..=effect.speed.1);
+
+            pool.spawn(PoolKind::Particle, &mut commands)
+                .insert(SpriteBundle {
+                    sprite: Sprite {
+                        color: color_over_life(effect.colors, &palette, 0.),
+                        custom_size: Some(Vec2::splat(effect.size.0)),
+                        ..Default::default()
+                    },
+                    transform: Transform::from_translation(
+                        position.extend(SortingLayers::Front.into()),
+                    ),
+                    ..Default::default()
+                })
+                .insert(Particle {
+                    velocity: Vec2::from_angle(angle).rotate(direction) * speed,
+                    gravity: effect.gravity,
+                    colors: effect.colors,
+                    size: effect.size,
+                })
+                .insert(Lifetime::from_seconds(effect.lifetime));
+        }
+
+        if effect.rate <= 0. {
+            pool.release(entity, &mut commands);
+        }
+    }
+}
+
+// Lifetimes take care of removing them
+pub fn update_particles(
+    mut q_particles: Query<(&mut Particle, &Lifetime, &mut Transform, &mut Sprite)>,
+    palette: Res<Palette>,
+    clock: Res<GameClock>,
+) {
+    for (mut particle, lifetime, mut transform, mut sprite) in q_particles.iter_mut() {
+        let t = 1. - lifetime.percent_left();
+
+        particle.velocity.y += particle.gravity * clock.delta_seconds();
+        transform.translation += (particle.velocity * clock.delta_seconds()).extend(0.);
+
+        sprite.color = color_over_life(particle.colors, &palette, t);
+        sprite.custom_size = Some(Vec2::splat(
+            particle.size.0 + (particle.size.1 - particle.size.0) * t,
+        ));
+    }
+}
@@ -0,0 +1,271 @@
+use bevy::prelude::*;
+use bevy_kira_audio::AudioControl;
+use rand::Rng;
+
+use crate::{
+    audio::FXChannel,
+    combat::health::{DamageSource, Health, TookDamageEvent},
+    constants::SortingLayers,
+    enemies::enemy::{Enemy, EnemyDeathEvent, EnemyType},
+    experience::{experience::Experience, xp_crystal::XPCrystal},
+    loading::{AbilityTextures, AudioAssets},
+    movement::{
+        edge_teleport::EdgeTeleports, fake_magnetic::FakeMagnetic, friction::Friction,
+        velocity::Velocity,
+    },
+    player::Player,
+    util::{
+        clock::{clock_is_running, GameClock},
+        despawn_on_exit::DespawnOnExit,
+        radians::Radian,
+        rng::{GlobalSeed, RNG},
+    },
+    GameState, InGame,
+};
+
+pub struct PickupPlugin;
+
+impl Plugin for PickupPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<ChestOpenedEvent>()
+            .add_systems(OnEnter(GameState::Playing), create_pickup_rng)
+            .add_systems(OnExit(InGame::GameOver), create_pickup_rng)
+            .add_systems(
+                Update,
+                (
+                    drop_pickups,
+                    pickup_update,
+                    collect_pickups.run_if(clock_is_running),
+                    damage_boost_update,
+                )
+                    .run_if(in_state(GameState::Playing)),
+            );
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum PickupKind {
+    Heart,
+    Vacuum,
+    Bomb,
+    DamageBoost,
+    Chest,
+}
+
+impl PickupKind {
+    // Pickups borrow the ability icons closest to what they do
+    pub fn get_texture(&self, textures: &Res<AbilityTextures>) -> Handle<Image> {
+        match self {
+            PickupKind::Heart => textures.max_hp.clone(),
+            PickupKind::Vacuum => textures.magnet.clone(),
+            PickupKind::Bomb => textures.deathrattle.clone(),
+            PickupKind::DamageBoost => textures.shells.clone(),
+            PickupKind::Chest => textures.bullets_galore.clone(),
+        }
+    }
+
+    pub fn get_sound(&self, audio: &Res<AudioAssets>) -> Handle<bevy_kira_audio::AudioSource> {
+        match self {
+            PickupKind::Heart => audio.vial.clone(),
+            PickupKind::Vacuum => audio.big_crystal.clone(),
+            PickupKind::Bomb => audio.explosion.clone(),
+            PickupKind::DamageBoost => audio.reload_done.clone(),
+            PickupKind::Chest => audio.levelup.clone(),
+        }
+    }
+}
+
+/*
+Chance of each pickup dropping, checked in order, at most one drop per death
+*/
+pub fn drop_table(enemy_type: EnemyType) -> Vec<(PickupKind, f32)> {
+    match enemy_type {
+        EnemyType::Imp => vec![
+            (PickupKind::Heart, 0.01),
+            (PickupKind::DamageBoost, 0.005),
+            (PickupKind::Vacuum, 0.003),
+        ],
+        EnemyType::ImpQueen => vec![
+            (PickupKind::Chest, 0.1),
+            (PickupKind::Bomb, 0.1),
+            (PickupKind::Heart, 0.2),
+        ],
+        EnemyType::Beholder => vec![
+            (PickupKind::Heart, 0.03),
+            (PickupKind::DamageBoost, 0.03),
+            (PickupKind::Bomb, 0.02),
+            (PickupKind::Vacuum, 0.01),
+        ],
+        EnemyType::BeholderPrince => vec![
+            (PickupKind::Chest, 0.25),
+            (PickupKind::Vacuum, 0.15),
+            (PickupKind::Heart, 0.25),
+        ],
+        EnemyType::Reaper => vec![(PickupKind::Chest, 0.5), (PickupKind::Heart, 0.3)],
+    }
+}
+
+pub const PICKUP_LIFETIME: f32 = 20.;
+// Pickups blink for this long before disappearing
+pub const PICKUP_BLINK_TIME: f32 = 3.;
+pub const PICKUP_DISTANCE: f32 = 20.;
+pub const VACUUM_FORCE: f32 = 100_000_000.;
+pub const BOMB_DAMAGE: u32 = 50;
+pub const DAMAGE_BOOST_MULT: f32 = 2.;
+pub const DAMAGE_BOOST_DURATION: f32 = 10.;
+
+#[derive(Resource)]
+pub struct PickupRNG(pub RNG);
+
+#[derive(Component)]
+pub struct Pickup {
+    pub kind: PickupKind,
+    lifetime: Timer,
+}
+
+// Opens an ability selection without leveling up
+#[derive(Event)]
+pub struct ChestOpenedEvent;
+
+#[derive(Component)]
+pub struct DamageBoost(Timer);
+
+fn create_pickup_rng(seed: Res<GlobalSeed>, mut commands: Commands) {
+    commands.insert_resource(PickupRNG(RNG::new(seed.0.as_str(), "pickup_rng")))
+}
+
+fn drop_pickups(
+    mut death_ev: EventReader<EnemyDeathEvent>,
+    mut rng: ResMut<PickupRNG>,
+    textures: Res<AbilityTextures>,
+    mut commands: Commands,
+) {
+    for death in death_ev.iter() {
+        let rng = &mut rng.0 .0;
+        let dropped = drop_table(death.enemy.enemy_type)
+            .into_iter()
+            .find(|(_, chance)| rng.gen_range(0. ..1.) < *chance);
+
+        let Some((kind, _)) = dropped else {
+            continue;
+        };
+
+        let direction = Radian {
+            angle: rng.gen_range(Radian::ZERO.angle..Radian::FULL.angle),
+        };
+
+        commands
+            .spawn(SpriteBundle {
+                transform: Transform::from_translation(Vec3 {
+                    x: death.location.x,
+                    y: death.location.y,
+                    z: SortingLayers::BehindAction.into(),
+                }),
+                texture: kind.get_texture(&textures),
+                ..Default::default()
+            })
+            .insert(Pickup {
+                kind,
+                lifetime: Timer::from_seconds(PICKUP_LIFETIME, TimerMode::Once),
+            })
+            .insert(Velocity::from(direction.unit_vector() * 30.))
+            .insert(Friction { force: 50.0 })
+            .insert(EdgeTeleports)
+            .insert(DespawnOnExit::run());
+    }
+}
+
+fn pickup_update(
+    mut q_pickups: Query<(Entity, &mut Pickup, &mut Visibility)>,
+    clock: Res<GameClock>,
+    mut commands: Commands,
+) {
+    for (entity, mut pickup, mut visibility) in q_pickups.iter_mut() {
+        pickup.lifetime.tick(clock.delta());
+
+        if pickup.lifetime.finished() {
+            commands.entity(entity).despawn_recursive();
+            continue;
+        }
+
+        let remaining = pickup.lifetime.remaining_secs();
+        *visibility = match remaining < PICKUP_BLINK_TIME && (remaining * 8.) as u32 % 2 == 0 {
+            true => Visibility::Hidden,
+            false => Visibility::Inherited,
+        };
+    }
+}
+
+fn collect_pickups(
+    q_pickups: Query<(Entity, &Transform, &Pickup), Without<Player>>,
+    mut q_player: Query<(Entity, &Transform, &mut Health, &Experience, &mut Player)>,
+    mut q_enemies: Query<(Entity, &mut Health), (With<Enemy>, Without<Player>)>,
+    mut q_crystals: Query<&mut FakeMagnetic, With<XPCrystal>>,
+    mut took_damage_ev: EventWriter<TookDamageEvent>,
+    mut chest_ev: EventWriter<ChestOpenedEvent>,
+    audio: Res<AudioAssets>,
+    fx: Res<FXChannel>,
+    mut commands: Commands,
+) {
+    let (player_entity, player_transform, mut health, experience, mut player) =
+        q_player.single_mut();
+
+    for (entity, transform, pickup) in q_pickups.iter() {
+        let distance = transform.translation.distance(player_transform.translation);
+
+        if distance > experience.pick_distance + PICKUP_DISTANCE {
+            continue;
+        }
+
+        match pickup.kind {
+            PickupKind::Heart => {
+                health.value = health.max.min(health.value + 1);
+            }
+            PickupKind::Vacuum => {
+                for mut magnetic in q_crystals.iter_mut() {
+                    magnetic.force = VACUUM_FORCE;
+                }
+            }
+            PickupKind::Bomb => {
+                for (enemy, mut enemy_health) in q_enemies.iter_mut() {
+                    enemy_health.take_damage(
+                        enemy,
+                        &mut took_damage_ev,
+                        BOMB_DAMAGE,
+                        DamageSource::Explosion,
+                    );
+                }
+            }
+            PickupKind::DamageBoost => {
+                player.damage_boost = DAMAGE_BOOST_MULT;
+                commands
+                    .entity(player_entity)
+                    .insert(DamageBoost(Timer::from_seconds(
+                        DAMAGE_BOOST_DURATION,
+                        TimerMode::Once,
+                    )));
+            }
+            PickupKind::Chest => {
+                chest_ev.send(ChestOpenedEvent);
+            }
+        }
+
+        fx.play(pickup.kind.get_sound(&audio));
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
+fn damage_boost_update(
+    mut q_player: Query<(Entity, &mut Player, &mut DamageBoost)>,
+    clock: Res<GameClock>,
+    mut commands: Commands,
+) {
+    for (entity, mut player, mut boost) in q_player.iter_mut() {
+        boost.0.tick(clock.delta());
+
+        if boost.0.finished() {
+            player.damage_boost = 1.;
+            commands.entity(entity).remove::<DamageBoost>();
+        }
+    }
+}
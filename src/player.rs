@@ -2,24 +2,26 @@ use crate::actions::Actions;
 use crate::animation::controller::AnimationController;
 use crate::animation::{make_animation_bundle, AnimationStateChangeEvent, AppAnimationSetup};
 use crate::audio::FXChannel;
+use crate::camera::{HitStopEvent, ScreenShakeEvent};
 use crate::collision::collider::{Collider, IsCollidingEvent};
-use crate::combat::fire::Fire;
-use crate::combat::health::{DeathEvent, Health, HealthType, TookDamageEvent};
-use crate::combat::projectile::{projectile_collision_check, Projectile};
+use crate::combat::health::{DamageSource, DeathEvent, Health, HealthType, TookDamageEvent};
+use crate::combat::projectile::projectile_collision_check;
 use crate::combat::teams::{Team, TeamMember};
 use crate::constants::SortingLayers;
 use crate::enemies::enemy::Enemy;
 use crate::enemies::spawning::SpawnInfo;
-use crate::experience::experience::Experience;
-use crate::experience::taken_abilities::TakenAbility;
-use crate::experience::xp_crystal::XPCrystal;
+use crate::experience::ability_selection::start_ability_selection;
+use crate::experience::experience::{Experience, PendingSelections, XP_CURVE};
 use crate::loading::{AudioAssets, FontAssets, TextureAssets};
 use crate::movement::edge_teleport::EdgeTeleports;
-use crate::movement::pause::{ActionPauseState, PauseMenuComponent};
+use crate::meta::{souls_for_run, StartingUpgradesPending, BASE_PICK_DISTANCE};
 use crate::palette::Palette;
-use crate::ui::game_timer::GameTimer;
+use crate::stats::summary::spawn_run_summary;
+use crate::stats::{RunEndedEvent, RunStats};
+use crate::util::clock::{clock_is_running, GameClock};
+use crate::util::despawn_on_exit::{AppDespawnOnExit, DespawnOnExit};
 use crate::util::pitch_rng::PitchRNG;
-use crate::GameState;
+use crate::{GameState, InGame};
 use bevy::prelude::*;
 
 use bevy_kira_audio::AudioControl;
@@ -28,20 +30,28 @@ use rand::Rng;
 use self::ability::Ability;
 use self::animations::{PlayerAnimationState, PlayerAnimations};
 use self::bullets_ui::{manage_bullet_ui_sprites, BulletUIAnimation, BulletUICount};
+use self::character::{Character, SelectedCharacter};
+use self::character_select::CharacterSelectPlugin;
+use self::dash::{DashPlugin, Dashing};
 use self::health_ui::{manage_health_ui_sprites, HealthUIAnimationState, HealthUICount};
 use self::hit::{spawn_hit_sprite, update_hit_sprite};
 use self::reload_ui::{spawn_reload_ui, update_reload_ui, ReloadTimer};
-use self::shooting::{shoot, ShootingCooldown};
+use self::skills::SkillsPlugin;
+use self::shooting::{shoot, ShootingCooldown, ShotFiredEvent};
 use self::thorns::{thorns_update, ThornsAnimation, ThornsTimer};
-use self::vial::{VialPlugin, Vial};
+use self::vial::VialPlugin;
 
 pub mod ability;
 mod animations;
 mod bullets_ui;
+pub mod character;
+mod character_select;
+pub mod dash;
 mod health_ui;
 mod hit;
 mod reload_ui;
-mod shooting;
+pub mod shooting;
+pub mod skills;
 mod thorns;
 mod vial;
 
@@ -53,13 +63,33 @@ pub struct Player {
     max_bullets: u32,
     is_reloading: bool,
     pub abilities: Vec<Ability>,
+    pub character: Character,
+    // Makes rarer abilities show up more often
+    pub luck: u32,
+    // Temporary multiplier from pickups
+    pub damage_boost: f32,
 }
 
 impl Player {
+    // Fresh for the start of a run, starting upgrades are applied on top
+    pub fn new(character: Character) -> Player {
+        Player {
+            max_bullets: character.max_bullets(),
+            curr_bullets: character.max_bullets(),
+            is_reloading: false,
+            abilities: vec![],
+            character,
+            luck: 0,
+            damage_boost: 1.,
+        }
+    }
+
     pub fn damage(&self) -> HealthType {
         self.abilities
             .iter()
-            .fold(5., |dmg, ability| dmg * ability.damage_mult()) as u32
+            .fold(5. * self.character.damage_mult() * self.damage_boost, |dmg, ability| {
+                dmg * ability.damage_mult()
+            }) as u32
     }
 
     pub fn shoot_time(&self) -> f32 {
@@ -71,7 +101,15 @@ impl Player {
     pub fn reload_time(&self) -> f32 {
         self.abilities
             .iter()
-            .fold(1.0, |dmg, ability| dmg / ability.reload_mult())
+            .fold(1.0 / self.character.reload_mult(), |dmg, ability| {
+                dmg / ability.reload_mult()
+            })
+    }
+
+    pub fn xp_mult(&self) -> f32 {
+        self.abilities
+            .iter()
+            .fold(1.0, |mult, ability| mult * ability.xp_mult())
     }
 
     pub fn knockback(&self) -> f32 {
@@ -79,6 +117,24 @@ impl Player {
             .iter()
             .fold(20., |dmg, ability| dmg * ability.knockback_mult())
     }
+
+    pub fn bullet_speed(&self) -> f32 {
+        let mut speed: f32 = 500.;
+
+        if self.abilities.contains(&Ability::Sniper) {
+            speed *= 2.;
+        }
+
+        if self.abilities.contains(&Ability::BigBullets) {
+            speed *= 0.8;
+        }
+
+        if self.abilities.contains(&Ability::BiggestBullets) {
+            speed *= 0.8;
+        }
+
+        speed
+    }
 }
 
 /// This plugin handles player related stuff like movement
@@ -87,19 +143,26 @@ impl Plugin for PlayerPlugin {
     fn build(&self, app: &mut App) {
         app.add_systems(
             OnEnter(GameState::Playing),
-            (spawn_player, spawn_reload_ui, spawn_hit_sprite),
+            (
+                spawn_player,
+                reset_player_timers,
+                spawn_reload_ui,
+                spawn_hit_sprite,
+            ),
         )
         .add_systems(
             Update,
             (
-                move_player,
-                shoot,
+                move_player.run_if(clock_is_running),
+                shoot.run_if(clock_is_running),
                 manage_bullet_ui_sprites,
                 manage_health_ui_sprites,
                 update_reload_ui,
-                game_over,
+                game_over
+                    .after(start_ability_selection)
+                    .run_if(in_state(InGame::Running)),
                 click_play_again_button,
-                enemy_collision,
+                enemy_collision.run_if(clock_is_running),
                 thorns_update,
                 update_hit_sprite,
                 update_bullets,
@@ -109,31 +172,29 @@ impl Plugin for PlayerPlugin {
             )
                 .run_if(in_state(GameState::Playing)),
         )
-        .insert_resource(ReloadTimer(Timer::from_seconds(0., TimerMode::Once)))
+        .add_systems(OnEnter(InGame::GameOver), spawn_game_over_screen)
+        .add_systems(OnExit(InGame::GameOver), (restart_run, reset_player_timers))
+        .despawn_on_exit(InGame::GameOver)
         .insert_resource(BulletUICount(0))
-        .insert_resource(ThornsTimer(Timer::from_seconds(0., TimerMode::Once)))
         .insert_resource(HealthUICount(0))
-        .insert_resource(InvincibilityTimer(Timer::from_seconds(
-            3.0,
-            TimerMode::Once,
-        )))
-        .insert_resource(ShootingCooldown(Timer::from_seconds(1.0, TimerMode::Once)))
+        .add_event::<ShotFiredEvent>()
         .add_animation::<PlayerAnimationState>()
         .add_animation::<BulletUIAnimation>()
         .add_animation::<HealthUIAnimationState>()
         .add_animation::<ThornsAnimation>()
-        .add_plugins(VialPlugin);
+        .add_plugins((VialPlugin, CharacterSelectPlugin, SkillsPlugin, DashPlugin));
     }
 }
 
 pub fn spawn_player(
+    selected: Res<SelectedCharacter>,
     player_animations: Res<PlayerAnimations>,
     textures: Res<TextureAssets>,
     mut texture_atlases: ResMut<Assets<TextureAtlas>>,
     mut commands: Commands,
 ) {
     let texture_atlas = TextureAtlas::from_grid(
-        textures.hatman.clone(),
+        selected.0.get_texture(&textures),
         Vec2 { x: 32., y: 32. },
         6,
         1,
@@ -143,12 +204,7 @@ pub fn spawn_player(
     let texture_atlas_handle = texture_atlases.add(texture_atlas);
 
     commands
-        .spawn(Player {
-            max_bullets: 6,
-            curr_bullets: 6,
-            is_reloading: false,
-            abilities: vec![],
-        })
+        .spawn(Player::new(selected.0))
         .insert(Collider::new_rect(Vec2 { x: 15., y: 30. }))
         .insert(make_animation_bundle(
             PlayerAnimationState::Idle,
@@ -164,17 +220,30 @@ pub fn spawn_player(
         .insert(Experience {
             curr_experience: 0,
             level: 0,
-            threshold: 20,
-            pick_distance: 10.0,
+            threshold: XP_CURVE.threshold(0),
+            pick_distance: BASE_PICK_DISTANCE,
+            fraction: 0.,
         })
         .insert(EdgeTeleports)
-        .insert(Health::new(3))
+        .insert(Health::new(selected.0.max_health()))
+        .insert(StartingUpgradesPending)
         .insert(TeamMember { team: Team::Player });
 }
 
+// Nothing left over from the last run, like a half finished reload
+fn reset_player_timers(mut commands: Commands) {
+    commands.insert_resource(ReloadTimer(Timer::from_seconds(0., TimerMode::Once)));
+    commands.insert_resource(ThornsTimer(Timer::from_seconds(0., TimerMode::Once)));
+    commands.insert_resource(InvincibilityTimer(Timer::from_seconds(
+        3.0,
+        TimerMode::Once,
+    )));
+    commands.insert_resource(ShootingCooldown(Timer::from_seconds(1.0, TimerMode::Once)));
+}
+
 fn update_bullets(mut q_player: Query<&mut Player>) {
     let mut player = q_player.single_mut();
-    player.max_bullets = 6
+    player.max_bullets = player.character.max_bullets()
         + (player
             .abilities
             .iter()
@@ -184,7 +253,7 @@ fn update_bullets(mut q_player: Query<&mut Player>) {
 }
 
 fn move_player(
-    time: Res<Time>,
+    clock: Res<GameClock>,
     actions: Res<Actions>,
     mut animation_change: EventWriter<AnimationStateChangeEvent<PlayerAnimationState>>,
     mut player_query: Query<(
@@ -193,16 +262,17 @@ fn move_player(
         &mut Transform,
         &mut AnimationController<PlayerAnimationState>,
         &mut TextureAtlasSprite,
+        Option<&Dashing>,
     )>,
-    pause: Res<ActionPauseState>,
 ) {
-    if pause.is_paused {
+    let (entity, player, mut player_transform, mut animation_controller, _, dashing) =
+        player_query.single_mut();
+
+    // The dash moves the player on its own
+    if dashing.is_some() {
         return;
     }
 
-    let (entity, player, mut player_transform, mut animation_controller, _) =
-        player_query.single_mut();
-
     if actions.player_movement.is_none() {
         if animation_controller.get_state() != PlayerAnimationState::Idle {
             animation_change.send(AnimationStateChangeEvent {
@@ -218,10 +288,10 @@ fn move_player(
         .iter()
         .filter(|ability| ability == &&Ability::Faster)
         .count();
-    let speed = 150. + 50. * faster_buffs as f32;
+    let speed = player.character.speed() + 50. * faster_buffs as f32;
     let movement = Vec3::new(
-        actions.player_movement.unwrap().x * speed * time.delta_seconds(),
-        actions.player_movement.unwrap().y * speed * time.delta_seconds(),
+        actions.player_movement.unwrap().x * speed * clock.delta_seconds(),
+        actions.player_movement.unwrap().y * speed * clock.delta_seconds(),
         0.,
     );
 
@@ -248,17 +318,14 @@ pub fn hit_immunity(
     mut q_player: Query<(Entity, &mut Health), With<Player>>,
     mut timer: ResMut<InvincibilityTimer>,
     mut ev_dmg: EventReader<TookDamageEvent>,
-    time: Res<Time>,
-    pause: Res<ActionPauseState>,
+    clock: Res<GameClock>,
     audio_assets: Res<AudioAssets>,
     fx_channel: Res<FXChannel>,
     mut pitch_rng: ResMut<PitchRNG>,
+    mut shake_ev: EventWriter<ScreenShakeEvent>,
+    mut hit_stop_ev: EventWriter<HitStopEvent>,
 ) {
-    if pause.is_paused {
-        return;
-    }
-
-    timer.0.tick(time.delta());
+    timer.0.tick(clock.delta());
     let (player_entity, mut player_health) = q_player.single_mut();
 
     if timer.0.just_finished() {
@@ -273,6 +340,9 @@ pub fn hit_immunity(
             fx_channel
                 .play(audio_assets.grunt.clone())
                 .with_playback_rate(pitch_rng.0 .0.gen_range(0.9..1.1));
+
+            shake_ev.send(ScreenShakeEvent { trauma: 0.5 });
+            hit_stop_ev.send(HitStopEvent { duration: 0.08 });
         }
     }
 }
@@ -282,12 +352,7 @@ pub fn enemy_collision(
     mut q_enemies: Query<(Entity, &mut Health), With<Enemy>>,
     mut collisions: EventReader<IsCollidingEvent>,
     mut ev_dmg: EventWriter<TookDamageEvent>,
-    pause: Res<ActionPauseState>,
 ) {
-    if pause.is_paused {
-        return;
-    }
-
     let (player, _player_stats, mut health) = q_player.single_mut();
     let mut is_hit = false;
 
@@ -309,249 +374,135 @@ pub fn enemy_collision(
     }
 
     if is_hit {
-        health.take_damage(player, &mut ev_dmg, 1);
+        health.take_damage(player, &mut ev_dmg, 1, DamageSource::Enemy);
     }
 }
 
 pub fn game_over(
     q_player: Query<Entity, With<Player>>,
     mut death_evs: EventReader<DeathEvent>,
-    mut pause: ResMut<ActionPauseState>,
-    palette: Res<Palette>,
-    font_assets: Res<FontAssets>,
     spawn_info: Res<SpawnInfo>,
-    mut commands: Commands,
+    mut run_ended_ev: EventWriter<RunEndedEvent>,
+    mut next_in_game: ResMut<NextState<InGame>>,
 ) {
     let player = q_player.single();
 
-    for death_ev in death_evs.iter() {
-        if death_ev.entity == player {
-            if pause.is_paused {
-                return;
-            }
+    if death_evs.iter().any(|death_ev| death_ev.entity == player) {
+        run_ended_ev.send(RunEndedEvent {
+            survived: spawn_info.game.elapsed(),
+        });
+        next_in_game.set(InGame::GameOver);
+    }
+}
 
-            pause.is_paused = true;
+#[derive(Component)]
+pub struct PlayAgainButton;
 
-            commands
-                .spawn(NodeBundle {
+fn spawn_game_over_screen(
+    palette: Res<Palette>,
+    font_assets: Res<FontAssets>,
+    spawn_info: Res<SpawnInfo>,
+    stats: Res<RunStats>,
+    mut commands: Commands,
+) {
+    commands
+        .spawn(NodeBundle {
+            style: Style {
+                display: Display::Grid,
+                width: Val::Percent(100.),
+                align_items: AlignItems::Center,
+                justify_content: JustifyContent::Center,
+                grid_auto_flow: GridAutoFlow::Row,
+                ..Default::default()
+            },
+            ..Default::default()
+        })
+        .insert(DespawnOnExit(InGame::GameOver))
+        .with_children(|parent| {
+            parent.spawn(
+                TextBundle::from_section(
+                    "You did not survive",
+                    TextStyle {
+                        font: font_assets.gothic_pxl.clone(),
+                        font_size: 100.0,
+                        color: palette.orange,
+                    },
+                )
+                .with_style(Style {
+                    margin: UiRect::all(Val::Auto),
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    ..default()
+                }),
+            );
+
+            parent.spawn(
+                TextBundle::from_section(
+                    format!(
+                        "Thou lasted {}:{}{}, earning {} souls",
+                        (spawn_info.game.elapsed().as_secs() / 60) as u32,
+                        match spawn_info.game.elapsed().as_secs() % 60 < 10 {
+                            true => "0",
+                            false => "",
+                        },
+                        spawn_info.game.elapsed().as_secs() % 60,
+                        souls_for_run(&stats, spawn_info.game.elapsed())
+                    ),
+                    TextStyle {
+                        font: font_assets.gothic.clone(),
+                        font_size: 40.0,
+                        color: palette.white,
+                    },
+                )
+                .with_style(Style {
+                    margin: UiRect::all(Val::Auto),
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    ..default()
+                }),
+            );
+
+            spawn_run_summary(parent, &stats, &font_assets, &palette);
+
+            parent
+                .spawn(ButtonBundle {
                     style: Style {
-                        display: Display::Grid,
-                        width: Val::Percent(100.),
-                        align_items: AlignItems::Center,
+                        width: Val::Px(250.0),
+                        height: Val::Px(50.0),
+                        margin: UiRect::all(Val::Auto),
                         justify_content: JustifyContent::Center,
-                        grid_auto_flow: GridAutoFlow::Row,
+                        align_items: AlignItems::Center,
                         ..Default::default()
                     },
+                    background_color: palette.dark.into(),
                     ..Default::default()
                 })
+                .insert(PlayAgainButton)
                 .with_children(|parent| {
-                    parent.spawn(
-                        TextBundle::from_section(
-                            "You did not survive",
-                            TextStyle {
-                                font: font_assets.gothic_pxl.clone(),
-                                font_size: 100.0,
-                                color: palette.orange,
-                            },
-                        )
-                        .with_style(Style {
-                            margin: UiRect::all(Val::Auto),
-                            justify_content: JustifyContent::Center,
-                            align_items: AlignItems::Center,
-                            ..default()
-                        }),
-                    );
-
-                    parent.spawn(
-                        TextBundle::from_section(
-                            format!(
-                                "Thou lasted {}:{}{}",
-                                (spawn_info.game.elapsed().as_secs() / 60) as u32,
-                                match spawn_info.game.elapsed().as_secs() % 60 < 10 {
-                                    true => "0",
-                                    false => "",
-                                },
-                                spawn_info.game.elapsed().as_secs() % 60
-                            ),
-                            TextStyle {
-                                font: font_assets.gothic.clone(),
-                                font_size: 40.0,
-                                color: palette.white,
-                            },
-                        )
-                        .with_style(Style {
-                            margin: UiRect::all(Val::Auto),
-                            justify_content: JustifyContent::Center,
-                            align_items: AlignItems::Center,
-                            ..default()
-                        }),
-                    );
-
-                    parent
-                        .spawn(ButtonBundle {
-                            style: Style {
-                                width: Val::Px(250.0),
-                                height: Val::Px(50.0),
-                                margin: UiRect::all(Val::Auto),
-                                justify_content: JustifyContent::Center,
-                                align_items: AlignItems::Center,
-                                ..Default::default()
-                            },
-                            background_color: palette.dark.into(),
-                            ..Default::default()
-                        })
-                        .with_children(|parent| {
-                            parent.spawn(TextBundle::from_section(
-                                "Play Again",
-                                TextStyle {
-                                    font: font_assets.gothic.clone(),
-                                    font_size: 40.0,
-                                    color: palette.white,
-                                },
-                            ));
-                        });
+                    parent.spawn(TextBundle::from_section(
+                        "Play Again",
+                        TextStyle {
+                            font: font_assets.gothic.clone(),
+                            font_size: 40.0,
+                            color: palette.white,
+                        },
+                    ));
                 });
-        }
-    }
+        });
 }
 
 fn click_play_again_button(
     palette: Res<Palette>,
     mut interaction_query: Query<
-        (Entity, &Interaction, &mut BackgroundColor),
-        (
-            Changed<Interaction>,
-            With<Button>,
-            Without<PauseMenuComponent>,
-        ),
-    >,
-    mut q_player: Query<
-        (&mut Player, &mut Transform, &mut Health, &mut Experience),
-        Without<Button>,
+        (&Interaction, &mut BackgroundColor),
+        (Changed<Interaction>, With<PlayAgainButton>),
     >,
-    q_enemies: Query<Entity, (With<Enemy>, Without<Button>, Without<Player>)>,
-    q_projectile: Query<
-        Entity,
-        (
-            With<Projectile>,
-            Without<Button>,
-            Without<Player>,
-            Without<Enemy>,
-        ),
-    >,
-    q_fire: Query<
-        Entity,
-        (
-            With<Fire>,
-            Without<Projectile>,
-            Without<Button>,
-            Without<Player>,
-            Without<Enemy>,
-        ),
-    >,
-    q_xp: Query<
-        Entity,
-        (
-            With<XPCrystal>,
-            Without<Fire>,
-            Without<Projectile>,
-            Without<Button>,
-            Without<Player>,
-            Without<Enemy>,
-        ),
-    >,
-    q_node: Query<
-        Entity,
-        (
-            With<Node>,
-            Without<GameTimer>,
-            Without<XPCrystal>,
-            Without<Fire>,
-            Without<Projectile>,
-            Without<Button>,
-            Without<Player>,
-            Without<Enemy>,
-        ),
-    >,
-    q_taken: Query<
-        Entity,
-        (
-            With<TakenAbility>,
-            Without<Node>,
-            Without<GameTimer>,
-            Without<XPCrystal>,
-            Without<Fire>,
-            Without<Projectile>,
-            Without<Button>,
-            Without<Player>,
-            Without<Enemy>,
-        ),
-    >,
-    q_vial: Query<
-        Entity,
-        (
-            With<Vial>,
-            Without<TakenAbility>,
-            Without<Node>,
-            Without<GameTimer>,
-            Without<XPCrystal>,
-            Without<Fire>,
-            Without<Projectile>,
-            Without<Button>,
-            Without<Player>,
-            Without<Enemy>,
-        ),
-    >,
-    mut pause: ResMut<ActionPauseState>,
-    mut spawning: ResMut<SpawnInfo>,
-    mut commands: Commands,
+    mut next_in_game: ResMut<NextState<InGame>>,
 ) {
-    for (button_entity, interaction, mut color) in &mut interaction_query {
+    for (interaction, mut color) in &mut interaction_query {
         match *interaction {
             Interaction::Pressed => {
-                let (mut player, mut transform, mut health, mut experience) = q_player.single_mut();
-                player.abilities = vec![];
-                transform.translation.x = 0.;
-                transform.translation.y = 0.;
-                health.max = 3;
-                health.value = 3;
-                experience.curr_experience = 0;
-                experience.threshold = 20;
-
-                for enemy in q_enemies.iter() {
-                    commands.entity(enemy).despawn_recursive();
-                }
-
-                for projectile in q_projectile.iter() {
-                    commands.entity(projectile).despawn_recursive();
-                }
-
-                for fire in q_fire.iter() {
-                    commands.entity(fire).despawn_recursive();
-                }
-
-                for xp in q_xp.iter() {
-                    commands.entity(xp).despawn_recursive();
-                }
-
-                for text in q_node.iter() {
-                    commands.entity(text).despawn_recursive();
-                }
-
-                for taken in q_taken.iter() {
-                    commands.entity(taken).despawn_recursive();
-                }
-
-                for vial in q_vial.iter() {
-                    commands.entity(vial).despawn_recursive();
-                }
-
-                commands.entity(button_entity).despawn_recursive();
-                spawning.count = 0;
-                spawning.timer.reset();
-                spawning.game.reset();
-
-                pause.is_paused = false;
+                next_in_game.set(InGame::Running);
             }
             Interaction::Hovered => {
                 *color = palette.orange.into();
@@ -562,3 +513,39 @@ fn click_play_again_button(
         }
     }
 }
+
+// Everything else from the last run is despawned on the way out of the game over screen
+fn restart_run(
+    mut q_player: Query<(
+        Entity,
+        &mut Player,
+        &mut Transform,
+        &mut Health,
+        &mut Experience,
+    )>,
+    mut spawning: ResMut<SpawnInfo>,
+    mut stats: ResMut<RunStats>,
+    mut pending_selections: ResMut<PendingSelections>,
+    mut commands: Commands,
+) {
+    let (player_entity, mut player, mut transform, mut health, mut experience) =
+        q_player.single_mut();
+    *player = Player::new(player.character);
+    transform.translation.x = 0.;
+    transform.translation.y = 0.;
+    *health = Health::new(player.character.max_health());
+    experience.curr_experience = 0;
+    experience.level = 0;
+    experience.threshold = XP_CURVE.threshold(0);
+    experience.fraction = 0.;
+    pending_selections.0 = 0;
+    commands
+        .entity(player_entity)
+        .insert(StartingUpgradesPending)
+        .remove::<Dashing>();
+
+    spawning.count = 0;
+    spawning.timer.reset();
+    spawning.game.reset();
+    *stats = RunStats::default();
+}
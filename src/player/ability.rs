@@ -2,29 +2,49 @@ use bevy::prelude::*;
 
 use crate::loading::AbilityTextures;
 
+pub mod fusion;
+pub mod rarity;
+
+use self::fusion::recipe_for;
+
 #[derive(PartialEq, Eq, Hash, Clone, Copy)]
 pub enum Ability {
     BigBullets,
     BiggestBullets,
     BloodthirstyVial,
+    Boomerang,
     BulletsGalore,
     Crossbow,
     Deathrattle,
     DoubleBarrel,
     Faster,
+    FickleFate,
     FlamingBullets,
+    Grenade,
     HotterFire,
+    Hourglass,
+    Inferno,
     Magnet,
     MaxHp,
     MediumBullets,
     MegaShotgun,
+    Nimble,
+    // Adds a dash charge
+    PhantomStep,
     Piercing,
     Reload,
+    Retaliation,
+    Ricochet,
+    RubberBullets,
+    Scholar,
+    SeekingBullets,
     Shells,
+    Shockwave,
     ShootingSpeed,
     Shotgun,
     Sixfold,
     Sniper,
+    SplittingBullets,
     Thorns,
     TripleBarrel,
     Potion,
@@ -41,6 +61,7 @@ impl Ability {
             Self::Deathrattle,
             Self::DoubleBarrel,
             Self::Faster,
+            Self::FickleFate,
             Self::FlamingBullets,
             Self::HotterFire,
             Self::Magnet,
@@ -57,6 +78,19 @@ impl Ability {
             Self::MaxHp,
             Self::Potion,
             Self::Piercing,
+            Self::Inferno,
+            Self::Retaliation,
+            Self::PhantomStep,
+            Self::Nimble,
+            Self::Shockwave,
+            Self::Hourglass,
+            Self::Grenade,
+            Self::Scholar,
+            Self::SeekingBullets,
+            Self::RubberBullets,
+            Self::Ricochet,
+            Self::Boomerang,
+            Self::SplittingBullets,
         ]
     }
 
@@ -76,6 +110,8 @@ impl Ability {
             Ability::MegaShotgun => textures.mega_shotgun.clone(),
             Ability::BulletsGalore => textures.bullets_galore.clone(),
             Ability::Faster => textures.faster.clone(),
+            // Shares the reload icon until it gets its own
+            Ability::FickleFate => textures.reload.clone(),
             Ability::HotterFire => textures.hotter_fire.clone(),
             Ability::Magnet => textures.magnet.clone(),
             Ability::MediumBullets => textures.medium_bullets.clone(),
@@ -86,6 +122,22 @@ impl Ability {
             Ability::MaxHp => textures.max_hp.clone(),
             Ability::Potion => textures.potion.clone(),
             Ability::Piercing => textures.piercing.clone(),
+            // Evolved abilities borrow the icon of what they evolved from
+            Ability::Inferno => textures.deathrattle.clone(),
+            Ability::Retaliation => textures.thorns.clone(),
+            // Skills borrow the icon closest to what they do
+            Ability::PhantomStep => textures.faster.clone(),
+            Ability::Nimble => textures.faster.clone(),
+            Ability::Shockwave => textures.thorns.clone(),
+            Ability::Hourglass => textures.reload.clone(),
+            Ability::Grenade => textures.deathrattle.clone(),
+            Ability::Scholar => textures.magnet.clone(),
+            // So do the projectile behaviors
+            Ability::SeekingBullets => textures.sniper.clone(),
+            Ability::RubberBullets => textures.bullets_galore.clone(),
+            Ability::Ricochet => textures.piercing.clone(),
+            Ability::Boomerang => textures.reload.clone(),
+            Ability::SplittingBullets => textures.shotgun.clone(),
         }
     }
 
@@ -99,6 +151,7 @@ impl Ability {
             Ability::Deathrattle => "Deathrattle",
             Ability::DoubleBarrel => "Double Barrel",
             Ability::Faster => "Faster",
+            Ability::FickleFate => "Fickle Fate",
             Ability::FlamingBullets => "Flaming Bullets",
             Ability::HotterFire => "Hotter Fire",
             Ability::Magnet => "Magnet",
@@ -115,6 +168,19 @@ impl Ability {
             Ability::TripleBarrel => "Triple Barrel",
             Ability::Potion => "Potion",
             Ability::Piercing => "Piercing",
+            Ability::Inferno => "Inferno",
+            Ability::Retaliation => "Retaliation",
+            Ability::PhantomStep => "Phantom Step",
+            Ability::Nimble => "Nimble",
+            Ability::Shockwave => "Shockwave",
+            Ability::Hourglass => "Hourglass",
+            Ability::Grenade => "Grenade",
+            Ability::Scholar => "Scholar",
+            Ability::SeekingBullets => "Seeking Bullets",
+            Ability::RubberBullets => "Rubber Bullets",
+            Ability::Ricochet => "Ricochet",
+            Ability::Boomerang => "Boomerang",
+            Ability::SplittingBullets => "Splitting Bullets",
         }
         .to_string()
     }
@@ -129,6 +195,7 @@ impl Ability {
             Ability::Deathrattle => "20% chance of explosion on kill\nExplosion deals x3 damage",
             Ability::DoubleBarrel => "2 Bullets\n-30% Shoot Speed",
             Ability::Faster => "+50 Move Speed",
+            Ability::FickleFate => "+2 Rerolls\n+1 Banish",
             Ability::FlamingBullets => "2 damage every 2 seconds",
             Ability::HotterFire => "+2 fire damage",
             Ability::Magnet => "Twice as attractive",
@@ -145,6 +212,19 @@ impl Ability {
             Ability::TripleBarrel => "3 Bullets\n-10% Shoot Speed",
             Ability::Potion => "Heal 2 hearts",
             Ability::Piercing => "Bullets pierce 3 enemies",
+            Ability::Inferno => "Burning enemies always explode",
+            Ability::Retaliation => "Thorns erupt whenever you are hurt",
+            Ability::PhantomStep => "+1 Dash charge",
+            Ability::Nimble => "-30% Dash cooldown",
+            Ability::Shockwave => "[Shift] Push enemies away\n8s cooldown",
+            Ability::Hourglass => "[Shift] Slow time for 3s\n20s cooldown",
+            Ability::Grenade => "[Right Click] Throw a grenade\n6s cooldown",
+            Ability::Scholar => "+25% Experience",
+            Ability::SeekingBullets => "Bullets curve toward enemies",
+            Ability::RubberBullets => "Bullets bounce twice\noff walls and obstacles",
+            Ability::Ricochet => "Bullets jump to 2 more enemies",
+            Ability::Boomerang => "Bullets pierce all enemies\nand fly back to you",
+            Ability::SplittingBullets => "Bullets split in 2 on hit\nPieces deal half damage",
         }.to_string()
     }
 
@@ -182,16 +262,44 @@ impl Ability {
             }
             Ability::BulletsGalore => true,
             Ability::Faster => true,
+            Ability::FickleFate => !player_abilities.contains(&self),
             Ability::HotterFire => player_abilities.contains(&Ability::FlamingBullets),
             Ability::Magnet => !player_abilities.contains(&self),
             Ability::MediumBullets => !player_abilities.contains(&self),
             Ability::Reload => true,
+            Ability::Scholar => true,
             Ability::ShootingSpeed => true,
             Ability::Sixfold => !player_abilities.contains(&self),
             Ability::Thorns => !player_abilities.contains(&self),
             Ability::MaxHp => true,
             Ability::Potion => true,
             Ability::Piercing => !player_abilities.contains(&self),
+            Ability::SeekingBullets
+            | Ability::RubberBullets
+            | Ability::Ricochet
+            | Ability::SplittingBullets => !player_abilities.contains(&self),
+            Ability::Boomerang => !player_abilities.contains(&self),
+            // Dashing is always there, these only stack a couple of times
+            Ability::PhantomStep | Ability::Nimble => {
+                player_abilities
+                    .iter()
+                    .filter(|ability| ability == &self)
+                    .count()
+                    < 2
+            }
+            Ability::Inferno | Ability::Retaliation => {
+                !player_abilities.contains(&self)
+                    && recipe_for(*self).is_some_and(|recipe| recipe.is_fulfilled(player_abilities))
+            }
+            // One skill per slot
+            Ability::Shockwave | Ability::Hourglass | Ability::Grenade => {
+                self.skill().is_some_and(|skill| {
+                    !player_abilities
+                        .iter()
+                        .filter_map(|ability| ability.skill())
+                        .any(|owned| owned.slot() == skill.slot())
+                })
+            }
         }
     }
 
@@ -224,6 +332,20 @@ impl Ability {
         }
     }
 
+    pub fn xp_mult(&self) -> f32 {
+        match self {
+            Ability::Scholar => 1.25,
+            _ => 1.,
+        }
+    }
+
+    pub fn dash_cooldown_mult(&self) -> f32 {
+        match self {
+            Ability::Nimble => 0.7,
+            _ => 1.,
+        }
+    }
+
     pub fn shoot_speed_mult(&self) -> f32 {
         match self {
             Ability::DoubleBarrel => 0.7,
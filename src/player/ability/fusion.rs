@@ -0,0 +1,56 @@
+use super::Ability;
+
+/*
+Owning every ingredient of a recipe unlocks its evolved ability
+*/
+pub struct Recipe {
+    pub ingredients: &'static [Ability],
+    pub result: Ability,
+}
+
+pub const RECIPES: &[Recipe] = &[
+    Recipe {
+        ingredients: &[Ability::FlamingBullets, Ability::Deathrattle],
+        result: Ability::Inferno,
+    },
+    Recipe {
+        ingredients: &[Ability::Thorns, Ability::MaxHp],
+        result: Ability::Retaliation,
+    },
+];
+
+impl Recipe {
+    pub fn is_fulfilled(&self, player_abilities: &[Ability]) -> bool {
+        self.ingredients
+            .iter()
+            .all(|ingredient| player_abilities.contains(ingredient))
+    }
+}
+
+pub fn recipe_for(ability: Ability) -> Option<&'static Recipe> {
+    RECIPES.iter().find(|recipe| recipe.result == ability)
+}
+
+// Hints for every unfinished recipe this ability is part of
+pub fn fusion_hints(ability: Ability, player_abilities: &[Ability]) -> Vec<String> {
+    RECIPES
+        .iter()
+        .filter(|recipe| {
+            recipe.ingredients.contains(&ability) && !player_abilities.contains(&recipe.result)
+        })
+        .map(|recipe| {
+            let others: Vec<String> = recipe
+                .ingredients
+                .iter()
+                .filter(|ingredient| **ingredient != ability)
+                .map(|ingredient| ingredient.get_name())
+                .collect();
+
+            format!(
+                "Fuses with {} into {}",
+                others.join(" + "),
+                recipe.result.get_name()
+            )
+        })
+        .collect()
+}
@@ -0,0 +1,135 @@
+use bevy::prelude::*;
+
+use super::Ability;
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum Rarity {
+    Common,
+    Rare,
+    Epic,
+    Legendary,
+}
+
+impl Rarity {
+    pub fn name(&self) -> String {
+        match self {
+            Rarity::Common => "Common",
+            Rarity::Rare => "Rare",
+            Rarity::Epic => "Epic",
+            Rarity::Legendary => "Legendary",
+        }
+        .to_string()
+    }
+
+    fn base_weight(&self) -> f32 {
+        match self {
+            Rarity::Common => 60.,
+            Rarity::Rare => 25.,
+            Rarity::Epic => 10.,
+            Rarity::Legendary => 5.,
+        }
+    }
+
+    // How much each point of luck boosts the weight, commons never get luckier
+    fn luck_bonus(&self) -> f32 {
+        match self {
+            Rarity::Common => 0.,
+            Rarity::Rare => 0.2,
+            Rarity::Epic => 0.35,
+            Rarity::Legendary => 0.5,
+        }
+    }
+
+    pub fn weight(&self, luck: u32) -> f32 {
+        self.base_weight() * (1. + self.luck_bonus() * luck as f32)
+    }
+
+    // Tint for the ability frame when offered
+    pub fn color(&self) -> Color {
+        match self {
+            Rarity::Common => Color::WHITE,
+            Rarity::Rare => Color::rgb(0.5, 0.7, 1.0),
+            Rarity::Epic => Color::rgb(0.8, 0.5, 1.0),
+            Rarity::Legendary => Color::rgb(1.0, 0.75, 0.3),
+        }
+    }
+}
+
+impl Ability {
+    pub fn rarity(&self) -> Rarity {
+        match self {
+            Ability::BulletsGalore
+            | Ability::Faster
+            | Ability::Magnet
+            | Ability::MaxHp
+            | Ability::MediumBullets
+            | Ability::Nimble
+            | Ability::Potion
+            | Ability::Reload
+            | Ability::RubberBullets
+            | Ability::Scholar
+            | Ability::Shells
+            | Ability::ShootingSpeed => Rarity::Common,
+            Ability::BigBullets
+            | Ability::DoubleBarrel
+            | Ability::FickleFate
+            | Ability::FlamingBullets
+            | Ability::Grenade
+            | Ability::HotterFire
+            | Ability::Hourglass
+            | Ability::PhantomStep
+            | Ability::Piercing
+            | Ability::SeekingBullets
+            | Ability::Shockwave
+            | Ability::Sniper
+            | Ability::SplittingBullets => Rarity::Rare,
+            Ability::BiggestBullets
+            | Ability::BloodthirstyVial
+            | Ability::Boomerang
+            | Ability::Crossbow
+            | Ability::Deathrattle
+            | Ability::Ricochet
+            | Ability::Shotgun
+            | Ability::Thorns
+            | Ability::TripleBarrel => Rarity::Epic,
+            Ability::Inferno
+            | Ability::MegaShotgun
+            | Ability::Retaliation
+            | Ability::Sixfold => Rarity::Legendary,
+        }
+    }
+}
+
+/*
+Chains of abilities that build on each other, in the order they unlock
+*/
+pub const BUILD_PATHS: &[&[Ability]] = &[
+    &[
+        Ability::DoubleBarrel,
+        Ability::TripleBarrel,
+        Ability::Shotgun,
+        Ability::MegaShotgun,
+    ],
+    &[
+        Ability::MediumBullets,
+        Ability::BigBullets,
+        Ability::BiggestBullets,
+    ],
+    &[Ability::Piercing, Ability::Crossbow],
+    &[Ability::RubberBullets, Ability::Ricochet],
+    &[Ability::FlamingBullets, Ability::HotterFire],
+];
+
+// The next step of every build path the player has already started
+pub fn build_path_steps(player_abilities: &[Ability]) -> Vec<Ability> {
+    BUILD_PATHS
+        .iter()
+        .filter_map(|path| {
+            path.windows(2)
+                .find(|step| {
+                    player_abilities.contains(&step[0]) && !player_abilities.contains(&step[1])
+                })
+                .map(|step| step[1])
+        })
+        .collect()
+}
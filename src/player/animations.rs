@@ -9,6 +9,7 @@ use crate::animation::{
 pub enum PlayerAnimationState {
     Idle,
     Running,
+    Dashing,
 }
 
 impl Animation<PlayerAnimationState> for PlayerAnimationState {
@@ -24,6 +25,13 @@ impl Animation<PlayerAnimationState> for PlayerAnimationState {
                 4,
                 Duration::from_secs_f32(1. / 10.),
             )
+            // Dashing plays the running frames sped up
+            .add_reused_frames(
+                PlayerAnimationState::Dashing,
+                2,
+                4,
+                Duration::from_secs_f32(1. / 30.),
+            )
             .build()
     }
 }
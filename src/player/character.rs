@@ -0,0 +1,196 @@
+use bevy::prelude::*;
+
+use crate::{
+    combat::health::{DamageSource, HealthType},
+    loading::TextureAssets,
+    save::SaveData,
+    stats::RunStats,
+};
+
+use super::ability::Ability;
+
+/*
+Playable characters, each with their own starting kit and rules
+*/
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum Character {
+    #[default]
+    Hatman,
+    Pyromancer,
+    Sharpshooter,
+    Warden,
+}
+
+#[derive(Resource, Default)]
+pub struct SelectedCharacter(pub Character);
+
+impl Character {
+    pub fn all() -> Vec<Character> {
+        vec![
+            Character::Hatman,
+            Character::Pyromancer,
+            Character::Sharpshooter,
+            Character::Warden,
+        ]
+    }
+
+    pub fn name(&self) -> String {
+        match self {
+            Character::Hatman => "Hatman",
+            Character::Pyromancer => "Pyromancer",
+            Character::Sharpshooter => "Sharpshooter",
+            Character::Warden => "Warden",
+        }
+        .to_string()
+    }
+
+    pub fn passive_description(&self) -> String {
+        match self {
+            Character::Hatman => "Reloads faster",
+            Character::Pyromancer => "Fire burns twice as hot",
+            Character::Sharpshooter => "Bullets deal 50% more damage, no spread weapons",
+            Character::Warden => "Thorns return twice as often, but moves slower",
+        }
+        .to_string()
+    }
+
+    pub fn get_texture(&self, textures: &Res<TextureAssets>) -> Handle<Image> {
+        match self {
+            Character::Hatman => textures.hatman.clone(),
+            Character::Pyromancer => textures.pyromancer.clone(),
+            Character::Sharpshooter => textures.sharpshooter.clone(),
+            Character::Warden => textures.warden.clone(),
+        }
+    }
+
+    pub fn tint(&self) -> Color {
+        match self {
+            Character::Hatman => Color::WHITE,
+            Character::Pyromancer => Color::rgb(1.0, 0.7, 0.5),
+            Character::Sharpshooter => Color::rgb(0.7, 0.9, 1.0),
+            Character::Warden => Color::rgb(0.7, 1.0, 0.7),
+        }
+    }
+
+    pub fn max_health(&self) -> HealthType {
+        match self {
+            Character::Pyromancer => 2,
+            Character::Warden => 5,
+            _ => 3,
+        }
+    }
+
+    pub fn max_bullets(&self) -> u32 {
+        match self {
+            Character::Sharpshooter => 4,
+            _ => 6,
+        }
+    }
+
+    pub fn speed(&self) -> f32 {
+        match self {
+            Character::Warden => 120.,
+            _ => 150.,
+        }
+    }
+
+    pub fn starting_abilities(&self) -> Vec<Ability> {
+        match self {
+            Character::Hatman => vec![],
+            Character::Pyromancer => vec![Ability::FlamingBullets],
+            Character::Sharpshooter => vec![Ability::Sniper],
+            Character::Warden => vec![Ability::Thorns],
+        }
+    }
+
+    // Whether this ability can show up when leveling up
+    pub fn allows(&self, ability: Ability) -> bool {
+        match self {
+            Character::Sharpshooter => !matches!(
+                ability,
+                Ability::Shotgun
+                    | Ability::MegaShotgun
+                    | Ability::DoubleBarrel
+                    | Ability::TripleBarrel
+                    | Ability::Sixfold
+            ),
+            Character::Warden => !matches!(ability, Ability::Sniper | Ability::Crossbow),
+            _ => true,
+        }
+    }
+
+    pub fn damage_mult(&self) -> f32 {
+        match self {
+            Character::Sharpshooter => 1.5,
+            _ => 1.,
+        }
+    }
+
+    pub fn reload_mult(&self) -> f32 {
+        match self {
+            Character::Hatman => 1.25,
+            _ => 1.,
+        }
+    }
+
+    pub fn fire_mult(&self) -> HealthType {
+        match self {
+            Character::Pyromancer => 2,
+            _ => 1,
+        }
+    }
+
+    pub fn thorns_cooldown_mult(&self) -> f32 {
+        match self {
+            Character::Warden => 0.5,
+            _ => 1.,
+        }
+    }
+
+    fn unlock_flag(&self) -> Option<String> {
+        match self {
+            Character::Hatman => None,
+            _ => Some(format!("character_{}", self.name().to_lowercase())),
+        }
+    }
+
+    pub fn unlock_hint(&self) -> String {
+        match self {
+            Character::Hatman => "",
+            Character::Pyromancer => "Deal 500 fire damage in a run",
+            Character::Sharpshooter => "Fire 100 shots with 60% accuracy in a run",
+            Character::Warden => "Survive for 5 minutes",
+        }
+        .to_string()
+    }
+
+    pub fn is_unlocked(&self, save: &SaveData) -> bool {
+        match self.unlock_flag() {
+            Some(flag) => save.is_unlocked(&flag),
+            None => true,
+        }
+    }
+
+    // Whether the run that just ended earns this character
+    fn is_earned(&self, stats: &RunStats, survived_secs: f32) -> bool {
+        match self {
+            Character::Hatman => true,
+            Character::Pyromancer => stats.damage_from(DamageSource::Fire) >= 500,
+            Character::Sharpshooter => stats.shots_fired >= 100 && stats.accuracy() >= 0.6,
+            Character::Warden => survived_secs >= 300.,
+        }
+    }
+}
+
+pub fn unlock_characters(save: &mut SaveData, stats: &RunStats, survived_secs: f32) {
+    for character in Character::all() {
+        if character.is_unlocked(save) || !character.is_earned(stats, survived_secs) {
+            continue;
+        }
+
+        if let Some(flag) = character.unlock_flag() {
+            info!("Unlocked {}", character.name());
+            save.unlock(&flag);
+        }
+    }
+}
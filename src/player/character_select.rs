@@ -0,0 +1,171 @@
+use bevy::prelude::*;
+
+use crate::{loading::FontAssets, palette::Palette, save::SaveData, GameState};
+
+use super::character::{Character, SelectedCharacter};
+
+pub struct CharacterSelectPlugin;
+
+impl Plugin for CharacterSelectPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<SelectedCharacter>()
+            .add_systems(OnEnter(GameState::CharacterSelect), setup_character_select)
+            .add_systems(
+                Update,
+                click_character_button.run_if(in_state(GameState::CharacterSelect)),
+            )
+            .add_systems(OnExit(GameState::CharacterSelect), cleanup_character_select);
+    }
+}
+
+#[derive(Component)]
+enum CharacterButton {
+    Pick(Character),
+    Back,
+}
+
+fn setup_character_select(
+    mut commands: Commands,
+    font_assets: Res<FontAssets>,
+    palette: Res<Palette>,
+    save: Res<SaveData>,
+) {
+    commands
+        .spawn(NodeBundle {
+            style: Style {
+                display: Display::Grid,
+                width: Val::Percent(100.),
+                align_items: AlignItems::Center,
+                justify_content: JustifyContent::Center,
+                grid_auto_flow: GridAutoFlow::Row,
+                ..Default::default()
+            },
+            ..Default::default()
+        })
+        .with_children(|parent| {
+            parent.spawn(
+                TextBundle::from_section(
+                    "Choose thy hunter",
+                    TextStyle {
+                        font: font_assets.gothic_pxl.clone(),
+                        font_size: 80.0,
+                        color: palette.orange,
+                    },
+                )
+                .with_style(Style {
+                    margin: UiRect::all(Val::Auto),
+                    ..default()
+                }),
+            );
+
+            for character in Character::all() {
+                let is_unlocked = character.is_unlocked(&save);
+
+                let mut button = parent.spawn(ButtonBundle {
+                    style: Style {
+                        width: Val::Px(500.0),
+                        height: Val::Px(60.0),
+                        margin: UiRect::all(Val::Auto),
+                        flex_direction: FlexDirection::Column,
+                        justify_content: JustifyContent::Center,
+                        align_items: AlignItems::Center,
+                        ..Default::default()
+                    },
+                    background_color: palette.dark.into(),
+                    ..Default::default()
+                });
+
+                // Locked characters can't be picked
+                if is_unlocked {
+                    button.insert(CharacterButton::Pick(character));
+                }
+
+                button.with_children(|parent| {
+                    parent.spawn(TextBundle::from_section(
+                        character.name(),
+                        TextStyle {
+                            font: font_assets.gothic.clone(),
+                            font_size: 30.0,
+                            color: match is_unlocked {
+                                true => character.tint(),
+                                false => palette.red,
+                            },
+                        },
+                    ));
+
+                    parent.spawn(TextBundle::from_section(
+                        match is_unlocked {
+                            true => character.passive_description(),
+                            false => format!("Locked: {}", character.unlock_hint()),
+                        },
+                        TextStyle {
+                            font: font_assets.gothic.clone(),
+                            font_size: 20.0,
+                            color: palette.white,
+                        },
+                    ));
+                });
+            }
+
+            parent
+                .spawn(ButtonBundle {
+                    style: Style {
+                        width: Val::Px(250.0),
+                        height: Val::Px(50.0),
+                        margin: UiRect::all(Val::Auto),
+                        justify_content: JustifyContent::Center,
+                        align_items: AlignItems::Center,
+                        ..Default::default()
+                    },
+                    background_color: palette.dark.into(),
+                    ..Default::default()
+                })
+                .insert(CharacterButton::Back)
+                .with_children(|parent| {
+                    parent.spawn(TextBundle::from_section(
+                        "Back",
+                        TextStyle {
+                            font: font_assets.gothic.clone(),
+                            font_size: 40.0,
+                            color: palette.white,
+                        },
+                    ));
+                });
+        });
+}
+
+fn click_character_button(
+    palette: Res<Palette>,
+    mut state: ResMut<NextState<GameState>>,
+    mut selected: ResMut<SelectedCharacter>,
+    mut interaction_query: Query<
+        (&Interaction, &CharacterButton, &mut BackgroundColor),
+        Changed<Interaction>,
+    >,
+) {
+    for (interaction, button, mut color) in &mut interaction_query {
+        match *interaction {
+            Interaction::Pressed => match button {
+                CharacterButton::Pick(character) => {
+                    selected.0 = *character;
+                    state.set(GameState::Playing);
+                }
+                CharacterButton::Back => {
+                    state.set(GameState::Menu);
+                }
+            },
+            Interaction::Hovered => {
+                *color = palette.orange.into();
+            }
+            Interaction::None => {
+                *color = palette.red.into();
+            }
+        }
+    }
+}
+
+fn cleanup_character_select(mut commands: Commands, ui: Query<Entity, With<Node>>) {
+    for entity in ui.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
@@ -0,0 +1,232 @@
+use bevy::prelude::*;
+
+use crate::{
+    actions::{
+        input_map::{ActionInput, InputAction},
+        Actions,
+    },
+    animation::{controller::AnimationController, AnimationStateChangeEvent},
+    combat::{
+        health::Health,
+        lifetime::Lifetime,
+        pool::{EntityPool, PoolKind},
+    },
+    constants::SortingLayers,
+    util::clock::{clock_is_running, GameClock},
+    GameState, InGame,
+};
+
+use super::{ability::Ability, animations::PlayerAnimationState, InvincibilityTimer, Player};
+
+pub struct DashPlugin;
+
+impl Plugin for DashPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(DashCharges::new(BASE_DASH_CHARGES))
+            .add_systems(OnEnter(GameState::Playing), reset_dash)
+            .add_systems(OnExit(InGame::GameOver), reset_dash)
+            .add_systems(
+                Update,
+                (
+                    start_dash.run_if(clock_is_running),
+                    dash_update,
+                    dash_recharge,
+                    afterimage_update,
+                )
+                    .run_if(in_state(GameState::Playing)),
+            );
+    }
+}
+
+pub const DASH_SPEED: f32 = 600.;
+pub const DASH_DURATION: f32 = 0.15;
+pub const DASH_COOLDOWN: f32 = 2.;
+pub const BASE_DASH_CHARGES: u32 = 1;
+pub const AFTERIMAGE_INTERVAL: f32 = 0.03;
+pub const AFTERIMAGE_LIFETIME: f32 = 0.25;
+
+impl Player {
+    pub fn max_dash_charges(&self) -> u32 {
+        BASE_DASH_CHARGES
+            + self
+                .abilities
+                .iter()
+                .filter(|ability| ability == &&Ability::PhantomStep)
+                .count() as u32
+    }
+
+    pub fn dash_cooldown(&self) -> f32 {
+        self.abilities
+            .iter()
+            .fold(DASH_COOLDOWN, |cooldown, ability| {
+                cooldown * ability.dash_cooldown_mult()
+            })
+    }
+}
+
+/*
+Dashes are stored up to the max and come back one at a time
+*/
+#[derive(Resource)]
+pub struct DashCharges {
+    pub charges: u32,
+    recharge: Timer,
+}
+
+impl DashCharges {
+    pub fn new(charges: u32) -> DashCharges {
+        DashCharges {
+            charges,
+            recharge: Timer::from_seconds(DASH_COOLDOWN, TimerMode::Once),
+        }
+    }
+}
+
+#[derive(Component)]
+pub struct Dashing {
+    direction: Vec2,
+    timer: Timer,
+    afterimage_timer: Timer,
+}
+
+#[derive(Component)]
+pub struct Afterimage;
+
+fn reset_dash(mut charges: ResMut<DashCharges>) {
+    *charges = DashCharges::new(BASE_DASH_CHARGES);
+}
+
+fn start_dash(
+    q_player: Query<
+        (Entity, &AnimationController<PlayerAnimationState>),
+        (With<Player>, Without<Dashing>),
+    >,
+    input: ActionInput,
+    actions: Res<Actions>,
+    mut charges: ResMut<DashCharges>,
+    mut animation_change: EventWriter<AnimationStateChangeEvent<PlayerAnimationState>>,
+    mut commands: Commands,
+) {
+    let Ok((entity, animation_controller)) = q_player.get_single() else {
+        return;
+    };
+
+    if !input.just_pressed(InputAction::Dash) || charges.charges == 0 {
+        return;
+    }
+
+    // Standing still dashes the way the player is facing
+    let direction =
+        actions
+            .player_movement
+            .unwrap_or(match animation_controller.is_facing_right() {
+                true => Vec2::X,
+                false => Vec2::NEG_X,
+            });
+
+    charges.charges -= 1;
+
+    commands.entity(entity).insert(Dashing {
+        direction,
+        timer: Timer::from_seconds(DASH_DURATION, TimerMode::Once),
+        afterimage_timer: Timer::from_seconds(AFTERIMAGE_INTERVAL, TimerMode::Repeating),
+    });
+
+    animation_change.send(AnimationStateChangeEvent {
+        id: entity,
+        state_id: PlayerAnimationState::Dashing,
+    });
+}
+
+fn dash_update(
+    mut q_player: Query<
+        (
+            Entity,
+            &mut Transform,
+            &mut Health,
+            &mut Dashing,
+            &TextureAtlasSprite,
+            &Handle<TextureAtlas>,
+        ),
+        With<Player>,
+    >,
+    mut animation_change: EventWriter<AnimationStateChangeEvent<PlayerAnimationState>>,
+    invincibility: Res<InvincibilityTimer>,
+    clock: Res<GameClock>,
+    mut pool: ResMut<EntityPool>,
+    mut commands: Commands,
+) {
+    for (entity, mut transform, mut health, mut dashing, sprite, atlas) in q_player.iter_mut() {
+        dashing.timer.tick(clock.delta());
+        dashing.afterimage_timer.tick(clock.delta());
+
+        // Untouchable for the whole dash, without cutting short the immunity from a hit
+        health.is_invincible = !dashing.timer.finished() || !invincibility.0.finished();
+
+        let movement = dashing.direction * DASH_SPEED * clock.delta_seconds();
+        transform.translation += movement.extend(0.);
+
+        if dashing.afterimage_timer.just_finished() {
+            pool.spawn(PoolKind::Afterimage, &mut commands)
+                .insert(SpriteSheetBundle {
+                    texture_atlas: atlas.clone(),
+                    sprite: TextureAtlasSprite {
+                        index: sprite.index,
+                        flip_x: sprite.flip_x,
+                        color: sprite.color.with_a(0.5),
+                        ..Default::default()
+                    },
+                    transform: Transform {
+                        translation: transform
+                            .translation
+                            .truncate()
+                            .extend(SortingLayers::BehindAction.into()),
+                        ..*transform
+                    },
+                    ..Default::default()
+                })
+                .insert(Afterimage)
+                .insert(Lifetime::from_seconds(AFTERIMAGE_LIFETIME));
+        }
+
+        if dashing.timer.finished() {
+            commands.entity(entity).remove::<Dashing>();
+            animation_change.send(AnimationStateChangeEvent {
+                id: entity,
+                state_id: PlayerAnimationState::Idle,
+            });
+        }
+    }
+}
+
+fn dash_recharge(
+    q_player: Query<&Player>,
+    clock: Res<GameClock>,
+    mut charges: ResMut<DashCharges>,
+) {
+    let player = q_player.single();
+
+    if charges.charges >= player.max_dash_charges() {
+        return;
+    }
+
+    let cooldown = player.dash_cooldown();
+    charges
+        .recharge
+        .set_duration(std::time::Duration::from_secs_f32(cooldown));
+    charges.recharge.tick(clock.delta());
+
+    if charges.recharge.finished() {
+        charges.charges += 1;
+        charges.recharge.reset();
+    }
+}
+
+// Fades out over its lifetime, which also takes care of removing it
+fn afterimage_update(
+    mut q_afterimages: Query<(&Lifetime, &mut TextureAtlasSprite), With<Afterimage>>,
+) {
+    for (lifetime, mut sprite) in q_afterimages.iter_mut() {
+        sprite.color.set_a(0.5 * lifetime.percent_left());
+    }
+}
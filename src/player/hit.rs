@@ -4,6 +4,7 @@ use crate::{
     combat::health::{Health, TookDamageEvent},
     constants::{SortingLayers, SCALING_VEC3},
     loading::TextureAssets,
+    util::clock::GameClock,
 };
 
 use super::Player;
@@ -15,7 +16,7 @@ pub fn update_hit_sprite(
     mut q_hit: Query<(Entity, &mut HitSprite, &mut Visibility, &mut Transform), Without<Player>>,
     q_player: Query<(Entity, &Health, &Transform), With<Player>>,
     mut ev_dmgs: EventReader<TookDamageEvent>,
-    time: Res<Time>,
+    clock: Res<GameClock>,
 ) {
     let (player, p_health, p_transform) = q_player.single();
     let (_entity, mut hit, mut visibility, mut transform) = q_hit.single_mut();
@@ -36,7 +37,7 @@ pub fn update_hit_sprite(
         }
     }
 
-    hit.0.tick(time.delta());
+    hit.0.tick(clock.delta());
 
     if hit.0.just_finished() {
         *visibility = match *visibility {
@@ -1,22 +1,27 @@
 use std::{f32::consts::PI, time::Duration};
 
-use bevy::{prelude::*, window::PrimaryWindow};
+use bevy::prelude::*;
 
 use bevy_kira_audio::AudioControl;
 
 use crate::{
+    actions::Actions,
     audio::FXChannel,
     collision::collider::Collider,
     combat::{
-        health::HealthType,
+        health::{DamageSource, HealthType},
         knockback::Knockback,
+        lifetime::{MaxRange, PROJECTILE_RANGE},
+        pool::{EntityPool, PoolKind},
         projectile::{DamageTarget, PiercingMode, Projectile},
+        projectile_behavior::{Boomerang, Bounce, Homing, Ricochet, Split},
         teams::Team,
     },
     constants::{SortingLayers, SCALING_VEC3},
     loading::{AudioAssets, TextureAssets},
-    movement::{pause::ActionPauseState, velocity::Velocity},
-    util::radians::Radian,
+    movement::velocity::Velocity,
+    particles::{emitter::ParticleEmitter, BULLET_TRAIL},
+    util::{clock::GameClock, radians::Radian},
 };
 
 use super::{ability::Ability, reload_ui::ReloadTimer, Player};
@@ -24,27 +29,29 @@ use super::{ability::Ability, reload_ui::ReloadTimer, Player};
 #[derive(Resource)]
 pub struct ShootingCooldown(pub Timer);
 
+// Once per trigger pull, however many bullets come out
+#[derive(Event)]
+pub struct ShotFiredEvent {
+    pub origin: Vec3,
+    pub direction: Vec2,
+}
+
 pub fn shoot(
-    buttons: Res<Input<MouseButton>>,
+    actions: Res<Actions>,
     mut q_player: Query<(&mut Player, &Transform)>,
-    q_windows: Query<&Window, With<PrimaryWindow>>,
     mut shooting_cooldown: ResMut<ShootingCooldown>,
     mut reload_timer: ResMut<ReloadTimer>,
     textures: Res<TextureAssets>,
     audio_assets: Res<AudioAssets>,
     fx_channel: Res<FXChannel>,
-    time: Res<Time>,
-    pause: Res<ActionPauseState>,
+    clock: Res<GameClock>,
+    mut pool: ResMut<EntityPool>,
+    mut shot_ev: EventWriter<ShotFiredEvent>,
     mut commands: Commands,
 ) {
-    if pause.is_paused {
-        return;
-    }
-
-    shooting_cooldown.0.tick(time.delta());
-    reload_timer.0.tick(time.delta());
+    shooting_cooldown.0.tick(clock.delta());
+    reload_timer.0.tick(clock.delta());
 
-    let window = q_windows.single();
     let (mut player, transform) = q_player.single_mut();
 
     if reload_timer.0.just_finished() {
@@ -52,9 +59,8 @@ pub fn shoot(
         player.is_reloading = false;
     }
 
-    if shooting_cooldown.0.finished() && !player.is_reloading && buttons.pressed(MouseButton::Left)
-    {
-        if let Some(cursor_position) = window.cursor_position() {
+    if shooting_cooldown.0.finished() && !player.is_reloading && actions.shooting {
+        if let Some(direction) = actions.aim_direction {
             // Reset cooldown
             shooting_cooldown
                 .0
@@ -64,12 +70,6 @@ pub fn shoot(
                 .set_duration(Duration::from_secs_f32(player.shoot_time()));
             shooting_cooldown.0.reset();
 
-            let target = Vec2::new(
-                cursor_position.x - window.width() / 2.,
-                window.height() / 2. - cursor_position.y,
-            );
-
-            let direction = target - transform.translation.truncate();
             // obtain angle to target with respect to x-axis.
             let angle_to_target = Radian::from(direction.y.atan2(direction.x) - PI / 2.);
             let direction_vec = angle_to_target.unit_vector();
@@ -81,21 +81,14 @@ pub fn shoot(
                 } * 10.
                 + Vec3::Z * 5.;
 
+            shot_ev.send(ShotFiredEvent {
+                origin: bullet_translation,
+                direction: direction_vec,
+            });
+
             let dmg = player.damage();
             let knockback = player.knockback();
-            let mut velocity: f32 = 500.;
-
-            if player.abilities.contains(&Ability::Sniper) {
-                velocity *= 2.;
-            }
-
-            if player.abilities.contains(&Ability::BigBullets) {
-                velocity *= 0.8;
-            }
-
-            if player.abilities.contains(&Ability::BiggestBullets) {
-                velocity *= 0.8;
-            }
+            let velocity = player.bullet_speed();
 
             player.curr_bullets -= 1;
             if player.curr_bullets == 0 {
@@ -112,6 +105,7 @@ pub fn shoot(
                         spawn_bullet(
                             &player,
                             &mut commands,
+                            &mut pool,
                             bullet_translation,
                             &textures,
                             (Radian::FULL / 6. * i as f32).normalize().unit_vector(),
@@ -137,6 +131,7 @@ pub fn shoot(
                     spawn_bullet(
                         &player,
                         &mut commands,
+                        &mut pool,
                         bullet_translation,
                         &textures,
                         (angle_to_target + offset_angle * ((bullets - 1) as f32 / -2. + i as f32))
@@ -149,6 +144,7 @@ pub fn shoot(
                     spawn_bullet(
                         &player,
                         &mut commands,
+                        &mut pool,
                         bullet_translation,
                         &textures,
                         (angle_to_target + offset_angle * ((bullets - 1) as f32 / -2. + i as f32))
@@ -167,6 +163,7 @@ pub fn shoot(
                     spawn_bullet(
                         &player,
                         &mut commands,
+                        &mut pool,
                         bullet_translation,
                         &textures,
                         (angle_to_target + offset_angle * ((bullets - 1) as f32 / -2. + i as f32))
@@ -183,6 +180,7 @@ pub fn shoot(
                 spawn_bullet(
                     &player,
                     &mut commands,
+                    &mut pool,
                     bullet_translation,
                     &textures,
                     (angle_to_target + offset_angle).normalize().unit_vector(),
@@ -193,6 +191,7 @@ pub fn shoot(
                 spawn_bullet(
                     &player,
                     &mut commands,
+                    &mut pool,
                     bullet_translation,
                     &textures,
                     (angle_to_target).unit_vector(),
@@ -203,6 +202,7 @@ pub fn shoot(
                 spawn_bullet(
                     &player,
                     &mut commands,
+                    &mut pool,
                     bullet_translation,
                     &textures,
                     (angle_to_target - offset_angle).normalize().unit_vector(),
@@ -220,6 +220,7 @@ pub fn shoot(
                 spawn_bullet(
                     &player,
                     &mut commands,
+                    &mut pool,
                     bullet_translation + (perp_vec * 5.),
                     &textures,
                     direction_vec,
@@ -230,6 +231,7 @@ pub fn shoot(
                 spawn_bullet(
                     &player,
                     &mut commands,
+                    &mut pool,
                     bullet_translation - (perp_vec * 5.),
                     &textures,
                     direction_vec,
@@ -241,6 +243,7 @@ pub fn shoot(
                 spawn_bullet(
                     &player,
                     &mut commands,
+                    &mut pool,
                     bullet_translation,
                     &textures,
                     direction_vec,
@@ -258,6 +261,7 @@ pub fn shoot(
 fn spawn_bullet(
     player: &Player,
     commands: &mut Commands,
+    pool: &mut EntityPool,
     translation: Vec3,
     textures: &Res<TextureAssets>,
     direction_vec: Vec2,
@@ -265,23 +269,28 @@ fn spawn_bullet(
     damage: HealthType,
     knockback: f32,
 ) {
-    commands
-        .spawn(SpriteBundle {
-            texture: match player.abilities.contains(&Ability::BigBullets) {
-                true => textures.bullet_medium.clone(),
-                false => textures.bullet_small.clone(),
-            },
-            transform: Transform {
-                translation,
-                scale: SCALING_VEC3,
-                rotation: Quat::IDENTITY,
-            },
-            ..Default::default()
-        })
+    let mut bullet = pool.spawn(PoolKind::Bullet, commands);
+
+    bullet.insert(SpriteBundle {
+        texture: match player.abilities.contains(&Ability::BigBullets) {
+            true => textures.bullet_medium.clone(),
+            false => textures.bullet_small.clone(),
+        },
+        transform: Transform {
+            translation,
+            scale: SCALING_VEC3,
+            rotation: Quat::IDENTITY,
+        },
+        ..Default::default()
+    });
+
+    bullet
         .insert(Projectile {
             dmg: damage,
             damage_target: DamageTarget::Team(Team::Enemy),
-            piercing_mode: match player.abilities.contains(&Ability::Crossbow) {
+            piercing_mode: match player.abilities.contains(&Ability::Crossbow)
+                || player.abilities.contains(&Ability::Boomerang)
+            {
                 true => PiercingMode::All,
                 false => match player.abilities.contains(&Ability::Piercing) {
                     true => PiercingMode::Count(3),
@@ -290,10 +299,39 @@ fn spawn_bullet(
             },
             entities_hit: vec![],
             is_alive: true,
+            source: DamageSource::Bullet,
         })
         .insert(Velocity {
             vec: direction_vec * velocity,
         })
         .insert(Collider::new_circle(5.))
-        .insert(Knockback { force: knockback });
+        .insert(Knockback { force: knockback })
+        .insert(MaxRange::new(translation, PROJECTILE_RANGE))
+        .insert(ParticleEmitter::new(BULLET_TRAIL, None));
+
+    if player.abilities.contains(&Ability::SeekingBullets) {
+        bullet.insert(Homing {
+            turn_rate: 180.,
+            acquire_radius: 150.,
+        });
+    }
+
+    if player.abilities.contains(&Ability::RubberBullets) {
+        bullet.insert(Bounce { count: 2 });
+    }
+
+    if player.abilities.contains(&Ability::Ricochet) {
+        bullet.insert(Ricochet { count: 2 });
+    }
+
+    if player.abilities.contains(&Ability::Boomerang) {
+        bullet.insert(Boomerang::new(0.6));
+    }
+
+    if player.abilities.contains(&Ability::SplittingBullets) {
+        bullet.insert(Split {
+            on_hit: true,
+            count: 2,
+        });
+    }
 }
@@ -0,0 +1,391 @@
+use bevy::{prelude::*, utils::HashMap, window::PrimaryWindow};
+use bevy_kira_audio::AudioControl;
+
+use crate::{
+    actions::{
+        input_map::{ActionInput, InputAction},
+        Actions,
+    },
+    animation::AnimationStateStorage,
+    audio::FXChannel,
+    collision::collider::Collider,
+    combat::{
+        deathrattle::{explosion_atlas, spawn_explosion, ExplosionAnimation},
+        health::DamageSource,
+        lifetime::Lifetime,
+        pool::EntityPool,
+        projectile::{DamageTarget, PiercingMode, Projectile},
+        teams::Team,
+    },
+    constants::{SortingLayers, SCALING_VEC3},
+    enemies::enemy::Enemy,
+    loading::{AudioAssets, DebugTextureAssets, TextureAssets},
+    movement::velocity::Velocity,
+    util::{
+        clock::{clock_is_running, GameClock, TimeLayer},
+        despawn_on_exit::DespawnOnExit,
+    },
+    GameState, InGame,
+};
+
+use super::{ability::Ability, Player};
+
+pub struct SkillsPlugin;
+
+impl Plugin for SkillsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<SkillCooldowns>()
+            .add_systems(
+                OnEnter(GameState::Playing),
+                (reset_skills, spawn_skill_cooldown_ui),
+            )
+            .add_systems(OnExit(InGame::GameOver), reset_skills)
+            .add_systems(
+                Update,
+                (
+                    use_skills.run_if(clock_is_running),
+                    time_slow_update,
+                    grenade_update,
+                    update_skill_cooldown_ui,
+                )
+                    .run_if(in_state(GameState::Playing)),
+            );
+    }
+}
+
+/*
+Skills are granted by abilities and triggered by hand, one per slot
+*/
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum Skill {
+    Shockwave,
+    TimeSlow,
+    Grenade,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum SkillSlot {
+    Shift,
+    RightMouse,
+}
+
+impl SkillSlot {
+    pub fn all() -> Vec<SkillSlot> {
+        vec![SkillSlot::Shift, SkillSlot::RightMouse]
+    }
+
+    pub fn action(&self) -> InputAction {
+        match self {
+            SkillSlot::Shift => InputAction::SkillOne,
+            SkillSlot::RightMouse => InputAction::SkillTwo,
+        }
+    }
+}
+
+impl Skill {
+    pub fn slot(&self) -> SkillSlot {
+        match self {
+            Skill::Shockwave => SkillSlot::Shift,
+            Skill::TimeSlow => SkillSlot::Shift,
+            Skill::Grenade => SkillSlot::RightMouse,
+        }
+    }
+
+    pub fn cooldown(&self) -> f32 {
+        match self {
+            Skill::Shockwave => 8.,
+            Skill::TimeSlow => 20.,
+            Skill::Grenade => 6.,
+        }
+    }
+}
+
+impl Ability {
+    pub fn skill(&self) -> Option<Skill> {
+        match self {
+            Ability::Shockwave => Some(Skill::Shockwave),
+            Ability::Hourglass => Some(Skill::TimeSlow),
+            Ability::Grenade => Some(Skill::Grenade),
+            _ => None,
+        }
+    }
+}
+
+impl Player {
+    pub fn skill_in(&self, slot: SkillSlot) -> Option<Skill> {
+        self.abilities
+            .iter()
+            .filter_map(|ability| ability.skill())
+            .find(|skill| skill.slot() == slot)
+    }
+}
+
+// Skills missing from here are ready
+#[derive(Resource, Default)]
+pub struct SkillCooldowns(pub HashMap<Skill, Timer>);
+
+impl SkillCooldowns {
+    pub fn is_ready(&self, skill: Skill) -> bool {
+        self.0.get(&skill).map_or(true, |timer| timer.finished())
+    }
+
+    // Between 0 and 1, 1 being ready
+    pub fn progress(&self, skill: Skill) -> f32 {
+        self.0.get(&skill).map_or(1., |timer| timer.percent())
+    }
+}
+
+pub const SHOCKWAVE_RADIUS: f32 = 120.;
+pub const SHOCKWAVE_FORCE: f32 = 400.;
+pub const SHOCKWAVE_DAMAGE: u32 = 10;
+pub const TIME_SLOW_SPEED: f32 = 0.4;
+// In real time, so the slow doesn't drag itself out
+pub const TIME_SLOW_DURATION: f32 = 3.;
+pub const GRENADE_SPEED: f32 = 200.;
+pub const GRENADE_FUSE: f32 = 0.6;
+
+#[derive(Resource)]
+pub struct TimeSlow(Timer);
+
+#[derive(Component)]
+pub struct Grenade(Timer);
+
+fn reset_skills(
+    mut cooldowns: ResMut<SkillCooldowns>,
+    mut clock: ResMut<GameClock>,
+    mut commands: Commands,
+) {
+    cooldowns.0.clear();
+    clock.clear_layer(TimeLayer::SlowMotion);
+    commands.remove_resource::<TimeSlow>();
+}
+
+fn use_skills(
+    q_player: Query<(&Player, &Transform)>,
+    mut q_enemies: Query<(&Transform, &mut Velocity), (With<Enemy>, Without<Player>)>,
+    input: ActionInput,
+    actions: Res<Actions>,
+    clock: Res<GameClock>,
+    mut cooldowns: ResMut<SkillCooldowns>,
+    textures: Res<TextureAssets>,
+    debug_textures: Res<DebugTextureAssets>,
+    audio: Res<AudioAssets>,
+    fx: Res<FXChannel>,
+    mut commands: Commands,
+) {
+    // Cooldowns run on real time so slowing time doesn't slow them down too
+    for timer in cooldowns.0.values_mut() {
+        timer.tick(clock.real_delta());
+    }
+
+    let (player, transform) = q_player.single();
+    let position = transform.translation.truncate();
+
+    for slot in SkillSlot::all() {
+        let Some(skill) = player.skill_in(slot) else {
+            continue;
+        };
+
+        if !input.just_pressed(slot.action()) || !cooldowns.is_ready(skill) {
+            continue;
+        }
+
+        match skill {
+            Skill::Shockwave => {
+                for (enemy_transform, mut velocity) in q_enemies.iter_mut() {
+                    let diff = enemy_transform.translation.truncate() - position;
+
+                    if diff.length() < SHOCKWAVE_RADIUS {
+                        velocity.vec += diff.normalize_or_zero() * SHOCKWAVE_FORCE;
+                    }
+                }
+
+                commands
+                    .spawn(SpriteBundle {
+                        texture: debug_textures.circle.clone(),
+                        sprite: Sprite {
+                            color: Color::WHITE.with_a(0.3),
+                            custom_size: Some(Vec2::splat(SHOCKWAVE_RADIUS * 2.)),
+                            ..Default::default()
+                        },
+                        transform: Transform::from_translation(
+                            position.extend(SortingLayers::Action.into()),
+                        ),
+                        ..Default::default()
+                    })
+                    .insert(Projectile {
+                        damage_target: DamageTarget::Team(Team::Enemy),
+                        dmg: SHOCKWAVE_DAMAGE,
+                        piercing_mode: PiercingMode::All,
+                        entities_hit: vec![],
+                        is_alive: true,
+                        source: DamageSource::Shockwave,
+                    })
+                    .insert(Collider::new_circle(SHOCKWAVE_RADIUS))
+                    .insert(Lifetime::from_seconds(0.2))
+                    .insert(DespawnOnExit::run());
+
+                fx.play(audio.explosion.clone());
+            }
+            Skill::TimeSlow => {
+                commands.insert_resource(TimeSlow(Timer::from_seconds(
+                    TIME_SLOW_DURATION,
+                    TimerMode::Once,
+                )));
+            }
+            Skill::Grenade => {
+                let Some(direction) = actions.aim_direction else {
+                    continue;
+                };
+
+                commands
+                    .spawn(SpriteBundle {
+                        texture: textures.bullet_large.clone(),
+                        transform: Transform {
+                            translation: position.extend(SortingLayers::Action.into()),
+                            rotation: Quat::IDENTITY,
+                            scale: SCALING_VEC3,
+                        },
+                        ..Default::default()
+                    })
+                    .insert(Velocity::from(direction * GRENADE_SPEED))
+                    .insert(Grenade(Timer::from_seconds(GRENADE_FUSE, TimerMode::Once)))
+                    .insert(DespawnOnExit::run());
+            }
+        }
+
+        cooldowns.0.insert(
+            skill,
+            Timer::from_seconds(skill.cooldown(), TimerMode::Once),
+        );
+    }
+}
+
+fn time_slow_update(
+    slow: Option<ResMut<TimeSlow>>,
+    mut clock: ResMut<GameClock>,
+    mut commands: Commands,
+) {
+    let Some(mut slow) = slow else {
+        return;
+    };
+
+    slow.0.tick(clock.real_delta());
+
+    match slow.0.finished() {
+        true => {
+            clock.clear_layer(TimeLayer::SlowMotion);
+            commands.remove_resource::<TimeSlow>();
+        }
+        false => clock.set_layer(TimeLayer::SlowMotion, TIME_SLOW_SPEED),
+    }
+}
+
+fn grenade_update(
+    mut q_grenades: Query<(Entity, &Transform, &mut Grenade)>,
+    q_player: Query<&Player>,
+    clock: Res<GameClock>,
+    animations: Res<AnimationStateStorage<ExplosionAnimation>>,
+    textures: Res<TextureAssets>,
+    mut texture_atlases: ResMut<Assets<TextureAtlas>>,
+    audio: Res<AudioAssets>,
+    fx: Res<FXChannel>,
+    mut pool: ResMut<EntityPool>,
+    mut commands: Commands,
+) {
+    let player = q_player.single();
+
+    for (entity, transform, mut grenade) in q_grenades.iter_mut() {
+        grenade.0.tick(clock.delta());
+
+        if !grenade.0.finished() {
+            continue;
+        }
+
+        fx.play(audio.explosion.clone());
+        spawn_explosion(
+            transform.translation,
+            player.damage() * 3,
+            DamageTarget::Team(Team::Enemy),
+            &animations,
+            explosion_atlas(&textures, &mut texture_atlases),
+            &mut pool,
+            &mut commands,
+        );
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
+#[derive(Component)]
+pub struct SkillCooldownUI(SkillSlot);
+
+fn spawn_skill_cooldown_ui(
+    textures: Res<TextureAssets>,
+    mut texture_atlases: ResMut<Assets<TextureAtlas>>,
+    mut commands: Commands,
+) {
+    let texture_atlas = TextureAtlas::from_grid(
+        textures.reload_ui.clone(),
+        Vec2 { x: 16., y: 16. },
+        10,
+        1,
+        None,
+        None,
+    );
+    let texture_atlas_handle = texture_atlases.add(texture_atlas);
+
+    for slot in SkillSlot::all() {
+        commands
+            .spawn(SpriteSheetBundle {
+                texture_atlas: texture_atlas_handle.clone(),
+                sprite: TextureAtlasSprite::new(9),
+                transform: Transform {
+                    translation: default(),
+                    rotation: Quat::IDENTITY,
+                    scale: SCALING_VEC3,
+                },
+                visibility: Visibility::Hidden,
+                ..Default::default()
+            })
+            .insert(SkillCooldownUI(slot));
+    }
+}
+
+// Same radial as the reload indicator, one per slot along the bottom right
+fn update_skill_cooldown_ui(
+    mut q_cooldown_ui: Query<(
+        &SkillCooldownUI,
+        &mut Transform,
+        &mut TextureAtlasSprite,
+        &mut Visibility,
+    )>,
+    q_windows: Query<&Window, (With<PrimaryWindow>, Without<SkillCooldownUI>)>,
+    q_player: Query<&Player>,
+    cooldowns: Res<SkillCooldowns>,
+) {
+    let window = q_windows.single();
+    let player = q_player.single();
+
+    for (cooldown_ui, mut transform, mut atlas, mut visibility) in q_cooldown_ui.iter_mut() {
+        let i = SkillSlot::all()
+            .iter()
+            .position(|slot| *slot == cooldown_ui.0)
+            .unwrap_or(0);
+
+        transform.translation = Vec3 {
+            x: window.width() / 2. - 40. - 40. * i as f32,
+            y: window.height() / -2. + 30.,
+            z: SortingLayers::UI.into(),
+        };
+
+        match player.skill_in(cooldown_ui.0) {
+            Some(skill) => {
+                *visibility = Visibility::Visible;
+                atlas.index = (9. * cooldowns.progress(skill)) as usize;
+            }
+            None => {
+                *visibility = Visibility::Hidden;
+            }
+        }
+    }
+}
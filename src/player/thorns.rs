@@ -10,14 +10,14 @@ use crate::{
     },
     collision::collider::{Collider},
     combat::{
-        health::TookDamageEvent,
+        health::{DamageSource, TookDamageEvent},
         projectile::{DamageTarget, PiercingMode, Projectile},
         teams::Team,
     },
     constants::{SortingLayers},
     enemies::{enemy::Enemy},
     loading::TextureAssets,
-    movement::pause::ActionPauseState,
+    util::{clock::GameClock, despawn_on_exit::DespawnOnExit},
 };
 
 use super::Player;
@@ -68,31 +68,39 @@ pub fn thorns_update(
         (Without<Player>, Without<Enemy>),
     >,
     mut animation_ev: EventWriter<AnimationStateChangeEvent<ThornsAnimation>>,
-    _hit_ev: EventReader<TookDamageEvent>,
+    mut hit_ev: EventReader<TookDamageEvent>,
     mut timer: ResMut<ThornsTimer>,
     animations: Res<AnimationStateStorage<ThornsAnimation>>,
     textures: Res<TextureAssets>,
     mut texture_atlases: ResMut<Assets<TextureAtlas>>,
-    pause: Res<ActionPauseState>,
-    time: Res<Time>,
+    clock: Res<GameClock>,
     mut commands: Commands,
 ) {
-    let (_player_entity, player, player_pos) = q_player.single();
+    let (player_entity, player, player_pos) = q_player.single();
 
-    if !player.abilities.contains(&super::ability::Ability::Thorns) {
-        return;
-    }
+    // Read every frame, so hits from before Retaliation was owned can't set it off later
+    let was_hit = hit_ev
+        .iter()
+        .filter(|hit| hit.entity == player_entity)
+        .count()
+        > 0;
 
-    if pause.is_paused {
+    if !player.abilities.contains(&super::ability::Ability::Thorns) {
         return;
     }
 
-    timer.0.tick(time.delta());
+    timer.0.tick(clock.delta());
     let thorns = q_thorns.get_single_mut();
 
+    // Retaliation brings the thorns out early when the player gets hurt
+    let retaliates = player
+        .abilities
+        .contains(&super::ability::Ability::Retaliation)
+        && was_hit;
+
     // No thorns
     if thorns.is_err() {
-        if timer.0.just_finished() {
+        if timer.0.just_finished() || retaliates {
             timer.0.set_duration(Duration::from_secs_f32(1.));
             timer.0.reset();
 
@@ -125,7 +133,9 @@ pub fn thorns_update(
                     piercing_mode: PiercingMode::All,
                     entities_hit: vec![],
                     is_alive: true,
-                });
+                    source: DamageSource::Thorns,
+                })
+                .insert(DespawnOnExit::run());
         }
     } else {
         let (thorns_entity, mut thorns_transform, thorns_controller) = thorns.unwrap();
@@ -167,7 +177,9 @@ pub fn thorns_update(
 
                     timer
                         .0
-                        .set_duration(Duration::from_secs_f32(THORNS_COOLDOWN));
+                        .set_duration(Duration::from_secs_f32(
+                            THORNS_COOLDOWN * player.character.thorns_cooldown_mult(),
+                        ));
                     timer.0.reset();
                 }
             }
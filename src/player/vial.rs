@@ -3,7 +3,7 @@ use std::time::Duration;
 use bevy::prelude::*;
 use bevy_kira_audio::AudioControl;
 
-use crate::{enemies::enemy::EnemyDeathEvent, ui::{alignment::{AnchorBundle, Alignment, AlignedBundle}, element::{UIElement, SizeVec2, SizeConstraint}, offset::Offset}, loading::{TextureAssets, AudioAssets}, GameState, combat::health::Health, constants::SortingLayers, audio::FXChannel};
+use crate::{enemies::enemy::EnemyDeathEvent, ui::{alignment::{AnchorBundle, Alignment, AlignedBundle}, element::{UIElement, SizeVec2, SizeConstraint}, offset::Offset}, loading::{TextureAssets, AudioAssets}, GameState, combat::health::Health, constants::SortingLayers, audio::FXChannel, util::{clock::GameClock, despawn_on_exit::DespawnOnExit}};
 
 use super::{Player, ability::Ability};
 
@@ -29,7 +29,7 @@ fn vial_update(
     mut death_ev : EventReader<EnemyDeathEvent>,
     textures: Res<TextureAssets>,
     mut texture_atlases: ResMut<Assets<TextureAtlas>>,
-    time : Res<Time>,
+    clock : Res<GameClock>,
     audio : Res<AudioAssets>,
     fx : Res<FXChannel>,
     mut commands : Commands,
@@ -68,12 +68,12 @@ fn vial_update(
             limit: 100,
             animation_count: 0,
             animation_timer: Timer::from_seconds(1. / 8., TimerMode::Repeating)
-        });
+        }).insert(DespawnOnExit::run());
         return;
     }
 
     let (mut vial, mut atlas) = vial_result.unwrap();
-    vial.animation_timer.tick(time.delta());
+    vial.animation_timer.tick(clock.delta());
 
     let mut is_healing = false;
 
@@ -0,0 +1,319 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    actions::{input_map::InputMap, targeting::TargetingMode},
+    audio::{FXChannel, Music, MusicChannel, Volume, FX},
+    meta::MetaUpgrade,
+    player::character::unlock_characters,
+    stats::{RunEndedEvent, RunStats},
+    util::rng::GlobalSeed,
+};
+
+pub mod storage;
+
+pub struct SavePlugin;
+
+impl Plugin for SavePlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(load())
+            .add_systems(Startup, apply_settings)
+            .add_systems(Update, (update_settings, record_run, write_save).chain());
+    }
+}
+
+// Bump this whenever the layout of SaveData changes, and add a step to `migrate`
+pub const SAVE_VERSION: u32 = 1;
+
+const SAVE_SLOT: &str = "save";
+// The last save that loaded fine, used when the main one is corrupted
+const BACKUP_SLOT: &str = "save_backup";
+// Unreadable saves are moved here instead of being overwritten
+const CORRUPT_SLOT: &str = "save_corrupt";
+
+// Runs kept per seed on the leaderboard
+pub const LEADERBOARD_SIZE: usize = 10;
+
+/*
+Everything that outlives a single launch of the game. Every field has a default so
+saves written by older versions still load, missing fields being filled in
+*/
+#[derive(Resource, Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct SaveData {
+    pub version: u32,
+    pub settings: Settings,
+    pub runs_played: u32,
+    // Best runs for each seed, best first
+    pub leaderboard: BTreeMap<String, Vec<RunRecord>>,
+    pub unlocks: BTreeSet<String>,
+    // Meta currency, earned every run
+    pub souls: u32,
+    pub upgrades: BTreeMap<MetaUpgrade, u32>,
+}
+
+impl Default for SaveData {
+    fn default() -> Self {
+        Self {
+            version: SAVE_VERSION,
+            settings: default(),
+            runs_played: 0,
+            leaderboard: default(),
+            unlocks: default(),
+            souls: 0,
+            upgrades: default(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct Settings {
+    pub music_volume: f32,
+    pub fx_volume: f32,
+    pub targeting: TargetingMode,
+    // With touch controls, shoots at the nearest enemy while the aim stick is let go
+    pub auto_fire: bool,
+    // Off for players who get sick from the camera moving on its own
+    pub screen_shake: bool,
+    pub bindings: InputMap,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            music_volume: 1.,
+            fx_volume: 1.,
+            targeting: default(),
+            auto_fire: true,
+            screen_shake: true,
+            bindings: default(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct RunRecord {
+    pub survived_secs: f32,
+    pub level: u32,
+    pub kills: u32,
+}
+
+impl SaveData {
+    pub fn best_run(&self, seed: &str) -> Option<&RunRecord> {
+        self.leaderboard.get(seed).and_then(|runs| runs.first())
+    }
+
+    pub fn best_time(&self) -> f32 {
+        self.leaderboard
+            .values()
+            .filter_map(|runs| runs.first())
+            .fold(0., |best, run| run.survived_secs.max(best))
+    }
+
+    pub fn add_run(&mut self, seed: &str, record: RunRecord) {
+        let runs = self.leaderboard.entry(seed.to_string()).or_default();
+        runs.push(record);
+        runs.sort_by(|a, b| b.survived_secs.total_cmp(&a.survived_secs));
+        runs.truncate(LEADERBOARD_SIZE);
+    }
+
+    pub fn is_unlocked(&self, flag: &str) -> bool {
+        self.unlocks.contains(flag)
+    }
+
+    pub fn unlock(&mut self, flag: &str) {
+        self.unlocks.insert(flag.to_string());
+    }
+}
+
+fn migrate(mut data: SaveData) -> SaveData {
+    if data.version > SAVE_VERSION {
+        warn!(
+            "Save was written by a newer version ({} > {}), unknown fields are dropped",
+            data.version, SAVE_VERSION
+        );
+    }
+
+    // Steps from older versions go here, e.g.
+    // if data.version < 2 { ... }
+
+    data.version = SAVE_VERSION;
+    data
+}
+
+fn parse(contents: &str) -> Option<SaveData> {
+    match ron::from_str::<SaveData>(contents) {
+        Ok(data) => Some(migrate(data)),
+        Err(err) => {
+            warn!("Could not read save: {}", err);
+            None
+        }
+    }
+}
+
+pub fn load() -> SaveData {
+    let contents = match storage::read(SAVE_SLOT) {
+        Some(contents) => contents,
+        None => return SaveData::default(),
+    };
+
+    if let Some(data) = parse(&contents) {
+        return data;
+    }
+
+    // Keep the unreadable save around rather than losing it on the next write
+    if let Err(err) = storage::write(CORRUPT_SLOT, &contents) {
+        warn!("Could not back up corrupted save: {}", err);
+    }
+
+    match storage::read(BACKUP_SLOT).and_then(|backup| parse(&backup)) {
+        Some(data) => {
+            warn!("Save was corrupted, restored from backup");
+            data
+        }
+        None => {
+            warn!("Save was corrupted, starting over");
+            SaveData::default()
+        }
+    }
+}
+
+pub fn write(data: &SaveData) {
+    let contents = match ron::ser::to_string_pretty(data, ron::ser::PrettyConfig::default()) {
+        Ok(contents) => contents,
+        Err(err) => {
+            warn!("Could not serialize save: {}", err);
+            return;
+        }
+    };
+
+    if let Some(previous) = storage::read(SAVE_SLOT) {
+        if parse(&previous).is_some() {
+            if let Err(err) = storage::write(BACKUP_SLOT, &previous) {
+                warn!("Could not back up save: {}", err);
+            }
+        }
+    }
+
+    if let Err(err) = storage::write(SAVE_SLOT, &contents) {
+        warn!("Could not write save: {}", err);
+    }
+}
+
+fn apply_settings(
+    save: Res<SaveData>,
+    mut music_volume: ResMut<Volume<Music>>,
+    mut fx_volume: ResMut<Volume<FX>>,
+    mut music_channel: ResMut<MusicChannel>,
+    mut fx_channel: ResMut<FXChannel>,
+    mut input_map: ResMut<InputMap>,
+) {
+    music_volume.set_volume(save.settings.music_volume, &mut music_channel);
+    fx_volume.set_volume(save.settings.fx_volume, &mut fx_channel);
+    *input_map = save.settings.bindings.clone();
+}
+
+fn update_settings(
+    music_volume: Res<Volume<Music>>,
+    fx_volume: Res<Volume<FX>>,
+    input_map: Res<InputMap>,
+    mut save: ResMut<SaveData>,
+) {
+    if input_map.is_changed() && save.settings.bindings != *input_map {
+        save.settings.bindings = input_map.clone();
+    }
+
+    if !music_volume.is_changed() && !fx_volume.is_changed() {
+        return;
+    }
+
+    if save.settings.music_volume != music_volume.volume()
+        || save.settings.fx_volume != fx_volume.volume()
+    {
+        save.settings.music_volume = music_volume.volume();
+        save.settings.fx_volume = fx_volume.volume();
+    }
+}
+
+fn record_run(
+    mut run_ended_ev: EventReader<RunEndedEvent>,
+    stats: Res<RunStats>,
+    seed: Res<GlobalSeed>,
+    mut save: ResMut<SaveData>,
+) {
+    for run_ended in run_ended_ev.iter() {
+        save.runs_played += 1;
+        save.add_run(
+            &seed.0,
+            RunRecord {
+                survived_secs: run_ended.survived.as_secs_f32(),
+                level: stats.level,
+                kills: stats.total_kills(),
+            },
+        );
+        unlock_characters(&mut save, &stats, run_ended.survived.as_secs_f32());
+    }
+}
+
+fn write_save(save: Res<SaveData>) {
+    if save.is_changed() && !save.is_added() {
+        write(&save);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::actions::input_map::{Binding, InputAction};
+
+    use super::*;
+
+    fn serialize(data: &SaveData) -> String {
+        ron::ser::to_string_pretty(data, ron::ser::PrettyConfig::default()).unwrap()
+    }
+
+    #[test]
+    fn missing_fields_are_filled_in() {
+        let data = parse("(version: 1, runs_played: 3)").unwrap();
+
+        assert_eq!(data.version, SAVE_VERSION);
+        assert_eq!(data.runs_played, 3);
+        assert_eq!(data.souls, 0);
+        assert!(data.upgrades.is_empty());
+        assert_eq!(data.settings.targeting, TargetingMode::AimAssist);
+        assert!(data.settings.auto_fire);
+        assert!(data.settings.screen_shake);
+        assert!(data.settings.bindings == InputMap::default());
+    }
+
+    #[test]
+    fn current_saves_round_trip() {
+        let mut data = SaveData::default();
+        data.souls = 40;
+        data.settings.targeting = TargetingMode::LowestHealth;
+        data.settings
+            .bindings
+            .rebind(InputAction::Dash, Binding::Key(KeyCode::E));
+        data.add_run(
+            "dawn",
+            RunRecord {
+                survived_secs: 90.,
+                level: 4,
+                kills: 30,
+            },
+        );
+
+        let loaded = parse(&serialize(&data)).unwrap();
+        assert_eq!(loaded.souls, 40);
+        assert_eq!(loaded.settings.targeting, TargetingMode::LowestHealth);
+        assert!(loaded.settings.bindings == data.settings.bindings);
+        assert_eq!(loaded.best_run("dawn").map(|run| run.kills), Some(30));
+    }
+
+    #[test]
+    fn unreadable_saves_are_rejected() {
+        assert!(parse("(version: 1, souls: \"many\")").is_none());
+    }
+}
@@ -0,0 +1,108 @@
+/*
+Where the save file actually lives. Native builds write files to the platform data
+directory, wasm builds use the browser's localStorage
+*/
+
+#[cfg(not(target_arch = "wasm32"))]
+mod backend {
+    use std::{
+        fs,
+        io::ErrorKind,
+        path::{Path, PathBuf},
+    };
+
+    use bevy::prelude::warn;
+
+    const APP_DIR: &str = "nightfall";
+
+    // None of the usual variables are set for Android apps, they get a private directory instead
+    #[cfg(target_os = "android")]
+    fn platform_dir() -> Option<PathBuf> {
+        bevy::winit::ANDROID_APP
+            .get()
+            .and_then(|app| app.internal_data_path())
+    }
+
+    #[cfg(not(target_os = "android"))]
+    fn platform_dir() -> Option<PathBuf> {
+        use std::env;
+
+        if cfg!(target_os = "windows") {
+            env::var_os("APPDATA").map(PathBuf::from)
+        } else if cfg!(any(target_os = "macos", target_os = "ios")) {
+            env::var_os("HOME").map(|home| PathBuf::from(home).join("Library/Application Support"))
+        } else {
+            env::var_os("XDG_DATA_HOME").map(PathBuf::from).or_else(|| {
+                env::var_os("HOME").map(|home| PathBuf::from(home).join(".local/share"))
+            })
+        }
+    }
+
+    fn data_dir() -> PathBuf {
+        let base = platform_dir().unwrap_or_else(|| {
+            warn!("No data directory found, saving next to the game");
+            PathBuf::from(".")
+        });
+
+        base.join(APP_DIR)
+    }
+
+    fn slot_path(slot: &str) -> PathBuf {
+        data_dir().join(format!("{}.ron", slot))
+    }
+
+    fn describe(path: &Path, err: std::io::Error) -> String {
+        format!("{}: {}", path.display(), err)
+    }
+
+    pub fn read(slot: &str) -> Option<String> {
+        let path = slot_path(slot);
+
+        match fs::read_to_string(&path) {
+            Ok(contents) => Some(contents),
+            // Nothing saved yet
+            Err(err) if err.kind() == ErrorKind::NotFound => None,
+            Err(err) => {
+                warn!("Could not read {}", describe(&path, err));
+                None
+            }
+        }
+    }
+
+    pub fn write(slot: &str, contents: &str) -> Result<(), String> {
+        let dir = data_dir();
+        let path = slot_path(slot);
+        let tmp_path = path.with_extension("ron.tmp");
+
+        fs::create_dir_all(&dir).map_err(|err| describe(&dir, err))?;
+        // Write then rename, so a crash mid-write never leaves a half written save
+        fs::write(&tmp_path, contents).map_err(|err| describe(&tmp_path, err))?;
+        fs::rename(&tmp_path, &path).map_err(|err| describe(&path, err))
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+mod backend {
+    use web_sys::Storage;
+
+    const KEY_PREFIX: &str = "nightfall_";
+
+    fn local_storage() -> Option<Storage> {
+        web_sys::window()?.local_storage().ok()?
+    }
+
+    pub fn read(slot: &str) -> Option<String> {
+        local_storage()?
+            .get_item(&format!("{}{}", KEY_PREFIX, slot))
+            .ok()?
+    }
+
+    pub fn write(slot: &str, contents: &str) -> Result<(), String> {
+        local_storage()
+            .ok_or("localStorage is unavailable")?
+            .set_item(&format!("{}{}", KEY_PREFIX, slot), contents)
+            .map_err(|err| format!("{:?}", err))
+    }
+}
+
+pub use backend::{read, write};
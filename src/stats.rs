@@ -0,0 +1,171 @@
+use std::time::Duration;
+
+use bevy::{
+    prelude::*,
+    utils::{HashMap, HashSet},
+};
+
+use crate::{
+    combat::{
+        health::{DamageSource, TookDamageEvent},
+        projectile::{projectile_collision_check, Projectile, ProjectileHitEvent},
+    },
+    enemies::enemy::{Enemy, EnemyDeathEvent, EnemyType},
+    experience::{
+        ability_selection::AbilityTakenEvent, experience::LevelUpEvent,
+        xp_crystal::XPCollectedEvent,
+    },
+    player::{ability::Ability, Player},
+    GameState,
+};
+
+use self::summary::summary_tab_update;
+
+pub mod summary;
+
+pub struct StatsPlugin;
+
+impl Plugin for StatsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<RunStats>()
+            .add_event::<RunEndedEvent>()
+            .add_systems(OnEnter(GameState::Playing), reset_run_stats)
+            .add_systems(
+                Update,
+                (
+                    track_kills,
+                    track_damage,
+                    track_shots.after(projectile_collision_check),
+                    track_progress,
+                    summary_tab_update,
+                )
+                    .run_if(in_state(GameState::Playing)),
+            );
+    }
+}
+
+// Sent once when the player dies and the game over screen comes up
+#[derive(Event)]
+pub struct RunEndedEvent {
+    pub survived: Duration,
+}
+
+/*
+Everything worth telling the player about once their run is over
+*/
+#[derive(Resource, Default)]
+pub struct RunStats {
+    pub kills: HashMap<EnemyType, u32>,
+    pub damage_dealt: HashMap<DamageSource, u32>,
+    pub damage_taken: u32,
+    pub shots_fired: u32,
+    // Shots that hit at least one enemy
+    pub shots_hit: u32,
+    pub xp_collected: u32,
+    pub level: u32,
+    pub abilities: Vec<Ability>,
+}
+
+impl RunStats {
+    pub fn kills_of(&self, enemy_type: EnemyType) -> u32 {
+        *self.kills.get(&enemy_type).unwrap_or(&0)
+    }
+
+    pub fn total_kills(&self) -> u32 {
+        self.kills.values().sum()
+    }
+
+    pub fn damage_from(&self, source: DamageSource) -> u32 {
+        *self.damage_dealt.get(&source).unwrap_or(&0)
+    }
+
+    pub fn total_damage_dealt(&self) -> u32 {
+        self.damage_dealt.values().sum()
+    }
+
+    // Between 0 and 1
+    pub fn accuracy(&self) -> f32 {
+        match self.shots_fired {
+            0 => 0.,
+            fired => self.shots_hit as f32 / fired as f32,
+        }
+    }
+}
+
+pub fn reset_run_stats(mut stats: ResMut<RunStats>) {
+    *stats = RunStats::default();
+}
+
+fn track_kills(mut death_ev: EventReader<EnemyDeathEvent>, mut stats: ResMut<RunStats>) {
+    for death in death_ev.iter() {
+        *stats.kills.entry(death.enemy.enemy_type).or_insert(0) += 1;
+    }
+}
+
+fn track_damage(
+    q_player: Query<Entity, With<Player>>,
+    q_enemies: Query<Entity, With<Enemy>>,
+    mut damage_ev: EventReader<TookDamageEvent>,
+    mut stats: ResMut<RunStats>,
+) {
+    let player = q_player.single();
+
+    for damage in damage_ev.iter() {
+        if damage.entity == player {
+            stats.damage_taken += damage.amount;
+        } else if q_enemies.contains(damage.entity) {
+            *stats.damage_dealt.entry(damage.source).or_insert(0) += damage.amount;
+        }
+    }
+}
+
+fn track_shots(
+    q_new_projectiles: Query<&Projectile, Added<Projectile>>,
+    q_projectiles: Query<&Projectile>,
+    q_enemies: Query<Entity, With<Enemy>>,
+    mut hit_ev: EventReader<ProjectileHitEvent>,
+    mut removed: RemovedComponents<Projectile>,
+    // Shots that already hit an enemy, piercing bullets only count once
+    mut counted: Local<HashSet<Entity>>,
+    mut stats: ResMut<RunStats>,
+) {
+    for projectile in removed.iter() {
+        counted.remove(&projectile);
+    }
+
+    stats.shots_fired += q_new_projectiles
+        .iter()
+        .filter(|projectile| projectile.source == DamageSource::Bullet)
+        .count() as u32;
+
+    for hit in hit_ev.iter() {
+        if !q_enemies.contains(hit.victim) {
+            continue;
+        }
+
+        if let Ok(projectile) = q_projectiles.get(hit.projectile) {
+            if projectile.source == DamageSource::Bullet && counted.insert(hit.projectile) {
+                stats.shots_hit += 1;
+            }
+        }
+    }
+}
+
+fn track_progress(
+    mut level_up_ev: EventReader<LevelUpEvent>,
+    mut collected_ev: EventReader<XPCollectedEvent>,
+    mut taken_ev: EventReader<AbilityTakenEvent>,
+    mut stats: ResMut<RunStats>,
+) {
+    for level_up in level_up_ev.iter() {
+        stats.level = stats.level.max(level_up.new_level);
+    }
+
+    for collected in collected_ev.iter() {
+        stats.xp_collected += collected.amount;
+    }
+
+    for taken in taken_ev.iter() {
+        stats.abilities.push(taken.ability);
+    }
+}
@@ -0,0 +1,214 @@
+use bevy::prelude::*;
+
+use crate::{
+    combat::health::DamageSource, enemies::enemy::EnemyType, loading::FontAssets,
+    palette::Palette,
+};
+
+use super::RunStats;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum SummaryTab {
+    Overview,
+    Combat,
+    Kills,
+    Abilities,
+}
+
+impl SummaryTab {
+    pub fn all() -> Vec<SummaryTab> {
+        vec![
+            SummaryTab::Overview,
+            SummaryTab::Combat,
+            SummaryTab::Kills,
+            SummaryTab::Abilities,
+        ]
+    }
+
+    pub fn name(&self) -> String {
+        match self {
+            SummaryTab::Overview => "Overview",
+            SummaryTab::Combat => "Combat",
+            SummaryTab::Kills => "Kills",
+            SummaryTab::Abilities => "Abilities",
+        }
+        .to_string()
+    }
+
+    fn lines(&self, stats: &RunStats) -> Vec<String> {
+        match self {
+            SummaryTab::Overview => vec![
+                format!("Reached level {}", stats.level),
+                format!("Slew {} foes", stats.total_kills()),
+                format!("Gathered {} experience", stats.xp_collected),
+                format!("Dealt {} damage", stats.total_damage_dealt()),
+                format!("Suffered {} wounds", stats.damage_taken),
+            ],
+            SummaryTab::Combat => {
+                let mut lines = vec![
+                    format!("Shots fired: {}", stats.shots_fired),
+                    format!("Accuracy: {}%", (stats.accuracy() * 100.).round()),
+                ];
+
+                for source in DamageSource::all() {
+                    if source == DamageSource::Enemy {
+                        continue;
+                    }
+
+                    lines.push(format!(
+                        "{} damage: {}",
+                        source.name(),
+                        stats.damage_from(source)
+                    ));
+                }
+
+                lines.push(format!("Damage taken: {}", stats.damage_taken));
+                lines
+            }
+            SummaryTab::Kills => EnemyType::all()
+                .iter()
+                .map(|enemy_type| format!("{}: {}", enemy_type.name(), stats.kills_of(*enemy_type)))
+                .collect(),
+            SummaryTab::Abilities => match stats.abilities.is_empty() {
+                true => vec!["None taken".to_string()],
+                false => stats
+                    .abilities
+                    .iter()
+                    .enumerate()
+                    .map(|(i, ability)| format!("{}. {}", i + 1, ability.get_name()))
+                    .collect(),
+            },
+        }
+    }
+}
+
+#[derive(Component)]
+pub struct SummaryTabButton(pub SummaryTab);
+
+#[derive(Component)]
+pub struct SummaryPage(pub SummaryTab);
+
+// Spawned as part of the game over screen
+pub fn spawn_run_summary(
+    parent: &mut ChildBuilder,
+    stats: &RunStats,
+    font_assets: &FontAssets,
+    palette: &Palette,
+) {
+    parent
+        .spawn(NodeBundle {
+            style: Style {
+                display: Display::Flex,
+                flex_direction: FlexDirection::Column,
+                align_items: AlignItems::Center,
+                margin: UiRect::all(Val::Auto),
+                ..default()
+            },
+            ..default()
+        })
+        .with_children(|parent| {
+            parent
+                .spawn(NodeBundle {
+                    style: Style {
+                        display: Display::Flex,
+                        flex_direction: FlexDirection::Row,
+                        ..default()
+                    },
+                    ..default()
+                })
+                .with_children(|parent| {
+                    for tab in SummaryTab::all() {
+                        parent
+                            .spawn(ButtonBundle {
+                                style: Style {
+                                    width: Val::Px(150.0),
+                                    height: Val::Px(40.0),
+                                    margin: UiRect::all(Val::Px(5.0)),
+                                    justify_content: JustifyContent::Center,
+                                    align_items: AlignItems::Center,
+                                    ..default()
+                                },
+                                background_color: match tab == SummaryTab::Overview {
+                                    true => palette.orange.into(),
+                                    false => palette.dark.into(),
+                                },
+                                ..default()
+                            })
+                            .insert(SummaryTabButton(tab))
+                            .with_children(|parent| {
+                                parent.spawn(TextBundle::from_section(
+                                    tab.name(),
+                                    TextStyle {
+                                        font: font_assets.gothic.clone(),
+                                        font_size: 30.0,
+                                        color: palette.white,
+                                    },
+                                ));
+                            });
+                    }
+                });
+
+            for tab in SummaryTab::all() {
+                parent
+                    .spawn(NodeBundle {
+                        style: Style {
+                            display: match tab == SummaryTab::Overview {
+                                true => Display::Flex,
+                                false => Display::None,
+                            },
+                            flex_direction: FlexDirection::Column,
+                            align_items: AlignItems::Center,
+                            min_height: Val::Px(200.0),
+                            ..default()
+                        },
+                        ..default()
+                    })
+                    .insert(SummaryPage(tab))
+                    .with_children(|parent| {
+                        for line in tab.lines(stats) {
+                            parent.spawn(TextBundle::from_section(
+                                line,
+                                TextStyle {
+                                    font: font_assets.gothic.clone(),
+                                    font_size: 30.0,
+                                    color: palette.white,
+                                },
+                            ));
+                        }
+                    });
+            }
+        });
+}
+
+pub fn summary_tab_update(
+    palette: Res<Palette>,
+    mut q_buttons: Query<(&Interaction, &SummaryTabButton, &mut BackgroundColor)>,
+    mut q_pages: Query<(&SummaryPage, &mut Style)>,
+) {
+    let selected = q_buttons
+        .iter()
+        .find(|(interaction, _, _)| **interaction == Interaction::Pressed)
+        .map(|(_, button, _)| button.0);
+
+    if let Some(selected) = selected {
+        for (page, mut style) in q_pages.iter_mut() {
+            style.display = match page.0 == selected {
+                true => Display::Flex,
+                false => Display::None,
+            };
+        }
+    }
+
+    let shown = q_pages
+        .iter()
+        .find(|(_, style)| style.display != Display::None)
+        .map(|(page, _)| page.0);
+
+    for (interaction, button, mut color) in q_buttons.iter_mut() {
+        *color = match (*interaction, Some(button.0) == shown) {
+            (_, true) => palette.orange.into(),
+            (Interaction::Hovered, false) => palette.red.into(),
+            _ => palette.dark.into(),
+        };
+    }
+}
@@ -94,6 +94,25 @@ impl Bar {
             entity.insert(bundle);
         }
     }
+
+    // Moves the value by `amount`, clamped to the bar
+    pub fn step(
+        &mut self,
+        entity: Entity,
+        amount: i32,
+        bar_update: &mut EventWriter<BarUpdatedEvent>,
+    ) {
+        let old_val = self.val;
+        self.val = (self.val as i32 + amount).clamp(0, self.max_val as i32) as u32;
+
+        if self.val != old_val {
+            bar_update.send(BarUpdatedEvent {
+                entity,
+                old_val,
+                new_val: self.val,
+            });
+        }
+    }
 }
 
 #[derive(Component)]
@@ -112,27 +131,13 @@ fn update_bars(
     for ev in click_ev.iter() {
         if let Ok(parent) = q_prev_buttons.get(ev.entity) {
             if let Ok((mut bar, _)) = q_bars.get_mut(parent.get()) {
-                if bar.val > 0 {
-                    bar.val -= 1;
-                    bar_update.send(BarUpdatedEvent {
-                        entity: parent.get(),
-                        old_val: bar.val + 1,
-                        new_val: bar.val,
-                    });
-                }
+                bar.step(parent.get(), -1, &mut bar_update);
             }
         }
 
         if let Ok(bar_entity) = q_next_buttons.get(ev.entity) {
             if let Ok((mut bar, _)) = q_bars.get_mut(bar_entity.get()) {
-                if bar.val < bar.max_val {
-                    bar.val += 1;
-                    bar_update.send(BarUpdatedEvent {
-                        entity: bar_entity.get(),
-                        old_val: bar.val - 1,
-                        new_val: bar.val,
-                    });
-                }
+                bar.step(bar_entity.get(), 1, &mut bar_update);
             }
         }
     }
@@ -43,33 +43,46 @@ pub struct UnclickedEvent {
     pub entity: Entity,
 }
 
+// Window positions have their origin at the top left, UI is centered on the camera
+pub fn window_to_ui(window: &Window, position: Vec2) -> Vec2 {
+    Vec2::new(
+        position.x - window.width() / 2.,
+        window.height() / 2. - position.y,
+    )
+}
+
 fn update_clickables(
     mut q_clickables: Query<(Entity, &mut Clickable, &Collider, &GlobalTransform), Without<Window>>,
     q_windows: Query<&Window, With<PrimaryWindow>>,
     mut clicked: EventWriter<ClickedEvent>,
     mut unclicked: EventWriter<UnclickedEvent>,
     mouse_button: Res<Input<MouseButton>>,
+    touches: Res<Touches>,
 ) {
     let window = q_windows.single();
-    let cursor_position = match window.cursor_position() {
-        Some(position) => position,
-        None => {
-            return;
-        }
-    };
-    let cursor_point = Vec2::new(
-        cursor_position.x - window.width() / 2.,
-        window.height() / 2. - cursor_position.y,
-    );
+    let cursor_point = window
+        .cursor_position()
+        .map(|position| window_to_ui(window, position));
+    // A tap clicks, and is let go of as soon as nothing hovers the clickable
+    let touch_points: Vec<Vec2> = touches
+        .iter_just_pressed()
+        .map(|touch| window_to_ui(window, touch.position()))
+        .collect();
 
     for (entity, mut clickable, collider, transform) in q_clickables.iter_mut() {
-        if collider.contains_point(transform.translation().truncate(), cursor_point) {
-            if mouse_button.just_pressed(MouseButton::Left) {
-                if !clickable.is_clicked {
-                    clickable.is_clicked = true;
-                    clicked.send(ClickedEvent { entity });
-                }
-            } else if mouse_button.just_released(MouseButton::Left) && clickable.is_clicked {
+        let position = transform.translation().truncate();
+        let is_hovered = cursor_point.is_some_and(|point| collider.contains_point(position, point));
+        let is_tapped = touch_points
+            .iter()
+            .any(|point| collider.contains_point(position, *point));
+
+        if is_tapped || (is_hovered && mouse_button.just_pressed(MouseButton::Left)) {
+            if !clickable.is_clicked {
+                clickable.is_clicked = true;
+                clicked.send(ClickedEvent { entity });
+            }
+        } else if is_hovered {
+            if mouse_button.just_released(MouseButton::Left) && clickable.is_clicked {
                 clickable.is_clicked = false;
                 unclicked.send(UnclickedEvent { entity });
             }
@@ -1,8 +1,14 @@
 use bevy::{prelude::*, window::PrimaryWindow};
 
-use crate::collision::collider::Collider;
+use crate::{
+    actions::input_map::{ActionInput, InputAction},
+    collision::collider::Collider,
+};
 
-use super::hoverable::{HoveredEvent, UnhoveredEvent};
+use super::{
+    clickable::window_to_ui,
+    hoverable::{HoveredEvent, UnhoveredEvent},
+};
 
 #[derive(Component)]
 pub struct SelectionGroup {
@@ -34,38 +40,47 @@ pub fn update_selection_groups(
     mut unhover: EventWriter<UnhoveredEvent>,
     mut select: EventWriter<SelectionEvent>,
     mouse_button: Res<Input<MouseButton>>,
-    keyboard_input: Res<Input<KeyCode>>,
+    touches: Res<Touches>,
+    input: ActionInput,
 ) {
     let window = q_windows.single();
+    let cursor_point = window
+        .cursor_position()
+        .map(|position| window_to_ui(window, position));
+    let touch_points: Vec<Vec2> = touches
+        .iter_just_pressed()
+        .map(|touch| window_to_ui(window, touch.position()))
+        .collect();
 
     for (entity, transform, parent, collider, element) in selection_elements.iter() {
         if let Ok((parent_entity, mut group, children)) = selection_groups.get_mut(parent.get()) {
-            if let Some(cursor_position) = window.cursor_position() {
-                let cursor_point = Vec2::new(
-                    cursor_position.x - window.width() / 2.,
-                    window.height() / 2. - cursor_position.y,
-                );
+            let position = transform.translation.truncate();
+            let is_hovered = cursor_point.is_some_and(|point| {
+                !point.x.is_nan() && !point.y.is_nan() && collider.contains_point(position, point)
+            });
+            // Tapping an element picks it right away, there is no hover to see first
+            let is_tapped = touch_points
+                .iter()
+                .any(|point| collider.contains_point(position, *point));
 
-                if !cursor_point.x.is_nan()
-                    && !cursor_point.y.is_nan()
-                    && collider.contains_point(transform.translation.truncate(), cursor_point)
-                {
-                    if group.hovered_index != element.index {
-                        unhover.send(UnhoveredEvent {
-                            entity: *children.get(group.hovered_index).unwrap(),
-                        });
-                        group.hovered_index = element.index;
-                        hover.send(HoveredEvent { entity });
-                    }
+            if !is_hovered && !is_tapped {
+                continue;
+            }
+
+            if group.hovered_index != element.index {
+                unhover.send(UnhoveredEvent {
+                    entity: *children.get(group.hovered_index).unwrap(),
+                });
+                group.hovered_index = element.index;
+                hover.send(HoveredEvent { entity });
+            }
 
-                    if mouse_button.just_pressed(MouseButton::Left) {
-                        select.send(SelectionEvent {
-                            parent: parent_entity,
-                            selected: *children.get(group.hovered_index).unwrap(),
-                            selected_index: group.hovered_index,
-                        })
-                    }
-                }
+            if is_tapped || mouse_button.just_pressed(MouseButton::Left) {
+                select.send(SelectionEvent {
+                    parent: parent_entity,
+                    selected: *children.get(group.hovered_index).unwrap(),
+                    selected_index: group.hovered_index,
+                })
             }
         }
     }
@@ -76,10 +91,8 @@ pub fn update_selection_groups(
         }
 
         if selection_group.is_horizontal {
-            let left_pressed = keyboard_input.just_pressed(KeyCode::Left)
-                || keyboard_input.just_pressed(KeyCode::A);
-            let right_pressed = keyboard_input.just_pressed(KeyCode::Right)
-                || keyboard_input.just_pressed(KeyCode::D);
+            let left_pressed = input.just_pressed(InputAction::MenuLeft);
+            let right_pressed = input.just_pressed(InputAction::MenuRight);
 
             let child_count = children.len();
 
@@ -102,7 +115,7 @@ pub fn update_selection_groups(
             }
         }
 
-        if keyboard_input.just_pressed(KeyCode::Return) {
+        if input.just_pressed(InputAction::Confirm) {
             select.send(SelectionEvent {
                 parent: entity,
                 selected: *children.get(selection_group.hovered_index).unwrap(),
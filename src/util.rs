@@ -1,10 +1,16 @@
 use bevy::prelude::*;
 use rand::{rngs::OsRng, seq::SliceRandom};
 
-use crate::GameState;
+use crate::{GameState, InGame};
 
-use self::{pitch_rng::spawn_pitch_rng, rng::GlobalSeed};
+use self::{
+    clock::{pause_game_clock, reset_game_clock, resume_game_clock, tick_game_clock, GameClock},
+    pitch_rng::spawn_pitch_rng,
+    rng::GlobalSeed,
+};
 
+pub mod clock;
+pub mod despawn_on_exit;
 pub mod pitch_rng;
 pub mod radians;
 pub mod rng;
@@ -14,15 +20,23 @@ pub struct UtilPlugin;
 
 impl Plugin for UtilPlugin {
     fn build(&self, app: &mut bevy::prelude::App) {
-        app.add_systems(OnEnter(GameState::Playing), spawn_pitch_rng)
-            .insert_resource(GlobalSeed(
-                vec![
-                    "dawn", "sun", "moon", "blade", "ring", "lantern", "beast", "shade", "hood",
-                    "powder", "doom", "gaze", "end", "flame",
-                ]
-                .choose(&mut OsRng)
-                .unwrap()
-                .to_string(),
-            ));
+        app.add_systems(
+            OnEnter(GameState::Playing),
+            (spawn_pitch_rng, reset_game_clock),
+        )
+        .init_resource::<GameClock>()
+        .add_systems(PreUpdate, tick_game_clock)
+        .add_systems(OnExit(InGame::Running), pause_game_clock)
+        .add_systems(OnEnter(InGame::Running), resume_game_clock)
+        .add_systems(OnExit(InGame::GameOver), (spawn_pitch_rng, reset_game_clock))
+        .insert_resource(GlobalSeed(
+            vec![
+                "dawn", "sun", "moon", "blade", "ring", "lantern", "beast", "shade", "hood",
+                "powder", "doom", "gaze", "end", "flame",
+            ]
+            .choose(&mut OsRng)
+            .unwrap()
+            .to_string(),
+        ));
     }
 }
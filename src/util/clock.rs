@@ -0,0 +1,170 @@
+use std::{collections::HashMap, time::Duration};
+
+use bevy::prelude::*;
+
+// Each layer scales the gameplay clock on its own, the clock runs at their product
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum TimeLayer {
+    Pause,
+    SlowMotion,
+    HitStop,
+    FastForward,
+}
+
+/*
+Time as the game world sees it. Gameplay reads this instead of `Time`, so pausing,
+slowing down and speeding up the game is one scale instead of a check in every system
+*/
+#[derive(Resource, Default)]
+pub struct GameClock {
+    frame: Duration,
+    layers: HashMap<TimeLayer, f32>,
+}
+
+impl GameClock {
+    pub fn set_layer(&mut self, layer: TimeLayer, scale: f32) {
+        self.layers.insert(layer, scale.max(0.));
+    }
+
+    pub fn clear_layer(&mut self, layer: TimeLayer) {
+        self.layers.remove(&layer);
+    }
+
+    pub fn has_layer(&self, layer: TimeLayer) -> bool {
+        self.layers.contains_key(&layer)
+    }
+
+    pub fn scale(&self) -> f32 {
+        self.layers.values().product()
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.scale() > 0.
+    }
+
+    // Scaled when asked for, so a layer changed during the frame applies right away
+    pub fn delta(&self) -> Duration {
+        self.frame.mul_f32(self.scale())
+    }
+
+    pub fn delta_seconds(&self) -> f32 {
+        self.delta().as_secs_f32()
+    }
+
+    // Unscaled time for things that shouldn't slow down with the game, still stops while paused
+    pub fn real_delta(&self) -> Duration {
+        match self.is_running() {
+            true => self.frame,
+            false => Duration::ZERO,
+        }
+    }
+
+    pub fn real_delta_seconds(&self) -> f32 {
+        self.real_delta().as_secs_f32()
+    }
+
+    // Back to a fresh run, fast forward is a debug toggle and survives it
+    pub fn reset(&mut self) {
+        self.layers
+            .retain(|layer, _| *layer == TimeLayer::FastForward);
+    }
+}
+
+pub fn tick_game_clock(mut clock: ResMut<GameClock>, time: Res<Time>) {
+    clock.frame = time.delta();
+}
+
+// The pause menu, ability selection and game over screen all stop the clock
+pub fn pause_game_clock(mut clock: ResMut<GameClock>) {
+    clock.set_layer(TimeLayer::Pause, 0.);
+}
+
+pub fn resume_game_clock(mut clock: ResMut<GameClock>) {
+    clock.clear_layer(TimeLayer::Pause);
+}
+
+pub fn reset_game_clock(mut clock: ResMut<GameClock>) {
+    clock.reset();
+}
+
+// For systems driven by input or collisions rather than time
+pub fn clock_is_running(clock: Res<GameClock>) -> bool {
+    clock.is_running()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn clock_with_frame(seconds: f32) -> GameClock {
+        GameClock {
+            frame: Duration::from_secs_f32(seconds),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn runs_at_full_speed_without_layers() {
+        let clock = clock_with_frame(0.1);
+
+        assert_eq!(clock.scale(), 1.);
+        assert!((clock.delta_seconds() - 0.1).abs() < 1e-6);
+    }
+
+    #[test]
+    fn layers_multiply() {
+        let mut clock = clock_with_frame(1.);
+        clock.set_layer(TimeLayer::SlowMotion, 0.5);
+        clock.set_layer(TimeLayer::HitStop, 0.1);
+        clock.set_layer(TimeLayer::FastForward, 4.);
+
+        assert!((clock.scale() - 0.2).abs() < 1e-6);
+        assert!((clock.delta_seconds() - 0.2).abs() < 1e-6);
+        assert_eq!(clock.real_delta_seconds(), 1.);
+    }
+
+    #[test]
+    fn setting_a_layer_again_replaces_it() {
+        let mut clock = clock_with_frame(1.);
+        clock.set_layer(TimeLayer::SlowMotion, 0.5);
+        clock.set_layer(TimeLayer::SlowMotion, 0.25);
+
+        assert_eq!(clock.scale(), 0.25);
+
+        clock.clear_layer(TimeLayer::SlowMotion);
+        assert_eq!(clock.scale(), 1.);
+    }
+
+    #[test]
+    fn pausing_stops_real_time_too() {
+        let mut clock = clock_with_frame(1.);
+        clock.set_layer(TimeLayer::FastForward, 4.);
+        clock.set_layer(TimeLayer::Pause, 0.);
+
+        assert!(!clock.is_running());
+        assert_eq!(clock.delta(), Duration::ZERO);
+        assert_eq!(clock.real_delta(), Duration::ZERO);
+    }
+
+    #[test]
+    fn negative_scales_are_clamped() {
+        let mut clock = clock_with_frame(1.);
+        clock.set_layer(TimeLayer::SlowMotion, -1.);
+
+        assert_eq!(clock.scale(), 0.);
+    }
+
+    #[test]
+    fn reset_only_keeps_fast_forward() {
+        let mut clock = clock_with_frame(1.);
+        clock.set_layer(TimeLayer::Pause, 0.);
+        clock.set_layer(TimeLayer::HitStop, 0.05);
+        clock.set_layer(TimeLayer::FastForward, 4.);
+        clock.reset();
+
+        assert!(clock.has_layer(TimeLayer::FastForward));
+        assert!(!clock.has_layer(TimeLayer::Pause));
+        assert!(!clock.has_layer(TimeLayer::HitStop));
+        assert_eq!(clock.scale(), 4.);
+    }
+}
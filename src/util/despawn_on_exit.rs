@@ -0,0 +1,41 @@
+use bevy::prelude::*;
+
+use crate::InGame;
+
+// Despawned along with its children once the state it was spawned in is left
+#[derive(Component)]
+pub struct DespawnOnExit<S: States>(pub S);
+
+impl DespawnOnExit<InGame> {
+    // Things that only last a run, leaving the game over screen is what starts the next one
+    pub fn run() -> DespawnOnExit<InGame> {
+        DespawnOnExit(InGame::GameOver)
+    }
+}
+
+pub trait AppDespawnOnExit {
+    fn despawn_on_exit<S: States>(&mut self, state: S) -> &mut Self;
+}
+
+impl AppDespawnOnExit for App {
+    fn despawn_on_exit<S: States>(&mut self, state: S) -> &mut Self {
+        let exited = state.clone();
+
+        self.add_systems(
+            OnExit(state),
+            (move || exited.clone()).pipe(despawn_scoped::<S>),
+        )
+    }
+}
+
+fn despawn_scoped<S: States>(
+    In(exited): In<S>,
+    q_scoped: Query<(Entity, &DespawnOnExit<S>)>,
+    mut commands: Commands,
+) {
+    for (entity, scope) in q_scoped.iter() {
+        if scope.0 == exited {
+            commands.entity(entity).despawn_recursive();
+        }
+    }
+}